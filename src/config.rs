@@ -16,6 +16,7 @@ pub struct Config {
     pub truncate: bool,
     pub transform: TransformConfig,
     pub source_type: Option<String>,
+    pub target_type: Option<String>,
     pub source_secret_id: Option<String>,
     pub dest_secret_id: Option<String>,
     pub source_options: HashMap<String, String>,
@@ -37,6 +38,7 @@ impl Default for Config {
             truncate: false,
             transform: TransformConfig::None,
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -0,0 +1,618 @@
+use arrow::array::{ArrayRef, AsArray};
+use arrow::compute::{can_cast_types, cast};
+use arrow::datatypes::{DataType, SchemaRef, TimeUnit, UInt64Type};
+use arrow::error::ArrowError;
+use arrow::ipc::reader::{FileReader, StreamReader};
+use arrow::ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+use arrow::ipc::CompressionType;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    connectors::{
+        parquet::{ParquetSource, ParquetTarget},
+        Source, Target,
+    },
+    schema::{Column, Row, Schema},
+    Result, TinyEtlError,
+};
+
+/// Extensions of Arrow IPC files. Feather v2 is the IPC file format.
+const EXTENSIONS: [&str; 3] = [".arrow", ".feather", ".ipc"];
+
+/// Files in the IPC file format start with this; streams start with a message
+const FILE_MAGIC: &[u8; 6] = b"ARROW1";
+
+pub fn is_arrow_file(path: &str) -> bool {
+    let path = path.to_lowercase();
+    EXTENSIONS.iter().any(|extension| path.ends_with(extension))
+}
+
+/// The two layouts of Arrow IPC data: the file format, with a footer that
+/// allows random access, and the stream format, a plain sequence of messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcFormat {
+    File,
+    Stream,
+}
+
+enum IpcReader {
+    File(FileReader<BufReader<File>>),
+    Stream(StreamReader<BufReader<File>>),
+}
+
+impl IpcReader {
+    fn next_batch(&mut self) -> Option<std::result::Result<RecordBatch, ArrowError>> {
+        match self {
+            IpcReader::File(reader) => reader.next(),
+            IpcReader::Stream(reader) => reader.next(),
+        }
+    }
+}
+
+/// Reads Arrow IPC files and streams (`.arrow`, `.feather`, `.ipc`). The
+/// format is detected from the file, and compressed buffers are decompressed.
+pub struct ArrowSource {
+    file_path: PathBuf,
+    format: Option<IpcFormat>,
+    /// Behind a mutex only so the source is `Sync`; reached through `get_mut`
+    reader: Option<Mutex<IpcReader>>,
+    arrow_schema: Option<SchemaRef>,
+    /// Rows of the last record batch not yet returned
+    pending: VecDeque<Row>,
+    has_more_data: bool,
+}
+
+impl ArrowSource {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            format: None,
+            reader: None,
+            arrow_schema: None,
+            pending: VecDeque::new(),
+            has_more_data: false,
+        })
+    }
+
+    /// The format of the file, once connected
+    pub fn format(&self) -> Option<IpcFormat> {
+        self.format
+    }
+
+    fn open_reader(&mut self) -> Result<()> {
+        let mut magic = [0u8; 6];
+        let mut file = File::open(&self.file_path).map_err(TinyEtlError::Io)?;
+        let is_file_format = file.read_exact(&mut magic).is_ok() && &magic == FILE_MAGIC;
+        let file = BufReader::new(File::open(&self.file_path).map_err(TinyEtlError::Io)?);
+
+        let invalid = |e: ArrowError| {
+            TinyEtlError::Connection(format!(
+                "Failed to read Arrow IPC file {}: {}",
+                self.file_path.display(),
+                e
+            ))
+        };
+        let (reader, format) = if is_file_format {
+            let reader = FileReader::try_new(file, None).map_err(invalid)?;
+            (IpcReader::File(reader), IpcFormat::File)
+        } else {
+            let reader = StreamReader::try_new(file, None).map_err(invalid)?;
+            (IpcReader::Stream(reader), IpcFormat::Stream)
+        };
+        self.arrow_schema = Some(match &reader {
+            IpcReader::File(reader) => reader.schema(),
+            IpcReader::Stream(reader) => reader.schema(),
+        });
+        self.format = Some(format);
+        self.reader = Some(Mutex::new(reader));
+        self.pending.clear();
+        self.has_more_data = true;
+        Ok(())
+    }
+}
+
+/// The cast to Int64 would null unsigned values past `i64::MAX`, so refuse them
+fn check_signed_range(name: &str, column: &ArrayRef) -> Result<()> {
+    let values = column.as_primitive::<UInt64Type>();
+    match values.iter().flatten().find(|v| *v > i64::MAX as u64) {
+        Some(value) => Err(TinyEtlError::DataTransfer(format!(
+            "Arrow column '{}' has the value {}, beyond the largest 64-bit signed integer",
+            name, value
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Cast the columns of a batch to the types the row conversion reads:
+/// Int64, Float64, decimals, Utf8, Boolean and nanosecond timestamps. Files written by
+/// other tools use narrower integers, large or view strings, dictionaries,
+/// dates and other timestamp units.
+fn normalize_batch(batch: &RecordBatch) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let target = match field.data_type() {
+            DataType::UInt64 => {
+                check_signed_range(field.name(), column)?;
+                Some(DataType::Int64)
+            }
+            DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32 => Some(DataType::Int64),
+            DataType::Float16 | DataType::Float32 => Some(DataType::Float64),
+            // Read exactly, without going through a float
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => None,
+            DataType::Date32 | DataType::Date64 => {
+                Some(DataType::Timestamp(TimeUnit::Nanosecond, None))
+            }
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => None,
+            DataType::Timestamp(_, tz) => {
+                Some(DataType::Timestamp(TimeUnit::Nanosecond, tz.clone()))
+            }
+            DataType::Int64 | DataType::Float64 | DataType::Utf8 | DataType::Boolean => None,
+            other if can_cast_types(other, &DataType::Utf8) => Some(DataType::Utf8),
+            _ => None,
+        };
+
+        match target {
+            Some(target) => {
+                let column = cast(column, &target).map_err(|e| {
+                    TinyEtlError::DataTransfer(format!(
+                        "Failed to convert Arrow column '{}': {}",
+                        field.name(),
+                        e
+                    ))
+                })?;
+                fields.push(field.as_ref().clone().with_data_type(target));
+                columns.push(column);
+            }
+            None => {
+                fields.push(field.as_ref().clone());
+                columns.push(column.clone());
+            }
+        }
+    }
+
+    let schema = arrow::datatypes::Schema::new(fields).with_metadata(schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to convert Arrow batch: {}", e)))
+}
+
+#[async_trait]
+impl Source for ArrowSource {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "Arrow file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        self.open_reader()
+    }
+
+    async fn infer_schema(&mut self, _sample_size: usize) -> Result<Schema> {
+        let arrow_schema = self
+            .arrow_schema
+            .as_ref()
+            .ok_or_else(|| TinyEtlError::Connection("Arrow file not opened".to_string()))?;
+
+        Ok(Schema {
+            columns: arrow_schema
+                .fields()
+                .iter()
+                .map(|field| Column::from_arrow_field(field))
+                .collect(),
+            estimated_rows: None,
+            primary_key_candidate: None,
+        })
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        while self.pending.len() < batch_size && self.has_more_data {
+            let reader = self
+                .reader
+                .as_mut()
+                .ok_or_else(|| TinyEtlError::Connection("Arrow file not opened".to_string()))?
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner());
+            let batch = match reader.next_batch() {
+                Some(batch) => batch.map_err(|e| {
+                    TinyEtlError::DataTransfer(format!("Failed to read Arrow batch: {}", e))
+                })?,
+                None => {
+                    self.has_more_data = false;
+                    break;
+                }
+            };
+
+            let batch = normalize_batch(&batch)?;
+            self.pending
+                .extend(ParquetSource::record_batch_to_rows(&batch)?);
+        }
+
+        let count = batch_size.min(self.pending.len());
+        Ok(self.pending.drain(..count).collect())
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        // Neither format records the row count outside the batches
+        Ok(None)
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.open_reader()
+    }
+
+    fn has_more(&self) -> bool {
+        !self.pending.is_empty() || self.has_more_data
+    }
+}
+
+enum IpcWriter {
+    File(FileWriter<BufWriter<File>>),
+    Stream(StreamWriter<BufWriter<File>>),
+}
+
+/// Writes Arrow IPC files, one record batch per batch of rows, in the file
+/// format by default or the stream format, optionally LZ4 or ZSTD compressed
+pub struct ArrowTarget {
+    file_path: PathBuf,
+    format: IpcFormat,
+    write_options: IpcWriteOptions,
+    schema: Option<SchemaRef>,
+    /// Behind a mutex only so the target is `Sync`; reached through `get_mut`
+    writer: Option<Mutex<IpcWriter>>,
+}
+
+impl ArrowTarget {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `format` is `file` (the default, also read as Feather v2) or `stream`.
+    /// `compression` is `none` (the default), `lz4` or `zstd`.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let format = match options.get("format").map(|f| f.trim().to_lowercase()) {
+            None => IpcFormat::File,
+            Some(format) => match format.as_str() {
+                "file" | "feather" => IpcFormat::File,
+                "stream" => IpcFormat::Stream,
+                _ => return Err(Self::invalid("format", &format, "expected file or stream")),
+            },
+        };
+        let compression = match options.get("compression").map(|c| c.trim().to_lowercase()) {
+            None => None,
+            Some(codec) => match codec.as_str() {
+                "none" | "uncompressed" | "" => None,
+                "lz4" => Some(CompressionType::LZ4_FRAME),
+                "zstd" => Some(CompressionType::ZSTD),
+                _ => {
+                    return Err(Self::invalid(
+                        "compression",
+                        &codec,
+                        "expected lz4, zstd or none",
+                    ))
+                }
+            },
+        };
+        let write_options = IpcWriteOptions::default()
+            .try_with_compression(compression)
+            .map_err(|e| {
+                TinyEtlError::Configuration(format!("Invalid Arrow compression: {}", e))
+            })?;
+
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            format,
+            write_options,
+            schema: None,
+            writer: None,
+        })
+    }
+
+    fn invalid(key: &str, value: &str, expected: &str) -> TinyEtlError {
+        TinyEtlError::Configuration(format!(
+            "Invalid value '{}' for Arrow option '{}': {}",
+            value, key, expected
+        ))
+    }
+}
+
+#[async_trait]
+impl Target for ArrowTarget {
+    async fn connect(&mut self) -> Result<()> {
+        // Nothing to do here - the file is created with the table
+        Ok(())
+    }
+
+    async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
+        let arrow_schema = Arc::new(schema.to_arrow_schema());
+
+        if let Some(parent) = self.file_path.parent() {
+            std::fs::create_dir_all(parent).map_err(TinyEtlError::Io)?;
+        }
+
+        let file = BufWriter::new(File::create(&self.file_path).map_err(TinyEtlError::Io)?);
+        let options = self.write_options.clone();
+        let writer = match self.format {
+            IpcFormat::File => {
+                FileWriter::try_new_with_options(file, &arrow_schema, options).map(IpcWriter::File)
+            }
+            IpcFormat::Stream => StreamWriter::try_new_with_options(file, &arrow_schema, options)
+                .map(IpcWriter::Stream),
+        }
+        .map_err(|e| TinyEtlError::Connection(format!("Failed to create Arrow writer: {}", e)))?;
+        self.writer = Some(Mutex::new(writer));
+        self.schema = Some(arrow_schema);
+
+        Ok(())
+    }
+
+    async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let schema = self
+            .schema
+            .as_ref()
+            .ok_or_else(|| TinyEtlError::Configuration("Schema not set".to_string()))?;
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Writer not connected".to_string()))?
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+
+        let batch = ParquetTarget::rows_to_record_batch(rows, schema)?;
+        match writer {
+            IpcWriter::File(writer) => writer.write(&batch),
+            IpcWriter::Stream(writer) => writer.write(&batch),
+        }
+        .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to write Arrow batch: {}", e)))?;
+
+        Ok(rows.len())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            // Writes the footer of the file format or the end marker of a stream
+            match writer.into_inner().unwrap_or_else(|e| e.into_inner()) {
+                IpcWriter::File(mut writer) => writer.finish(),
+                IpcWriter::Stream(mut writer) => writer.finish(),
+            }
+            .map_err(|e| {
+                TinyEtlError::Connection(format!("Failed to close Arrow writer: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, _table_name: &str) -> Result<bool> {
+        Ok(self.file_path.exists())
+    }
+
+    async fn truncate(&mut self, _table_name: &str) -> Result<()> {
+        // The file is recreated in create_table
+        self.writer = None;
+        Ok(())
+    }
+
+    fn supports_append(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{DataType as SchemaType, Value};
+    use arrow::array::{
+        Date32Array, Decimal128Array, Float32Array, Int16Array, LargeStringArray, UInt64Array,
+    };
+    use arrow::datatypes::Field;
+    use tempfile::tempdir;
+
+    fn schema() -> Schema {
+        let column = |name: &str, data_type| Column {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+        };
+        Schema {
+            columns: vec![
+                column("id", SchemaType::Integer),
+                column("name", SchemaType::String),
+                column("active", SchemaType::Boolean),
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        }
+    }
+
+    fn rows(ids: std::ops::Range<i64>) -> Vec<Row> {
+        ids.map(|id| {
+            HashMap::from([
+                ("id".to_string(), Value::Integer(id)),
+                (
+                    "name".to_string(),
+                    if id % 3 == 0 {
+                        Value::Null
+                    } else {
+                        Value::String(format!("user {}", id))
+                    },
+                ),
+                ("active".to_string(), Value::Boolean(id % 2 == 0)),
+            ])
+        })
+        .collect()
+    }
+
+    async fn read_all(path: &str) -> (ArrowSource, Vec<Row>) {
+        let mut source = ArrowSource::new(path).unwrap();
+        source.connect().await.unwrap();
+        let mut rows = Vec::new();
+        while source.has_more() {
+            rows.extend(source.read_batch(4).await.unwrap());
+        }
+        (source, rows)
+    }
+
+    #[tokio::test]
+    async fn test_arrow_round_trip_formats_and_compression() {
+        let dir = tempdir().unwrap();
+        let cases = [
+            ("plain.arrow", "file", "none"),
+            ("lz4.feather", "file", "lz4"),
+            ("zstd.ipc", "stream", "zstd"),
+        ];
+
+        for (name, format, compression) in cases {
+            let path = dir.path().join(name);
+            let path = path.to_str().unwrap();
+            let options = HashMap::from([
+                ("format".to_string(), format.to_string()),
+                ("compression".to_string(), compression.to_string()),
+            ]);
+            let mut target = ArrowTarget::with_options(path, &options).unwrap();
+            target.connect().await.unwrap();
+            target.create_table("users", &schema()).await.unwrap();
+            assert_eq!(target.write_batch(&rows(0..6)).await.unwrap(), 6);
+            target.write_batch(&rows(6..10)).await.unwrap();
+            target.finalize().await.unwrap();
+
+            let (mut source, read) = read_all(path).await;
+            let expected = if format == "file" {
+                IpcFormat::File
+            } else {
+                IpcFormat::Stream
+            };
+            assert_eq!(source.format(), Some(expected), "{}", name);
+            assert_eq!(read, rows(0..10), "{}", name);
+
+            let names: Vec<String> = source
+                .infer_schema(10)
+                .await
+                .unwrap()
+                .columns
+                .into_iter()
+                .map(|c| c.name)
+                .collect();
+            assert_eq!(names, ["id", "name", "active"]);
+            source.reset().await.unwrap();
+            assert_eq!(source.read_batch(1).await.unwrap(), rows(0..1));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_arrow_source_converts_foreign_types() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("polars.arrow");
+        let arrow_schema = Arc::new(arrow::datatypes::Schema::new(vec![
+            Field::new("small", DataType::Int16, false),
+            Field::new("ratio", DataType::Float32, true),
+            Field::new("label", DataType::LargeUtf8, true),
+            Field::new("day", DataType::Date32, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            arrow_schema.clone(),
+            vec![
+                Arc::new(Int16Array::from(vec![7, -2])),
+                Arc::new(Float32Array::from(vec![Some(0.5), None])),
+                Arc::new(LargeStringArray::from(vec![Some("a"), Some("b")])),
+                Arc::new(Date32Array::from(vec![Some(19_723), None])),
+            ],
+        )
+        .unwrap();
+        let mut writer = FileWriter::try_new(File::create(&path).unwrap(), &arrow_schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+
+        let (mut source, rows) = read_all(path.to_str().unwrap()).await;
+        let types: Vec<SchemaType> = source
+            .infer_schema(10)
+            .await
+            .unwrap()
+            .columns
+            .into_iter()
+            .map(|c| c.data_type)
+            .collect();
+        assert_eq!(
+            types,
+            [
+                SchemaType::Integer,
+                SchemaType::Decimal,
+                SchemaType::String,
+                SchemaType::Date
+            ]
+        );
+        assert_eq!(rows[0]["small"], Value::Integer(7));
+        assert_eq!(rows[0]["ratio"], Value::Decimal("0.5".parse().unwrap()));
+        assert_eq!(rows[1]["label"], Value::String("b".to_string()));
+        match &rows[0]["day"] {
+            Value::Date(date) => assert_eq!(date.format("%Y-%m-%d").to_string(), "2024-01-01"),
+            other => panic!("expected a date, got {:?}", other),
+        }
+        assert_eq!(rows[1]["day"], Value::Null);
+    }
+
+    fn write_arrow(path: &std::path::Path, column: ArrayRef) {
+        let field = Field::new("value", column.data_type().clone(), true);
+        let arrow_schema = Arc::new(arrow::datatypes::Schema::new(vec![field]));
+        let batch = RecordBatch::try_new(arrow_schema.clone(), vec![column]).unwrap();
+        let mut writer = FileWriter::try_new(File::create(path).unwrap(), &arrow_schema).unwrap();
+        writer.write(&batch).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_arrow_source_exact_decimals_and_unsigned_range() {
+        let dir = tempdir().unwrap();
+
+        // More digits than a float holds
+        let path = dir.path().join("money.arrow");
+        let decimals = Decimal128Array::from(vec![Some(1_234_567_890_123_456_789), None])
+            .with_precision_and_scale(20, 2)
+            .unwrap();
+        write_arrow(&path, Arc::new(decimals));
+        let (_, rows) = read_all(path.to_str().unwrap()).await;
+        assert_eq!(
+            rows[0]["value"],
+            Value::Decimal("12345678901234567.89".parse().unwrap())
+        );
+        assert_eq!(rows[1]["value"], Value::Null);
+
+        let path = dir.path().join("counts.arrow");
+        write_arrow(&path, Arc::new(UInt64Array::from(vec![1, u64::MAX])));
+        let mut source = ArrowSource::new(path.to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let err = source.read_batch(10).await.unwrap_err();
+        assert!(err.to_string().contains("18446744073709551615"), "{}", err);
+    }
+
+    #[test]
+    fn test_arrow_target_options() {
+        assert!(is_arrow_file("data/Out.FEATHER"));
+        assert!(!is_arrow_file("out.parquet"));
+
+        let invalid = |key: &str, value: &str| {
+            let options = HashMap::from([(key.to_string(), value.to_string())]);
+            ArrowTarget::with_options("out.arrow", &options).is_err()
+        };
+        assert!(invalid("format", "table"));
+        assert!(invalid("compression", "snappy"));
+        assert!(!invalid("compression", "ZSTD"));
+    }
+}
@@ -1,35 +1,94 @@
-use apache_avro::{types::Value as AvroValue, Reader, Schema as AvroSchema, Writer};
+use apache_avro::{
+    to_avro_datum, types::Value as AvroValue, Codec, Reader, Schema as AvroSchema, Writer,
+};
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 use crate::{
-    connectors::{Source, Target},
+    connectors::{compression::Compression, stream, Source, Target},
     schema::{Column, DataType, Row, Schema, Value},
     Result, TinyEtlError,
 };
 
+type AvroReader = Reader<'static, BufReader<Box<dyn Read + Send + Sync>>>;
+
 pub struct AvroSource {
     file_path: PathBuf,
+    /// Schema records are resolved to, from the `reader_schema` option
+    reader_schema: Option<AvroSchema>,
+    /// Behind a mutex only so the source is `Sync`; reached through `get_mut`
+    reader: Option<Mutex<AvroReader>>,
+    /// Scale of each decimal column, which the values don't carry
+    decimal_scales: HashMap<String, u32>,
     current_position: usize,
     total_records: Option<usize>,
     has_more: bool,
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
 }
 
 impl AvroSource {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `reader_schema` is an `.avsc` file or inline schema JSON. Records are
+    /// resolved to it, so fields can be dropped, renamed through aliases, or
+    /// added with their defaults.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let reader_schema = match options.get("reader_schema").map(|s| s.trim()) {
+            None | Some("") => None,
+            Some(schema) => Some(load_schema(schema)?),
+        };
+
         Ok(Self {
             file_path: PathBuf::from(file_path),
+            reader_schema,
+            reader: None,
+            decimal_scales: HashMap::new(),
             current_position: 0,
             total_records: None,
             has_more: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
         })
     }
 
+    /// Open the file and read its header
+    fn open_reader(&self, bytes_read: &Arc<AtomicU64>) -> Result<AvroReader> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "Avro file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        let input = stream::open_file(&self.file_path, Compression::None, bytes_read)?;
+        Reader::new(BufReader::new(input))
+            .map_err(|e| TinyEtlError::DataTransfer(format!("Invalid Avro file: {}", e)))
+    }
+
+    /// Fields of the schema records are read with: the reader schema if one
+    /// was given, otherwise the file's own. Serialized in full rather than in
+    /// canonical form, which drops logical types.
+    fn record_fields(&self, writer_schema: &AvroSchema) -> Result<Vec<JsonValue>> {
+        let schema = self.reader_schema.as_ref().unwrap_or(writer_schema);
+        let schema_json = serde_json::to_value(schema).map_err(|e| {
+            TinyEtlError::DataTransfer(format!("Failed to parse Avro schema: {}", e))
+        })?;
+        match schema_json.get("fields") {
+            Some(JsonValue::Array(fields)) => Ok(fields.clone()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
     fn avro_type_to_schema_type(avro_type: &JsonValue) -> DataType {
         match avro_type {
             JsonValue::String(type_name) => match type_name.as_str() {
@@ -43,32 +102,45 @@ impl AvroSource {
             },
             JsonValue::Array(union_types) => {
                 // Handle union types (e.g., ["null", "string"])
-                for union_type in union_types {
-                    if let JsonValue::String(type_str) = union_type {
-                        if type_str != "null" {
-                            return Self::avro_type_to_schema_type(union_type);
-                        }
-                    }
+                match union_types.iter().find(|t| t.as_str() != Some("null")) {
+                    Some(union_type) => Self::avro_type_to_schema_type(union_type),
+                    None => DataType::String, // Default
                 }
-                DataType::String // Default
             }
             JsonValue::Object(obj) => {
-                if let Some(logical_type) = obj.get("logicalType") {
-                    match logical_type.as_str().unwrap_or("") {
-                        "date" => DataType::Date,
-                        "timestamp-millis" | "timestamp-micros" => DataType::DateTime,
-                        _ => DataType::String,
-                    }
-                } else if let Some(type_val) = obj.get("type") {
-                    Self::avro_type_to_schema_type(type_val)
-                } else {
-                    DataType::String
+                match obj.get("logicalType").and_then(|t| t.as_str()) {
+                    Some("decimal") => DataType::Decimal,
+                    Some("date") => DataType::Date,
+                    Some(
+                        "timestamp-millis"
+                        | "timestamp-micros"
+                        | "local-timestamp-millis"
+                        | "local-timestamp-micros",
+                    ) => DataType::DateTime,
+                    // Times of day and uuids are read as text
+                    Some("time-millis" | "time-micros" | "uuid") => DataType::String,
+                    // Unknown logical types are read as their underlying type
+                    _ => match obj.get("type") {
+                        Some(type_val) => Self::avro_type_to_schema_type(type_val),
+                        None => DataType::String,
+                    },
                 }
             }
             _ => DataType::String,
         }
     }
 
+    /// Scale of a decimal field type, looking through nullable unions
+    fn decimal_scale(avro_type: &JsonValue) -> Option<u32> {
+        match avro_type {
+            JsonValue::Array(union_types) => union_types.iter().find_map(Self::decimal_scale),
+            JsonValue::Object(obj) if obj.get("logicalType")?.as_str()? == "decimal" => {
+                Some(obj.get("scale").and_then(|s| s.as_u64()).unwrap_or(0) as u32)
+            }
+            _ => None,
+        }
+    }
+
     fn is_nullable(avro_type: &JsonValue) -> bool {
         match avro_type {
             JsonValue::Array(union_types) => union_types.iter().any(|t| {
@@ -155,52 +227,21 @@ impl AvroSource {
                     .and_utc();
                 Ok(Value::Date(datetime))
             }
-            AvroValue::TimeMillis(millis) => {
-                // Time in milliseconds since midnight
-                Ok(Value::String(format!("{}ms", millis)))
-            }
-            AvroValue::TimeMicros(micros) => {
-                // Time in microseconds since midnight
-                Ok(Value::String(format!("{}μs", micros)))
-            }
-            AvroValue::TimestampMillis(millis) => {
-                // Timestamp in milliseconds since epoch
-                let datetime = chrono::DateTime::from_timestamp(
-                    *millis / 1000,
-                    ((*millis % 1000) * 1_000_000) as u32,
-                )
-                .ok_or_else(|| TinyEtlError::DataTransfer("Invalid timestamp".to_string()))?;
-                Ok(Value::Date(datetime))
-            }
-            AvroValue::TimestampMicros(micros) => {
-                // Timestamp in microseconds since epoch
-                let datetime = chrono::DateTime::from_timestamp(
-                    *micros / 1_000_000,
-                    ((*micros % 1_000_000) * 1000) as u32,
-                )
-                .ok_or_else(|| TinyEtlError::DataTransfer("Invalid timestamp".to_string()))?;
-                Ok(Value::Date(datetime))
+            AvroValue::TimeMillis(millis) => Ok(Self::time_of_day(*millis as i64 * 1000)),
+            AvroValue::TimeMicros(micros) => Ok(Self::time_of_day(*micros)),
+            // Local timestamps have no zone and are read as UTC
+            AvroValue::TimestampMillis(millis) | AvroValue::LocalTimestampMillis(millis) => {
+                chrono::DateTime::from_timestamp_millis(*millis)
+                    .map(Value::Date)
+                    .ok_or_else(|| TinyEtlError::DataTransfer("Invalid timestamp".to_string()))
             }
-            AvroValue::LocalTimestampMillis(millis) => {
-                // Local timestamp in milliseconds since epoch
-                let datetime = chrono::DateTime::from_timestamp(
-                    *millis / 1000,
-                    ((*millis % 1000) * 1_000_000) as u32,
-                )
-                .ok_or_else(|| TinyEtlError::DataTransfer("Invalid local timestamp".to_string()))?;
-                Ok(Value::Date(datetime))
-            }
-            AvroValue::LocalTimestampMicros(micros) => {
-                // Local timestamp in microseconds since epoch
-                let datetime = chrono::DateTime::from_timestamp(
-                    *micros / 1_000_000,
-                    ((*micros % 1_000_000) * 1000) as u32,
-                )
-                .ok_or_else(|| TinyEtlError::DataTransfer("Invalid local timestamp".to_string()))?;
-                Ok(Value::Date(datetime))
+            AvroValue::TimestampMicros(micros) | AvroValue::LocalTimestampMicros(micros) => {
+                chrono::DateTime::from_timestamp_micros(*micros)
+                    .map(Value::Date)
+                    .ok_or_else(|| TinyEtlError::DataTransfer("Invalid timestamp".to_string()))
             }
             AvroValue::Decimal(decimal) => {
-                // Convert decimal to string representation
+                // Without the scale from the schema, keep the raw representation
                 Ok(Value::String(format!("{:?}", decimal)))
             }
             AvroValue::Uuid(uuid) => Ok(Value::String(uuid.to_string())),
@@ -211,6 +252,62 @@ impl AvroSource {
         }
     }
 
+    /// Convert a record field. Decimals need the scale from the field's schema.
+    fn field_value_to_value(avro_value: &AvroValue, decimal_scale: Option<u32>) -> Result<Value> {
+        match (avro_value, decimal_scale) {
+            (AvroValue::Union(_, boxed_value), _) => {
+                Self::field_value_to_value(boxed_value, decimal_scale)
+            }
+            (AvroValue::Decimal(decimal), Some(scale)) => {
+                Ok(Self::decimal_to_value(decimal, scale))
+            }
+            _ => Self::avro_value_to_value(avro_value),
+        }
+    }
+
+    /// Decimals are stored as a big-endian two's complement unscaled integer
+    fn decimal_to_value(decimal: &apache_avro::Decimal, scale: u32) -> Value {
+        let fallback = || Value::String(format!("{:?}", decimal));
+        let Ok(bytes) = Vec::<u8>::try_from(decimal) else {
+            return fallback();
+        };
+
+        // Drop sign extension beyond what an i128 holds
+        let fill = if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+            0xff
+        } else {
+            0
+        };
+        let mut bytes = bytes.as_slice();
+        while bytes.len() > 16 && bytes[0] == fill && (bytes[1] & 0x80) == (fill & 0x80) {
+            bytes = &bytes[1..];
+        }
+        if bytes.len() > 16 {
+            return fallback();
+        }
+
+        let mut unscaled = [fill; 16];
+        unscaled[16 - bytes.len()..].copy_from_slice(bytes);
+        match Decimal::try_from_i128_with_scale(i128::from_be_bytes(unscaled), scale) {
+            Ok(d) => Value::Decimal(d),
+            Err(_) => fallback(),
+        }
+    }
+
+    /// Times of day as `HH:MM:SS` text, with fractional seconds when set
+    fn time_of_day(micros: i64) -> Value {
+        let time = u32::try_from(micros.div_euclid(1_000_000))
+            .ok()
+            .and_then(|secs| {
+                let nanos = micros.rem_euclid(1_000_000) as u32 * 1000;
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)
+            });
+        match time {
+            Some(time) => Value::String(time.format("%H:%M:%S%.f").to_string()),
+            None => Value::String(micros.to_string()),
+        }
+    }
+
     fn avro_value_to_json_value(avro_value: &AvroValue) -> Result<JsonValue> {
         match avro_value {
             AvroValue::Null => Ok(JsonValue::Null),
@@ -233,20 +330,18 @@ impl AvroSource {
 #[async_trait]
 impl Source for AvroSource {
     async fn connect(&mut self) -> Result<()> {
-        if !self.file_path.exists() {
-            return Err(TinyEtlError::Connection(format!(
-                "Avro file not found: {}",
-                self.file_path.display()
-            )));
-        }
-
-        // Just verify the file can be opened and is a valid Avro file
-        let file = File::open(&self.file_path)?;
-        let buf_reader = BufReader::new(file);
-
-        let _reader = Reader::new(buf_reader)
-            .map_err(|e| TinyEtlError::DataTransfer(format!("Invalid Avro file: {}", e)))?;
-
+        let reader = self.open_reader(&self.bytes_read)?;
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.decimal_scales = self
+            .record_fields(reader.writer_schema())?
+            .iter()
+            .filter_map(|field| {
+                let name = field.get("name")?.as_str()?;
+                Some((name.to_string(), Self::decimal_scale(field.get("type")?)?))
+            })
+            .collect();
+
+        self.reader = Some(Mutex::new(reader));
         self.current_position = 0;
         self.has_more = true;
 
@@ -254,53 +349,28 @@ impl Source for AvroSource {
     }
 
     async fn infer_schema(&mut self, _sample_size: usize) -> Result<Schema> {
-        if !self.file_path.exists() {
-            return Err(TinyEtlError::Connection(format!(
-                "Avro file not found: {}",
-                self.file_path.display()
-            )));
-        }
-
-        // Create a new reader for schema inference
-        let file = File::open(&self.file_path)?;
-        let buf_reader = BufReader::new(file);
-
-        let reader = Reader::new(buf_reader).map_err(|e| {
-            TinyEtlError::DataTransfer(format!("Failed to create Avro reader: {}", e))
-        })?;
-
-        let avro_schema = reader.writer_schema();
-
-        // Parse the Avro schema to extract field information
-        let schema_json: JsonValue =
-            serde_json::from_str(&avro_schema.canonical_form()).map_err(|e| {
-                TinyEtlError::DataTransfer(format!("Failed to parse Avro schema: {}", e))
-            })?;
+        // Only the header is read, for the writer schema
+        let reader = self.open_reader(&Arc::new(AtomicU64::new(0)))?;
 
         let mut columns = Vec::new();
-
-        if let JsonValue::Object(obj) = &schema_json {
-            if let Some(JsonValue::Array(fields)) = obj.get("fields") {
-                for field in fields {
-                    if let JsonValue::Object(field_obj) = field {
-                        let name = field_obj
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("unknown")
-                            .to_string();
-
-                        let default_type = JsonValue::String("string".to_string());
-                        let field_type = field_obj.get("type").unwrap_or(&default_type);
-                        let data_type = Self::avro_type_to_schema_type(field_type);
-                        let nullable = Self::is_nullable(field_type);
-
-                        columns.push(Column {
-                            name,
-                            data_type,
-                            nullable,
-                        });
-                    }
-                }
+        for field in self.record_fields(reader.writer_schema())? {
+            if let JsonValue::Object(field_obj) = &field {
+                let name = field_obj
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+
+                let default_type = JsonValue::String("string".to_string());
+                let field_type = field_obj.get("type").unwrap_or(&default_type);
+                let data_type = Self::avro_type_to_schema_type(field_type);
+                let nullable = Self::is_nullable(field_type);
+
+                columns.push(Column {
+                    name,
+                    data_type,
+                    nullable,
+                });
             }
         }
 
@@ -316,62 +386,46 @@ impl Source for AvroSource {
             return Ok(vec![]);
         }
 
-        // Create a new reader each time (not ideal for performance, but works around lifetime issues)
-        let file = File::open(&self.file_path)?;
-        let buf_reader = BufReader::new(file);
-
-        let reader = Reader::new(buf_reader).map_err(|e| {
-            TinyEtlError::DataTransfer(format!("Failed to create Avro reader: {}", e))
-        })?;
+        let reader = self
+            .reader
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Avro file not opened".to_string()))?
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
 
         let mut rows = Vec::new();
-        let mut count = 0;
-        let mut current_index = 0;
-        let mut reached_end = false;
-
-        for value_result in reader {
-            // Skip records we've already read
-            if current_index < self.current_position {
-                current_index += 1;
-                continue;
-            }
-
-            if count >= batch_size {
-                break;
-            }
-
-            match value_result {
-                Ok(avro_value) => {
-                    if let AvroValue::Record(fields) = avro_value {
-                        let mut row = HashMap::new();
-                        for (field_name, field_value) in fields {
-                            let converted_value = Self::avro_value_to_value(&field_value)?;
-                            row.insert(field_name, converted_value);
-                        }
-                        rows.push(row);
-                        count += 1;
-                        self.current_position += 1;
-                        current_index += 1;
-                    }
+        while rows.len() < batch_size {
+            let record = match reader.next() {
+                Some(record) => record.map_err(|e| {
+                    TinyEtlError::DataTransfer(format!("Failed to read Avro record: {}", e))
+                })?,
+                None => {
+                    self.has_more = false;
+                    break;
                 }
-                Err(e) => {
-                    return Err(TinyEtlError::DataTransfer(format!(
-                        "Failed to read Avro record: {}",
+            };
+            let record = match &self.reader_schema {
+                Some(schema) => record.resolve(schema).map_err(|e| {
+                    TinyEtlError::DataTransfer(format!(
+                        "Avro record does not match the reader schema: {}",
                         e
-                    )));
+                    ))
+                })?,
+                None => record,
+            };
+
+            if let AvroValue::Record(fields) = record {
+                let mut row = HashMap::with_capacity(fields.len());
+                for (field_name, field_value) in fields {
+                    let scale = self.decimal_scales.get(&field_name).copied();
+                    let converted_value = Self::field_value_to_value(&field_value, scale)?;
+                    row.insert(field_name, converted_value);
                 }
+                rows.push(row);
+                self.current_position += 1;
             }
         }
 
-        // Check if we've reached the end by seeing if we can read more
-        if count < batch_size {
-            reached_end = true;
-        }
-
-        if rows.is_empty() || reached_end {
-            self.has_more = false;
-        }
-
         Ok(rows)
     }
 
@@ -388,24 +442,107 @@ impl Source for AvroSource {
     fn has_more(&self) -> bool {
         self.has_more
     }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+/// Parse an Avro schema from the path of an `.avsc` file, or from inline JSON
+fn load_schema(schema: &str) -> Result<AvroSchema> {
+    let text = if schema.starts_with('{') || schema.starts_with('[') {
+        schema.to_string()
+    } else {
+        std::fs::read_to_string(schema).map_err(|e| {
+            TinyEtlError::Configuration(format!("Cannot read Avro schema {}: {}", schema, e))
+        })?
+    };
+    AvroSchema::parse_str(&text)
+        .map_err(|e| TinyEtlError::Configuration(format!("Invalid Avro schema: {}", e)))
 }
 
 pub struct AvroTarget {
     file_path: PathBuf,
+    codec: Codec,
+    record_name: String,
+    namespace: Option<String>,
+    /// Schema records must conform to, from the `writer_schema` option
+    writer_schema: Option<AvroSchema>,
+    /// Schema the file is written with, set by `create_table`
     schema: Option<AvroSchema>,
-    buffer: Vec<Row>,
+    /// Table columns, when the schema is generated from them
+    columns: Vec<Column>,
+    /// Fields of the `writer_schema`, when one is given
+    writer_fields: Vec<WriterField>,
+    writer: Option<BufWriter<File>>,
+    /// Sync marker from the file header, which ends every block
+    marker: [u8; 16],
+}
+
+/// A field of the `writer_schema` and what its values are converted to
+struct WriterField {
+    name: String,
+    /// The logical type, or the Avro type, of the field
+    type_name: String,
+    /// Scale and encoded length of decimal values
+    decimal: Option<(u32, usize)>,
+    has_default: bool,
 }
 
 impl AvroTarget {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `codec` compresses blocks (null, deflate, snappy or zstd).
+    /// `record_name` and `namespace` name the generated record schema, while
+    /// `writer_schema` (an `.avsc` file or inline JSON) replaces it entirely.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let codec = match options.get("codec").map(|s| s.trim().to_lowercase()) {
+            None => Codec::Null,
+            Some(codec) => match codec.as_str() {
+                "" | "null" | "none" => Codec::Null,
+                "deflate" => Codec::Deflate,
+                "snappy" => Codec::Snappy,
+                "zstd" | "zstandard" => Codec::Zstandard,
+                _ => {
+                    return Err(TinyEtlError::Configuration(format!(
+                        "Invalid Avro codec '{}': expected null, deflate, snappy or zstd",
+                        codec
+                    )))
+                }
+            },
+        };
+        let record_name = match options.get("record_name").map(|s| s.trim()) {
+            None | Some("") => "Record".to_string(),
+            Some(name) => name.to_string(),
+        };
+        let namespace = options
+            .get("namespace")
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let writer_schema = match options.get("writer_schema").map(|s| s.trim()) {
+            None | Some("") => None,
+            Some(schema) => Some(load_schema(schema)?),
+        };
+
         Ok(Self {
             file_path: PathBuf::from(file_path),
+            codec,
+            record_name,
+            namespace,
+            writer_schema,
             schema: None,
-            buffer: Vec::new(),
+            columns: Vec::new(),
+            writer_fields: Vec::new(),
+            writer: None,
+            marker: [0; 16],
         })
     }
 
-    fn schema_to_avro_schema(schema: &Schema) -> Result<AvroSchema> {
+    fn schema_to_avro_schema(&self, schema: &Schema) -> Result<AvroSchema> {
         let mut fields = Vec::new();
 
         for column in &schema.columns {
@@ -469,17 +606,99 @@ impl AvroTarget {
             }));
         }
 
-        let avro_schema_json = json!({
+        let mut avro_schema_json = json!({
             "type": "record",
-            "name": "Record",
+            "name": self.record_name,
             "fields": fields
         });
+        if let Some(namespace) = &self.namespace {
+            avro_schema_json["namespace"] = json!(namespace);
+        }
 
         AvroSchema::parse(&avro_schema_json).map_err(|e| {
             TinyEtlError::Configuration(format!("Failed to create Avro schema: {}", e))
         })
     }
 
+    /// Check that the table can fill every field of the writer schema, and
+    /// note how values are converted for each
+    fn writer_fields(writer_schema: &AvroSchema, schema: &Schema) -> Result<Vec<WriterField>> {
+        let schema_json = serde_json::to_value(writer_schema).map_err(|e| {
+            TinyEtlError::Configuration(format!("Failed to parse Avro schema: {}", e))
+        })?;
+        let Some(JsonValue::Array(fields)) = schema_json.get("fields") else {
+            return Err(TinyEtlError::Configuration(
+                "Avro writer schema must be a record".to_string(),
+            ));
+        };
+
+        let null_type = JsonValue::Null;
+        let mut writer_fields = Vec::new();
+        for field in fields {
+            let name = field.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let field_type = field.get("type").unwrap_or(&null_type);
+            let has_default = field.get("default").is_some();
+            let has_column = schema.columns.iter().any(|c| c.name == name);
+            if !has_column && !has_default && !AvroSource::is_nullable(field_type) {
+                return Err(TinyEtlError::Configuration(format!(
+                    "Avro writer schema field '{}' has no matching column and no default",
+                    name
+                )));
+            }
+            writer_fields.push(WriterField {
+                name: name.to_string(),
+                type_name: field_type_name(field_type).to_string(),
+                decimal: decimal_layout(field_type),
+                has_default,
+            });
+        }
+
+        let unused: Vec<&str> = schema
+            .columns
+            .iter()
+            .filter(|c| !writer_fields.iter().any(|f| f.name == c.name))
+            .map(|c| c.name.as_str())
+            .collect();
+        if !unused.is_empty() {
+            tracing::warn!(
+                "Columns not in the Avro writer schema are not written: {}",
+                unused.join(", ")
+            );
+        }
+        Ok(writer_fields)
+    }
+
+    fn record(&self, row: &Row, schema: &AvroSchema) -> Result<AvroValue> {
+        if self.writer_schema.is_none() {
+            let mut fields = Vec::with_capacity(self.columns.len());
+            for column in &self.columns {
+                let value = row.get(&column.name).unwrap_or(&Value::Null);
+                let avro_value =
+                    Self::value_to_avro_value(value, &column.data_type, column.nullable)?;
+                fields.push((column.name.clone(), avro_value));
+            }
+            return Ok(AvroValue::Record(fields));
+        }
+
+        // Missing columns are left out where the field has a default, so that
+        // it applies. Resolving picks union branches, promotes numbers and
+        // checks enum symbols.
+        let mut fields = Vec::with_capacity(self.writer_fields.len());
+        for field in &self.writer_fields {
+            match row.get(&field.name) {
+                Some(value) => fields.push((field.name.clone(), field.avro_value(value)?)),
+                None if !field.has_default => fields.push((field.name.clone(), AvroValue::Null)),
+                None => {}
+            }
+        }
+        AvroValue::Record(fields).resolve(schema).map_err(|e| {
+            TinyEtlError::DataTransfer(format!(
+                "Row does not conform to the Avro writer schema: {}",
+                e
+            ))
+        })
+    }
+
     fn value_to_avro_value(
         value: &Value,
         data_type: &DataType,
@@ -553,6 +772,179 @@ impl AvroTarget {
     }
 }
 
+impl WriterField {
+    /// The value in the Avro form the field's type expects, before resolving
+    fn avro_value(&self, value: &Value) -> Result<AvroValue> {
+        let out_of_range = || {
+            TinyEtlError::DataTransfer(format!(
+                "Value {:?} is out of range for Avro field '{}'",
+                value, self.name
+            ))
+        };
+        let to_f64 = |d: &Decimal| f64::try_from(*d).unwrap_or(0.0);
+
+        Ok(match (value, self.type_name.as_str()) {
+            (Value::Null, _) => AvroValue::Null,
+            (Value::Integer(i), "int") => {
+                AvroValue::Int(i32::try_from(*i).map_err(|_| out_of_range())?)
+            }
+            (Value::Integer(i), "float") => AvroValue::Float(*i as f32),
+            (Value::Integer(i), "string") => AvroValue::String(i.to_string()),
+            (Value::Integer(i), "decimal") => self.decimal_value(Decimal::from(*i))?,
+            (Value::Integer(i), _) => AvroValue::Long(*i),
+            (Value::Decimal(d), "decimal") => self.decimal_value(*d)?,
+            (Value::Decimal(d), "float") => AvroValue::Float(to_f64(d) as f32),
+            (Value::Decimal(d), "string") => AvroValue::String(d.to_string()),
+            (Value::Decimal(d), _) => AvroValue::Double(to_f64(d)),
+            (Value::Boolean(b), "string") => AvroValue::String(b.to_string()),
+            (Value::Boolean(b), _) => AvroValue::Boolean(*b),
+            (Value::Date(dt), "date") => {
+                let days = dt.timestamp().div_euclid(86_400);
+                AvroValue::Date(i32::try_from(days).map_err(|_| out_of_range())?)
+            }
+            (Value::Date(dt), "timestamp-millis") => {
+                AvroValue::TimestampMillis(dt.timestamp_millis())
+            }
+            (Value::Date(dt), "timestamp-micros") => {
+                AvroValue::TimestampMicros(dt.timestamp_micros())
+            }
+            (Value::Date(dt), "local-timestamp-millis") => {
+                AvroValue::LocalTimestampMillis(dt.timestamp_millis())
+            }
+            (Value::Date(dt), "local-timestamp-micros") => {
+                AvroValue::LocalTimestampMicros(dt.timestamp_micros())
+            }
+            (Value::Date(dt), "time-millis") => {
+                AvroValue::TimeMillis((micros_of_day(dt.time()) / 1000) as i32)
+            }
+            (Value::Date(dt), "time-micros") => AvroValue::TimeMicros(micros_of_day(dt.time())),
+            (Value::Date(dt), "long") => AvroValue::Long(dt.timestamp_millis()),
+            (Value::Date(dt), _) => AvroValue::String(dt.to_rfc3339()),
+            (Value::String(s), "time-millis" | "time-micros") => {
+                let time = chrono::NaiveTime::parse_from_str(s, "%H:%M:%S%.f").map_err(|_| {
+                    TinyEtlError::DataTransfer(format!(
+                        "Cannot convert '{}' to a time for Avro field '{}'",
+                        s, self.name
+                    ))
+                })?;
+                if self.type_name == "time-millis" {
+                    AvroValue::TimeMillis((micros_of_day(time) / 1000) as i32)
+                } else {
+                    AvroValue::TimeMicros(micros_of_day(time))
+                }
+            }
+            (Value::String(s), "decimal") => {
+                let d = s.trim().parse::<Decimal>().map_err(|_| {
+                    TinyEtlError::DataTransfer(format!("Cannot convert '{}' to decimal", s))
+                })?;
+                self.decimal_value(d)?
+            }
+            (Value::String(s), _) => AvroValue::String(s.clone()),
+            (Value::Json(j), "string") => AvroValue::String(j.to_string()),
+            (Value::Json(j), _) => AvroValue::from(j.clone()),
+        })
+    }
+
+    /// Decimals are written as a big-endian two's complement unscaled
+    /// integer, sign-extended to the length the type calls for
+    fn decimal_value(&self, decimal: Decimal) -> Result<AvroValue> {
+        let (scale, len) = self.decimal.unwrap_or((decimal.scale(), 16));
+        let mut decimal = decimal;
+        decimal.rescale(scale);
+        let unscaled = decimal.mantissa();
+        let fits = len >= 16 || {
+            let limit = 1i128 << (8 * len - 1);
+            (-limit..limit).contains(&unscaled)
+        };
+        if decimal.scale() != scale || !fits {
+            return Err(TinyEtlError::DataTransfer(format!(
+                "Decimal {} does not fit Avro field '{}'",
+                decimal, self.name
+            )));
+        }
+
+        let fill = if unscaled < 0 { 0xff } else { 0 };
+        let mut bytes = vec![fill; len.saturating_sub(16)];
+        bytes.extend_from_slice(&unscaled.to_be_bytes()[16 - len.min(16)..]);
+        Ok(AvroValue::Decimal(bytes.into()))
+    }
+}
+
+/// The logical type of a field if it has one, otherwise its Avro type.
+/// Unions use their first non-null branch.
+fn field_type_name(avro_type: &JsonValue) -> &str {
+    match avro_type {
+        JsonValue::String(name) => name,
+        JsonValue::Array(union_types) => union_types
+            .iter()
+            .map(field_type_name)
+            .find(|name| *name != "null")
+            .unwrap_or("null"),
+        JsonValue::Object(obj) => match obj.get("logicalType").and_then(|t| t.as_str()) {
+            Some(logical_type) => logical_type,
+            None => obj.get("type").map(field_type_name).unwrap_or("null"),
+        },
+        _ => "null",
+    }
+}
+
+/// Scale and encoded length of a decimal type: the size of a fixed type, or
+/// for bytes the shortest length that holds the full precision, which the
+/// writer checks values against
+fn decimal_layout(avro_type: &JsonValue) -> Option<(u32, usize)> {
+    match avro_type {
+        JsonValue::Array(union_types) => union_types.iter().find_map(decimal_layout),
+        JsonValue::Object(obj) if obj.get("logicalType")?.as_str()? == "decimal" => {
+            let scale = obj.get("scale").and_then(|s| s.as_u64()).unwrap_or(0) as u32;
+            let size = obj
+                .get("size")
+                .or_else(|| obj.get("type")?.get("size"))
+                .and_then(|s| s.as_u64());
+            let len = match size {
+                Some(size) => size as usize,
+                None => {
+                    let precision = obj.get("precision").and_then(|p| p.as_u64()).unwrap_or(38);
+                    (1..16)
+                        .find(|len| {
+                            let digits = (8 * len - 1) as f64 * std::f64::consts::LOG10_2;
+                            digits.floor() as u64 >= precision
+                        })
+                        .unwrap_or(16)
+                }
+            };
+            Some((scale, len))
+        }
+        _ => None,
+    }
+}
+
+/// Object container file header: the magic bytes, the schema and codec as
+/// metadata, and the sync marker that also ends every block
+fn container_header(schema: &AvroSchema, codec: Codec, marker: &[u8; 16]) -> Result<Vec<u8>> {
+    let metadata = HashMap::from([
+        (
+            "avro.schema".to_string(),
+            AvroValue::Bytes(serde_json::to_string(schema)?.into_bytes()),
+        ),
+        ("avro.codec".to_string(), AvroValue::from(codec)),
+    ]);
+    let metadata = to_avro_datum(
+        &AvroSchema::Map(Box::new(AvroSchema::Bytes)),
+        AvroValue::Map(metadata),
+    )
+    .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to write Avro header: {}", e)))?;
+
+    let mut header = b"Obj\x01".to_vec();
+    header.extend_from_slice(&metadata);
+    header.extend_from_slice(marker);
+    Ok(header)
+}
+
+fn micros_of_day(time: chrono::NaiveTime) -> i64 {
+    use chrono::Timelike;
+    time.num_seconds_from_midnight() as i64 * 1_000_000 + (time.nanosecond() / 1_000) as i64
+}
+
 #[async_trait]
 impl Target for AvroTarget {
     async fn connect(&mut self) -> Result<()> {
@@ -561,80 +953,61 @@ impl Target for AvroTarget {
     }
 
     async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
-        let avro_schema = Self::schema_to_avro_schema(schema)?;
+        let avro_schema = match &self.writer_schema {
+            Some(writer_schema) => {
+                self.writer_fields = Self::writer_fields(writer_schema, schema)?;
+                writer_schema.clone()
+            }
+            None => {
+                self.columns = schema.columns.clone();
+                self.schema_to_avro_schema(schema)?
+            }
+        };
+
+        // The header is written on its own, so a file without rows is still
+        // valid, and its sync marker reused for the blocks each batch appends
+        self.marker = *uuid::Uuid::new_v4().as_bytes();
+        let header = container_header(&avro_schema, self.codec, &self.marker)?;
+
+        let mut writer = BufWriter::new(File::create(&self.file_path)?);
+        writer.write_all(&header)?;
+        self.writer = Some(writer);
         self.schema = Some(avro_schema);
         Ok(())
     }
 
     async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
-        if self.schema.is_none() {
+        let Some(schema) = self.schema.as_ref() else {
             return Err(TinyEtlError::Configuration(
                 "Schema not initialized. Call create_table() first.".to_string(),
             ));
-        }
-
-        // Buffer the rows for writing during finalization
-        self.buffer.extend_from_slice(rows);
-        Ok(rows.len())
-    }
-
-    async fn finalize(&mut self) -> Result<()> {
-        if self.schema.is_none() {
-            return Ok(());
-        }
-
-        let schema = self.schema.as_ref().unwrap();
-
-        // Create writer and write all buffered data
-        let file = File::create(&self.file_path)?;
-        let buf_writer = BufWriter::new(file);
-
-        let mut writer = Writer::new(schema, buf_writer);
-
-        for row in &self.buffer {
-            let mut record_fields = Vec::new();
-
-            // Extract fields from schema to maintain order
-            let schema_json: JsonValue =
-                serde_json::from_str(&schema.canonical_form()).map_err(|e| {
-                    TinyEtlError::DataTransfer(format!("Failed to parse schema: {}", e))
-                })?;
-
-            if let JsonValue::Object(obj) = &schema_json {
-                if let Some(JsonValue::Array(fields)) = obj.get("fields") {
-                    for field in fields {
-                        if let JsonValue::Object(field_obj) = field {
-                            let field_name = field_obj
-                                .get("name")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("unknown");
-
-                            let default_type = JsonValue::String("string".to_string());
-                            let field_type = field_obj.get("type").unwrap_or(&default_type);
-                            let data_type = AvroSource::avro_type_to_schema_type(field_type);
-                            let nullable = AvroSource::is_nullable(field_type);
-
-                            let value = row.get(field_name).unwrap_or(&Value::Null);
-                            let avro_value =
-                                Self::value_to_avro_value(value, &data_type, nullable)?;
-
-                            record_fields.push((field_name.to_string(), avro_value));
-                        }
-                    }
-                }
-            }
+        };
+        let records = rows
+            .iter()
+            .map(|row| self.record(row, schema))
+            .collect::<Result<Vec<_>>>()?;
 
-            let record = AvroValue::Record(record_fields);
+        let Some(file) = self.writer.as_mut() else {
+            return Err(TinyEtlError::Configuration(
+                "Schema not initialized. Call create_table() first.".to_string(),
+            ));
+        };
+        let mut writer = Writer::append_to_with_codec(schema, file, self.codec, self.marker);
+        for record in records {
             writer.append(record).map_err(|e| {
                 TinyEtlError::DataTransfer(format!("Failed to write Avro record: {}", e))
             })?;
         }
-
         writer.flush().map_err(|e| {
             TinyEtlError::DataTransfer(format!("Failed to flush Avro writer: {}", e))
         })?;
+        Ok(rows.len())
+    }
 
-        self.buffer.clear();
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
         Ok(())
     }
 
@@ -643,14 +1016,13 @@ impl Target for AvroTarget {
     }
 
     async fn truncate(&mut self, _table_name: &str) -> Result<()> {
-        // For Avro files, truncation means clearing buffer
-        self.buffer.clear();
+        // The file is recreated by create_table
+        self.writer = None;
         Ok(())
     }
 
     fn supports_append(&self) -> bool {
-        // Avro files don't easily support append - would require reading existing file and merging
-        // For simplicity, we return false to force truncation
+        // Appending would have to match the existing file's schema and codec
         false
     }
 }
@@ -658,6 +1030,7 @@ impl Target for AvroTarget {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::connectors::options;
     use chrono::DateTime;
     use tempfile::NamedTempFile;
 
@@ -875,6 +1248,137 @@ mod tests {
         assert_eq!(rows.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_avro_source_logical_types() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let schema = AvroSchema::parse(&json!({
+            "type": "record",
+            "name": "Payment",
+            "fields": [
+                {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+                {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+                {"name": "refund", "type": ["null", {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}]},
+                {"name": "day", "type": {"type": "int", "logicalType": "date"}},
+                {"name": "at", "type": {"type": "int", "logicalType": "time-millis"}},
+                {"name": "paid_at", "type": {"type": "long", "logicalType": "timestamp-micros"}},
+                {"name": "local", "type": {"type": "long", "logicalType": "local-timestamp-millis"}}
+            ]
+        }))
+        .unwrap();
+
+        let id = uuid::Uuid::new_v4();
+        let mut writer = Writer::new(&schema, temp_file.reopen().unwrap());
+        writer
+            .append(AvroValue::Record(vec![
+                ("id".to_string(), AvroValue::Uuid(id)),
+                (
+                    "amount".to_string(),
+                    AvroValue::Decimal(apache_avro::Decimal::from(vec![0x30, 0x39])),
+                ),
+                (
+                    "refund".to_string(),
+                    AvroValue::Union(
+                        1,
+                        Box::new(AvroValue::Decimal(apache_avro::Decimal::from(vec![
+                            0xff, 0x9c,
+                        ]))),
+                    ),
+                ),
+                ("day".to_string(), AvroValue::Date(19_000)),
+                ("at".to_string(), AvroValue::TimeMillis(45_296_000)),
+                ("paid_at".to_string(), AvroValue::TimestampMicros(-1)),
+                (
+                    "local".to_string(),
+                    AvroValue::LocalTimestampMillis(1_609_459_200_000),
+                ),
+            ]))
+            .unwrap();
+        writer.flush().unwrap();
+
+        let mut source = AvroSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        let schema = source.infer_schema(10).await.unwrap();
+        let types: Vec<DataType> = schema.columns.iter().map(|c| c.data_type.clone()).collect();
+        assert_eq!(
+            types,
+            [
+                DataType::String,
+                DataType::Decimal,
+                DataType::Decimal,
+                DataType::Date,
+                DataType::String,
+                DataType::DateTime,
+                DataType::DateTime,
+            ]
+        );
+
+        source.connect().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        let row = &rows[0];
+        assert_eq!(row.get("id"), Some(&Value::String(id.to_string())));
+        assert_eq!(
+            row.get("amount"),
+            Some(&Value::Decimal(Decimal::new(12345, 2)))
+        );
+        assert_eq!(
+            row.get("refund"),
+            Some(&Value::Decimal(Decimal::new(-100, 2)))
+        );
+        assert_eq!(row.get("at"), Some(&Value::String("12:34:56".to_string())));
+        assert_eq!(
+            row.get("paid_at"),
+            Some(&Value::Date(
+                DateTime::parse_from_rfc3339("1969-12-31T23:59:59.999999Z")
+                    .unwrap()
+                    .into()
+            ))
+        );
+        assert_eq!(
+            row.get("local"),
+            Some(&Value::Date(
+                DateTime::parse_from_rfc3339("2021-01-01T00:00:00Z")
+                    .unwrap()
+                    .into()
+            ))
+        );
+        let (read, total) = source.progress_bytes().unwrap();
+        assert_eq!(read, total);
+    }
+
+    #[tokio::test]
+    async fn test_avro_source_reader_schema() {
+        let temp_file = create_test_avro_file().unwrap();
+        // Drops most fields and adds one with a default
+        let reader_schema = json!({
+            "type": "record",
+            "name": "TestRecord",
+            "fields": [
+                {"name": "id", "type": "long"},
+                {"name": "country", "type": "string", "default": "NZ"}
+            ]
+        })
+        .to_string();
+        let options = HashMap::from([("reader_schema".to_string(), reader_schema)]);
+
+        let mut source =
+            AvroSource::with_options(temp_file.path().to_str().unwrap(), &options).unwrap();
+        let schema = source.infer_schema(10).await.unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "country"]);
+
+        source.connect().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].len(), 2);
+        assert_eq!(rows[1].get("id"), Some(&Value::Integer(2)));
+        assert_eq!(
+            rows[1].get("country"),
+            Some(&Value::String("NZ".to_string()))
+        );
+
+        let options = HashMap::from([("reader_schema".to_string(), "missing.avsc".to_string())]);
+        assert!(AvroSource::with_options("data.avro", &options).is_err());
+    }
+
     #[tokio::test]
     async fn test_avro_type_to_schema_type() {
         assert_eq!(
@@ -1000,7 +1504,7 @@ mod tests {
         let target = target.unwrap();
         assert_eq!(target.file_path.to_string_lossy(), "output.avro");
         assert!(target.schema.is_none());
-        assert!(target.buffer.is_empty());
+        assert!(target.writer.is_none());
     }
 
     #[tokio::test]
@@ -1012,7 +1516,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_avro_target_create_table() {
-        let mut target = AvroTarget::new("output.avro").unwrap();
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut target = AvroTarget::new(temp_file.path().to_str().unwrap()).unwrap();
 
         let schema = Schema {
             columns: vec![
@@ -1051,6 +1556,29 @@ mod tests {
         assert!(target.schema.is_some());
     }
 
+    #[tokio::test]
+    async fn test_avro_target_without_rows() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("empty.avro");
+        let options = HashMap::from([("codec".to_string(), "deflate".to_string())]);
+        let mut target = AvroTarget::with_options(path.to_str().unwrap(), &options).unwrap();
+        let schema = Schema {
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        target.create_table("empty", &schema).await.unwrap();
+        target.finalize().await.unwrap();
+
+        let reader = Reader::new(File::open(&path).unwrap()).unwrap();
+        assert!(reader.writer_schema().canonical_form().contains("\"id\""));
+        assert_eq!(reader.count(), 0);
+    }
+
     #[tokio::test]
     async fn test_schema_to_avro_schema() {
         let schema = Schema {
@@ -1075,7 +1603,9 @@ mod tests {
             primary_key_candidate: None,
         };
 
-        let avro_schema = AvroTarget::schema_to_avro_schema(&schema);
+        let avro_schema = AvroTarget::new("output.avro")
+            .unwrap()
+            .schema_to_avro_schema(&schema);
         assert!(avro_schema.is_ok());
 
         let schema_json: JsonValue =
@@ -1339,6 +1869,174 @@ mod tests {
         assert!(!target_nonexistent.exists("test_table").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_avro_target_codec_and_record_name() {
+        let schema = Schema {
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                Column {
+                    name: "day".to_string(),
+                    data_type: DataType::Date,
+                    nullable: true,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let day = DateTime::parse_from_rfc3339("2024-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let rows: Vec<Row> = (0..100)
+            .map(|i| {
+                HashMap::from([
+                    ("id".to_string(), Value::Integer(i)),
+                    ("day".to_string(), Value::Date(day)),
+                ])
+            })
+            .collect();
+
+        for (codec, name) in [
+            ("null", "null"),
+            ("deflate", "deflate"),
+            ("snappy", "snappy"),
+            ("zstd", "zstandard"),
+        ] {
+            let temp_file = NamedTempFile::new().unwrap();
+            let path = temp_file.path().to_str().unwrap();
+            let mut target = AvroTarget::with_options(
+                path,
+                &options(&[
+                    ("codec", codec),
+                    ("record_name", "Order"),
+                    ("namespace", "com.example"),
+                ]),
+            )
+            .unwrap();
+            target.create_table("orders", &schema).await.unwrap();
+            target.write_batch(&rows[..60]).await.unwrap();
+            target.write_batch(&rows[60..]).await.unwrap();
+            target.finalize().await.unwrap();
+
+            let bytes = std::fs::read(path).unwrap();
+            assert!(bytes.windows(name.len()).any(|w| w == name.as_bytes()));
+            let reader = Reader::new(bytes.as_slice()).unwrap();
+            let written: JsonValue = serde_json::to_value(reader.writer_schema()).unwrap();
+            assert_eq!(written["name"], "Order");
+            assert_eq!(written["namespace"], "com.example");
+
+            let mut source = AvroSource::new(path).unwrap();
+            source.connect().await.unwrap();
+            let read = source.read_batch(1000).await.unwrap();
+            assert_eq!(read.len(), 100, "codec {}", codec);
+            assert_eq!(read[99]["id"], Value::Integer(99));
+            assert_eq!(read[99]["day"], Value::Date(day));
+        }
+
+        let err = AvroTarget::with_options("out.avro", &options(&[("codec", "lzma")]));
+        assert!(matches!(err, Err(TinyEtlError::Configuration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_avro_target_writer_schema() {
+        let schema_file = NamedTempFile::new().unwrap();
+        std::fs::write(
+            schema_file.path(),
+            json!({
+                "type": "record",
+                "name": "Payment",
+                "namespace": "com.example.events",
+                "fields": [
+                    {"name": "id", "type": "int"},
+                    {"name": "amount", "type": {
+                        "type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2
+                    }},
+                    {"name": "status", "type": {
+                        "type": "enum", "name": "Status", "symbols": ["PAID", "REFUNDED"]
+                    }},
+                    {"name": "paid_at", "type": ["null", {
+                        "type": "long", "logicalType": "timestamp-micros"
+                    }]},
+                    {"name": "channel", "type": "string", "default": "web"}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let writer_schema = schema_file.path().to_str().unwrap();
+
+        let column = |name: &str, data_type: DataType| Column {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+        };
+        let schema = Schema {
+            columns: vec![
+                column("id", DataType::Integer),
+                column("amount", DataType::Decimal),
+                column("status", DataType::String),
+                column("paid_at", DataType::DateTime),
+                column("extra", DataType::String),
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let paid_at = DateTime::parse_from_rfc3339("2024-03-01T12:30:00.000001Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let row = |status: &str| {
+            HashMap::from([
+                ("id".to_string(), Value::Integer(7)),
+                ("amount".to_string(), Value::Decimal(Decimal::new(125, 1))),
+                ("status".to_string(), Value::String(status.to_string())),
+                ("paid_at".to_string(), Value::Date(paid_at)),
+                ("extra".to_string(), Value::String("dropped".to_string())),
+            ])
+        };
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+        let mut target =
+            AvroTarget::with_options(path, &options(&[("writer_schema", writer_schema)])).unwrap();
+        target.create_table("payments", &schema).await.unwrap();
+        target.write_batch(&[row("PAID")]).await.unwrap();
+        let mut null_time = row("REFUNDED");
+        null_time.insert("paid_at".to_string(), Value::Null);
+        target.write_batch(&[null_time]).await.unwrap();
+        assert!(target.write_batch(&[row("LOST")]).await.is_err());
+        target.finalize().await.unwrap();
+
+        // Written with exactly the supplied schema
+        let reader = Reader::new(File::open(path).unwrap()).unwrap();
+        assert_eq!(
+            reader.writer_schema().canonical_form(),
+            load_schema(writer_schema).unwrap().canonical_form()
+        );
+
+        let mut source = AvroSource::new(path).unwrap();
+        source.connect().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["id"], Value::Integer(7));
+        assert_eq!(rows[0]["amount"], Value::Decimal(Decimal::new(1250, 2)));
+        assert_eq!(rows[0]["status"], Value::String("PAID".to_string()));
+        assert_eq!(rows[0]["paid_at"], Value::Date(paid_at));
+        assert_eq!(rows[0]["channel"], Value::String("web".to_string()));
+        assert!(!rows[0].contains_key("extra"));
+        assert_eq!(rows[1]["paid_at"], Value::Null);
+
+        // A required field the table can't fill
+        let mut missing = schema.clone();
+        missing.columns.retain(|c| c.name != "status");
+        let mut target =
+            AvroTarget::with_options(path, &options(&[("writer_schema", writer_schema)])).unwrap();
+        let err = target.create_table("payments", &missing).await.unwrap_err();
+        assert!(err.to_string().contains("'status'"), "{}", err);
+    }
+
     #[tokio::test]
     async fn test_avro_source_invalid_file_content() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -1453,22 +2151,14 @@ mod tests {
         }
 
         // Test TimeMillis
-        let time_millis = AvroValue::TimeMillis(3600000); // 1 hour
+        let time_millis = AvroValue::TimeMillis(3600500); // 1 hour and 500ms
         let result = AvroSource::avro_value_to_value(&time_millis).unwrap();
-        if let Value::String(s) = result {
-            assert!(s.contains("3600000"));
-        } else {
-            panic!("Expected String value");
-        }
+        assert_eq!(result, Value::String("01:00:00.500".to_string()));
 
         // Test TimeMicros
-        let time_micros = AvroValue::TimeMicros(3600000000); // 1 hour
+        let time_micros = AvroValue::TimeMicros(45296000001); // 12:34:56 and 1μs
         let result = AvroSource::avro_value_to_value(&time_micros).unwrap();
-        if let Value::String(s) = result {
-            assert!(s.contains("3600000000"));
-        } else {
-            panic!("Expected String value");
-        }
+        assert_eq!(result, Value::String("12:34:56.000001".to_string()));
 
         // Test TimestampMicros
         let timestamp_micros = AvroValue::TimestampMicros(1609459200000000); // 2021-01-01
@@ -1599,7 +2289,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_avro_target_buffer_size() {
+    async fn test_avro_target_multiple_batches() {
         let temp_file = NamedTempFile::new().unwrap();
         let mut target = AvroTarget::new(temp_file.path().to_str().unwrap()).unwrap();
 
@@ -1616,18 +2306,25 @@ mod tests {
         target.connect().await.unwrap();
         target.create_table("test", &schema).await.unwrap();
 
-        // Write multiple small batches
+        // Write multiple small batches, each appended as it arrives
         for i in 0..5 {
             let row = std::collections::HashMap::from([("id".to_string(), Value::Integer(i))]);
             target.write_batch(&[row]).await.unwrap();
         }
 
-        // Check buffer
-        assert_eq!(target.buffer.len(), 5);
-
-        // Finalize to flush buffer
         target.finalize().await.unwrap();
-        assert_eq!(target.buffer.len(), 0);
+        assert!(target.writer.is_none());
+
+        let mut source = AvroSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let ids: Vec<Value> = source
+            .read_batch(10)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row["id"].clone())
+            .collect();
+        assert_eq!(ids, (0..5).map(Value::Integer).collect::<Vec<_>>());
     }
 
     #[test]
@@ -1715,7 +2412,9 @@ mod tests {
             primary_key_candidate: None,
         };
 
-        let avro_schema = AvroTarget::schema_to_avro_schema(&schema);
+        let avro_schema = AvroTarget::new("output.avro")
+            .unwrap()
+            .schema_to_avro_schema(&schema);
         assert!(avro_schema.is_ok());
 
         let schema_json: JsonValue =
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::io::{BufWriter, Read, Write};
+
+use crate::{Result, TinyEtlError};
+
+/// File name suffixes recognised as compressed, checked case-insensitively
+const SUFFIXES: [&str; 6] = [".gz", ".gzip", ".zst", ".zstd", ".bz2", ".xz"];
+
+/// Compression of a file, from the `compression` option or the file name suffix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    /// Compression implied by the file name, e.g. gzip for `orders.csv.gz`
+    pub fn from_path(path: &str) -> Self {
+        compression_suffix(path)
+            .and_then(|suffix| Self::from_name(&suffix[1..]))
+            .unwrap_or(Compression::None)
+    }
+
+    /// Parse the `compression` option (`none`, `gzip`, `zstd`, `bzip2` or `xz`),
+    /// falling back to the file name suffix when it is not set
+    pub fn from_options(options: &HashMap<String, String>, path: &str) -> Result<Self> {
+        match options.get("compression").map(|c| c.trim()) {
+            None | Some("") => Ok(Self::from_path(path)),
+            Some(name) => Self::from_name(name).ok_or_else(|| {
+                TinyEtlError::Configuration(format!(
+                    "Unknown compression '{}'. Supported: none, gzip, zstd, bzip2, xz",
+                    name
+                ))
+            }),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "none" => Some(Compression::None),
+            "gzip" | "gz" => Some(Compression::Gzip),
+            "zstd" | "zst" => Some(Compression::Zstd),
+            "bzip2" | "bz2" => Some(Compression::Bzip2),
+            "xz" => Some(Compression::Xz),
+            _ => None,
+        }
+    }
+
+    /// Wrap a reader so it yields the decompressed bytes. Concatenated streams
+    /// (e.g. from `cat a.gz b.gz`) are read through to the end.
+    pub fn decompress<R>(&self, reader: R) -> Result<Box<dyn Read + Send + Sync>>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        Ok(match self {
+            Compression::None => Box::new(reader),
+            Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+            Compression::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+            Compression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+            Compression::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+        })
+    }
+
+    /// Wrap a writer so bytes written to it are compressed. Call `finish` on
+    /// the returned writer to complete the stream.
+    pub fn compress<W>(&self, writer: W) -> Result<CompressedWriter>
+    where
+        W: Write + Send + Sync + 'static,
+    {
+        let writer: Box<dyn Write + Send + Sync> = Box::new(writer);
+        let encoder = match self {
+            Compression::None => Encoder::None(writer),
+            Compression::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                writer,
+                flate2::Compression::default(),
+            )),
+            Compression::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(writer, 0)?),
+            Compression::Bzip2 => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                writer,
+                bzip2::Compression::default(),
+            )),
+            Compression::Xz => Encoder::Xz(xz2::write::XzEncoder::new(writer, 6)),
+        };
+        Ok(CompressedWriter {
+            encoder: Some(encoder),
+        })
+    }
+}
+
+type BoxedWriter = Box<dyn Write + Send + Sync>;
+
+enum Encoder {
+    None(BoxedWriter),
+    Gzip(flate2::write::GzEncoder<BoxedWriter>),
+    Zstd(zstd::stream::write::Encoder<'static, BoxedWriter>),
+    Bzip2(bzip2::write::BzEncoder<BoxedWriter>),
+    Xz(xz2::write::XzEncoder<BoxedWriter>),
+}
+
+/// Writer that compresses what is written to it. The end of the stream is
+/// only written by `finish`, which reports errors such as a full disk; a
+/// writer that is dropped instead finishes its stream but can only log them.
+pub struct CompressedWriter {
+    /// Taken by `finish`
+    encoder: Option<Encoder>,
+}
+
+impl CompressedWriter {
+    /// Write the end of the stream and flush it to the underlying writer
+    pub fn finish(mut self) -> Result<()> {
+        let mut writer = match self.encoder.take().expect("writer not finished") {
+            Encoder::None(writer) => writer,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Zstd(encoder) => encoder.finish()?,
+            Encoder::Bzip2(encoder) => encoder.finish()?,
+            Encoder::Xz(encoder) => encoder.finish()?,
+        };
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Flush the buffer of a buffered writer, then finish the stream
+    pub fn finish_buffered(writer: BufWriter<Self>) -> Result<()> {
+        writer
+            .into_inner()
+            .map_err(|e| TinyEtlError::Io(e.into_error()))?
+            .finish()
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self.encoder.as_mut().expect("writer not finished") {
+            Encoder::None(writer) => writer,
+            Encoder::Gzip(encoder) => encoder,
+            Encoder::Zstd(encoder) => encoder,
+            Encoder::Bzip2(encoder) => encoder,
+            Encoder::Xz(encoder) => encoder,
+        }
+    }
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer().flush()
+    }
+}
+
+impl Drop for CompressedWriter {
+    fn drop(&mut self) {
+        // The other encoders finish their streams when they are dropped
+        if let Some(Encoder::Zstd(encoder)) = &mut self.encoder {
+            if let Err(e) = encoder.do_finish() {
+                tracing::warn!("Failed to finish zstd stream: {}", e);
+            }
+        }
+    }
+}
+
+/// The compression suffix of a file name including the dot, e.g. `.gz` for `orders.csv.gz`
+pub fn compression_suffix(path: &str) -> Option<&str> {
+    SUFFIXES.iter().find_map(|suffix| {
+        let start = path.len().checked_sub(suffix.len())?;
+        path.get(start..)
+            .filter(|tail| tail.eq_ignore_ascii_case(suffix))
+    })
+}
+
+/// The file name without its compression suffix, e.g. `orders.csv` for `orders.csv.gz`
+pub fn strip_compression_suffix(path: &str) -> Option<&str> {
+    compression_suffix(path).map(|suffix| &path[..path.len() - suffix.len()])
+}
+
+/// A file name and its compression suffix, which is empty for uncompressed files
+pub fn split_compression_suffix(path: &str) -> (&str, &str) {
+    let suffix = compression_suffix(path).unwrap_or_default();
+    path.split_at(path.len() - suffix.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer whose contents stay readable after the compressor is dropped
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_from_path_and_options() {
+        assert_eq!(Compression::from_path("orders.csv.gz"), Compression::Gzip);
+        assert_eq!(Compression::from_path("events.JSON.ZST"), Compression::Zstd);
+        assert_eq!(Compression::from_path("a.csv.bz2"), Compression::Bzip2);
+        assert_eq!(Compression::from_path("a.csv.xz"), Compression::Xz);
+        assert_eq!(Compression::from_path("a.csv"), Compression::None);
+        assert_eq!(strip_compression_suffix("out/a.csv.gz"), Some("out/a.csv"));
+        assert_eq!(strip_compression_suffix("a.csv"), None);
+        assert_eq!(split_compression_suffix("a.csv.XZ"), ("a.csv", ".XZ"));
+        assert_eq!(split_compression_suffix("a.csv"), ("a.csv", ""));
+
+        let options = |value: &str| HashMap::from([("compression".to_string(), value.to_string())]);
+        assert_eq!(
+            Compression::from_options(&options("zstd"), "a.csv").unwrap(),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::from_options(&options("none"), "a.csv.gz").unwrap(),
+            Compression::None
+        );
+        assert!(Compression::from_options(&options("lz4"), "a.csv").is_err());
+    }
+
+    /// Writer for a disk that is already full
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "no space left on device",
+            ))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_finish_reports_errors() {
+        // The compressors write nothing until the end of the stream
+        for compression in [
+            Compression::Gzip,
+            Compression::Zstd,
+            Compression::Bzip2,
+            Compression::Xz,
+        ] {
+            let writer = compression.compress(FullDisk).unwrap();
+            assert!(writer.finish().is_err(), "{:?}", compression);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "id,name\n1,Alice\n2,Bob\n".repeat(100);
+        for compression in [
+            Compression::None,
+            Compression::Gzip,
+            Compression::Zstd,
+            Compression::Bzip2,
+            Compression::Xz,
+        ] {
+            let buffer = SharedBuffer::default();
+            let mut writer = compression.compress(buffer.clone()).unwrap();
+            writer.write_all(text.as_bytes()).unwrap();
+            writer.finish().unwrap();
+            let compressed = buffer.0.lock().unwrap().clone();
+            if compression != Compression::None {
+                assert!(compressed.len() < text.len(), "{:?}", compression);
+            }
+
+            let mut decompressed = String::new();
+            compression
+                .decompress(Cursor::new(compressed))
+                .unwrap()
+                .read_to_string(&mut decompressed)
+                .unwrap();
+            assert_eq!(decompressed, text, "{:?}", compression);
+        }
+    }
+}
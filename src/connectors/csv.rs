@@ -1,36 +1,321 @@
 use async_trait::async_trait;
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::{QuoteStyle, ReaderBuilder, StringRecord, Terminator, WriterBuilder};
 use rust_decimal::Decimal;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use crate::{
-    connectors::{Source, Target},
+    connectors::{
+        compression::{CompressedWriter, Compression},
+        encoding::{EncodingWriter, TextEncoding},
+        stream, Source, Target,
+    },
     date_parser::DateParser,
-    schema::{Row, Schema, Value},
+    schema::{DataType, Row, Schema, Value},
     Result, TinyEtlError,
 };
 
+/// CSV dialect settings from `source.options` / `target.options`
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub quote: u8,
+    pub escape: Option<u8>,
+    pub double_quote: bool,
+    /// Lines starting with this byte are skipped (source only)
+    pub comment: Option<u8>,
+    pub has_header: bool,
+    /// Column names to use instead of the header row, e.g. for headerless files (source only)
+    pub columns: Option<Vec<String>>,
+    /// Lines to skip before the header (source only)
+    pub skip_rows: usize,
+    /// Records to drop at the end of the file (source only)
+    pub skip_footer: usize,
+    /// Record terminator; by default any of `\r`, `\n` or `\r\n` is read and `\n` is written
+    pub terminator: Option<Terminator>,
+    /// When to quote fields (target only)
+    pub quote_style: QuoteStyle,
+    pub encoding: TextEncoding,
+    /// Cells read as null in addition to empty ones, e.g. `NA` or `\N` (source only)
+    pub null_values: Vec<String>,
+    /// Columns kept as strings whatever they look like (source only)
+    pub string_columns: Vec<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            double_quote: true,
+            comment: None,
+            has_header: true,
+            columns: None,
+            skip_rows: 0,
+            skip_footer: 0,
+            terminator: None,
+            quote_style: QuoteStyle::Necessary,
+            encoding: TextEncoding::Default,
+            null_values: Vec::new(),
+            string_columns: Vec::new(),
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Parse `delimiter`, `quote`, `escape`, `double_quote`, `comment`, `has_header`,
+    /// `columns`, `skip_rows`, `skip_footer`, `line_terminator`, `quote_style`, `encoding`,
+    /// `null_values` and `string_columns`.
+    /// Other keys belong to the protocol or the target table and are ignored.
+    pub fn from_options(options: &HashMap<String, String>) -> Result<Self> {
+        let mut csv_options = Self {
+            encoding: TextEncoding::from_options(options)?,
+            ..Self::default()
+        };
+
+        for (key, value) in options {
+            match key.as_str() {
+                "delimiter" => csv_options.delimiter = Self::parse_byte(key, value)?,
+                "quote" => csv_options.quote = Self::parse_byte(key, value)?,
+                "escape" => csv_options.escape = Some(Self::parse_byte(key, value)?),
+                "double_quote" => csv_options.double_quote = Self::parse_bool(key, value)?,
+                "comment" => csv_options.comment = Some(Self::parse_byte(key, value)?),
+                "has_header" => csv_options.has_header = Self::parse_bool(key, value)?,
+                "columns" => csv_options.columns = Some(Self::parse_list(value)),
+                "null_values" => csv_options.null_values = Self::parse_list(value),
+                "string_columns" => csv_options.string_columns = Self::parse_list(value),
+                "skip_rows" => csv_options.skip_rows = Self::parse_count(key, value)?,
+                "skip_footer" => csv_options.skip_footer = Self::parse_count(key, value)?,
+                "line_terminator" => {
+                    csv_options.terminator = Some(match value.as_str() {
+                        "\r\n" | "\\r\\n" | "crlf" | "CRLF" => Terminator::CRLF,
+                        _ => Terminator::Any(Self::parse_byte(key, value)?),
+                    })
+                }
+                "quote_style" => {
+                    csv_options.quote_style = match value.to_lowercase().as_str() {
+                        "necessary" => QuoteStyle::Necessary,
+                        "always" => QuoteStyle::Always,
+                        "non_numeric" => QuoteStyle::NonNumeric,
+                        "never" => QuoteStyle::Never,
+                        _ => {
+                            return Err(TinyEtlError::Configuration(format!(
+                                "Invalid quote_style '{}': expected necessary, always, non_numeric or never",
+                                value
+                            )))
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(csv_options)
+    }
+
+    /// A single ASCII character, or an escape such as `\t` written literally in YAML
+    fn parse_byte(key: &str, value: &str) -> Result<u8> {
+        match value {
+            "\\t" | "tab" => return Ok(b'\t'),
+            "\\n" | "lf" | "LF" => return Ok(b'\n'),
+            "\\r" => return Ok(b'\r'),
+            _ => {}
+        }
+        match value.as_bytes() {
+            [byte] if byte.is_ascii() => Ok(*byte),
+            _ => Err(TinyEtlError::Configuration(format!(
+                "Invalid value '{}' for CSV option '{}': expected a single ASCII character",
+                value, key
+            ))),
+        }
+    }
+
+    fn parse_list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .collect()
+    }
+
+    fn parse_bool(key: &str, value: &str) -> Result<bool> {
+        value.parse().map_err(|_| {
+            TinyEtlError::Configuration(format!(
+                "Invalid value '{}' for CSV option '{}': expected true or false",
+                value, key
+            ))
+        })
+    }
+
+    fn parse_count(key: &str, value: &str) -> Result<usize> {
+        value.parse().map_err(|_| {
+            TinyEtlError::Configuration(format!(
+                "Invalid value '{}' for CSV option '{}': expected a number",
+                value, key
+            ))
+        })
+    }
+
+    fn reader_builder(&self) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.double_quote)
+            .comment(self.comment)
+            .has_headers(self.has_header)
+            // Trailer records rarely have the same number of fields as the data
+            .flexible(self.skip_footer > 0);
+        if let Some(terminator) = self.terminator {
+            builder.terminator(terminator);
+        }
+        builder
+    }
+
+    fn writer_builder(&self) -> WriterBuilder {
+        let mut builder = WriterBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .double_quote(self.double_quote)
+            .quote_style(self.quote_style);
+        if let Some(escape) = self.escape {
+            builder.escape(escape);
+        }
+        if let Some(terminator) = self.terminator {
+            builder.terminator(terminator);
+        }
+        builder
+    }
+}
+
 pub struct CsvSource {
     file_path: PathBuf,
-    reader: Option<csv::Reader<std::fs::File>>,
+    options: CsvOptions,
+    compression: Compression,
+    reader: Option<csv::Reader<BufReader<Box<dyn Read + Send + Sync>>>>,
     headers: Vec<String>,
     current_position: u64,
     has_more_data: bool,
+    /// Records held back so the last `skip_footer` are never returned
+    footer_buffer: VecDeque<StringRecord>,
+    /// Column types from `set_schema`, e.g. a schema file
+    declared_types: HashMap<String, DataType>,
+    /// Column types from the last `infer_schema`
+    inferred_types: HashMap<String, DataType>,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
 }
 
 impl CsvSource {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
         Ok(Self {
             file_path: PathBuf::from(file_path),
+            options: CsvOptions::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
             reader: None,
             headers: Vec::new(),
             current_position: 0,
             has_more_data: true,
+            footer_buffer: VecDeque::new(),
+            declared_types: HashMap::new(),
+            inferred_types: HashMap::new(),
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
         })
     }
 
+    /// Open the file positioned after the `skip_rows` preamble
+    fn open_reader(&self) -> Result<csv::Reader<BufReader<Box<dyn Read + Send + Sync>>>> {
+        let file = stream::open_file(&self.file_path, self.compression, &self.bytes_read)?;
+        let mut file = BufReader::new(self.options.encoding.decode(file)?);
+        let mut line = Vec::new();
+        for _ in 0..self.options.skip_rows {
+            line.clear();
+            if file.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+        }
+        Ok(self.options.reader_builder().from_reader(file))
+    }
+
+    /// Next record, holding back `skip_footer` records until the end of the file
+    fn next_record(&mut self) -> Result<Option<StringRecord>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+
+        loop {
+            let mut record = StringRecord::new();
+            if !reader.read_record(&mut record)? {
+                // Whatever is still buffered is the footer
+                self.footer_buffer.clear();
+                return Ok(None);
+            }
+            self.footer_buffer.push_back(record);
+            if self.footer_buffer.len() > self.options.skip_footer {
+                return Ok(self.footer_buffer.pop_front());
+            }
+        }
+    }
+
+    fn record_to_row(&self, record: &StringRecord) -> Row {
+        let mut row = Row::new();
+        for (i, field) in record.iter().enumerate() {
+            if let Some(header) = self.headers.get(i) {
+                row.insert(header.clone(), self.parse_field(header, field));
+            }
+        }
+        row
+    }
+
+    /// Parse a cell as its column's declared or inferred type, guessing only
+    /// when the type is unknown. Cells that don't parse are kept as strings.
+    fn parse_field(&self, column: &str, value: &str) -> Value {
+        if value.is_empty() || self.options.null_values.iter().any(|n| n == value) {
+            return Value::Null;
+        }
+        if self.options.string_columns.iter().any(|c| c == column) {
+            return Value::String(value.to_string());
+        }
+
+        let data_type = self
+            .declared_types
+            .get(column)
+            .or_else(|| self.inferred_types.get(column));
+        Self::parse_as(value, data_type)
+    }
+
+    /// Parse text as `data_type`, or guess its type when that is unknown.
+    /// Text that doesn't parse is kept as a string.
+    pub(crate) fn parse_as(value: &str, data_type: Option<&DataType>) -> Value {
+        let parsed = match data_type {
+            None | Some(DataType::Null) => return Self::parse_value(value),
+            Some(DataType::String) => None,
+            Some(DataType::Integer) => value.parse::<i64>().ok().map(Value::Integer),
+            Some(DataType::Decimal) => value.parse::<Decimal>().ok().map(Value::Decimal),
+            Some(DataType::Boolean) => match value.to_lowercase().as_str() {
+                "true" => Some(Value::Boolean(true)),
+                "false" => Some(Value::Boolean(false)),
+                _ => None,
+            },
+            Some(DataType::Date | DataType::DateTime) => DateParser::try_parse(value),
+            Some(DataType::Json) => serde_json::from_str(value).ok().map(Value::Json),
+        };
+        parsed.unwrap_or_else(|| Value::String(value.to_string()))
+    }
+
     fn infer_schema_with_order(&self, rows: &[Row]) -> Result<Schema> {
         if rows.is_empty() {
             return Ok(Schema {
@@ -79,17 +364,24 @@ impl CsvSource {
         })
     }
 
+    /// Guess the type of a cell whose column type is unknown
     fn parse_value(value: &str) -> Value {
-        // Try to parse as different types
+        // Codes with leading zeros (zip codes, account numbers) and numbers in
+        // scientific notation (often IDs) are not numbers worth converting
+        let leading_zero =
+            value.len() > 1 && value.starts_with('0') && value.as_bytes()[1].is_ascii_digit();
+        let plain_number = !leading_zero && !value.contains(['e', 'E']);
 
         // Try integer first
-        if let Ok(int_val) = value.parse::<i64>() {
-            return Value::Integer(int_val);
-        }
+        if plain_number {
+            if let Ok(int_val) = value.parse::<i64>() {
+                return Value::Integer(int_val);
+            }
 
-        // Try decimal
-        if let Ok(decimal_val) = value.parse::<Decimal>() {
-            return Value::Decimal(decimal_val);
+            // Try decimal
+            if let Ok(decimal_val) = value.parse::<Decimal>() {
+                return Value::Decimal(decimal_val);
+            }
         }
 
         // Try boolean
@@ -121,15 +413,23 @@ impl Source for CsvSource {
             )));
         }
 
-        let file = std::fs::File::open(&self.file_path)?;
-        let mut reader = ReaderBuilder::new().has_headers(true).from_reader(file);
-
-        // Read and store headers
-        self.headers = reader.headers()?.iter().map(|h| h.to_string()).collect();
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        let mut reader = self.open_reader()?;
+
+        // Read and store headers. Without a header row, `headers()` returns the
+        // first record without consuming it, which gives the column count.
+        let first_record = reader.headers()?;
+        self.headers = match &self.options.columns {
+            Some(columns) => columns.clone(),
+            None if self.options.has_header => first_record.iter().map(|h| h.to_string()).collect(),
+            None => (1..=first_record.len())
+                .map(|i| format!("column_{}", i))
+                .collect(),
+        };
 
         // Reset file for actual reading
-        let file = std::fs::File::open(&self.file_path)?;
-        self.reader = Some(ReaderBuilder::new().has_headers(true).from_reader(file));
+        self.reader = Some(self.open_reader()?);
+        self.footer_buffer.clear();
 
         Ok(())
     }
@@ -139,27 +439,14 @@ impl Source for CsvSource {
             self.connect().await?;
         }
 
+        // Guess afresh rather than parsing as the previous inference's types
+        self.inferred_types.clear();
         let mut sample_rows = Vec::new();
-        let mut count = 0;
-
-        if let Some(ref mut reader) = self.reader {
-            for result in reader.records() {
-                if count >= sample_size {
-                    break;
-                }
 
-                let record = result?;
-                let mut row = Row::new();
-
-                for (i, field) in record.iter().enumerate() {
-                    if let Some(header) = self.headers.get(i) {
-                        let value = Self::parse_value(field);
-                        row.insert(header.clone(), value);
-                    }
-                }
-
-                sample_rows.push(row);
-                count += 1;
+        while sample_rows.len() < sample_size {
+            match self.next_record()? {
+                Some(record) => sample_rows.push(self.record_to_row(&record)),
+                None => break,
             }
         }
 
@@ -167,7 +454,23 @@ impl Source for CsvSource {
         self.reset().await?;
 
         // Custom schema inference that preserves column order from CSV headers
-        self.infer_schema_with_order(&sample_rows)
+        let schema = self.infer_schema_with_order(&sample_rows)?;
+
+        // Parse the remaining rows consistently as the inferred types
+        self.inferred_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        Ok(schema)
+    }
+
+    fn set_schema(&mut self, schema: &Schema) {
+        self.declared_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
     }
 
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
@@ -176,32 +479,14 @@ impl Source for CsvSource {
         }
 
         let mut rows = Vec::new();
-        let mut count = 0;
-
-        if let Some(ref mut reader) = self.reader {
-            let mut records_iter = reader.records();
-
-            while count < batch_size {
-                match records_iter.next() {
-                    Some(Ok(record)) => {
-                        let mut row = Row::new();
 
-                        for (i, field) in record.iter().enumerate() {
-                            if let Some(header) = self.headers.get(i) {
-                                let value = Self::parse_value(field);
-                                row.insert(header.clone(), value);
-                            }
-                        }
-
-                        rows.push(row);
-                        count += 1;
-                    }
-                    Some(Err(e)) => return Err(e.into()),
-                    None => {
-                        // No more records available
-                        self.has_more_data = false;
-                        break;
-                    }
+        while rows.len() < batch_size {
+            match self.next_record()? {
+                Some(record) => rows.push(self.record_to_row(&record)),
+                None => {
+                    // No more records available
+                    self.has_more_data = false;
+                    break;
                 }
             }
         }
@@ -210,16 +495,16 @@ impl Source for CsvSource {
     }
 
     async fn estimated_row_count(&self) -> Result<Option<usize>> {
-        // Simple estimation by counting lines (not perfect but fast)
-        let content = std::fs::read_to_string(&self.file_path)?;
-        let line_count = content.lines().count();
-        // Subtract 1 for header if present
-        Ok(Some(line_count.saturating_sub(1)))
+        let line_count = stream::estimate_line_count(&self.file_path, self.compression)?;
+        let header_lines = usize::from(self.options.has_header);
+        Ok(Some(line_count.saturating_sub(
+            self.options.skip_rows + header_lines + self.options.skip_footer,
+        )))
     }
 
     async fn reset(&mut self) -> Result<()> {
-        let file = std::fs::File::open(&self.file_path)?;
-        self.reader = Some(ReaderBuilder::new().has_headers(true).from_reader(file));
+        self.reader = Some(self.open_reader()?);
+        self.footer_buffer.clear();
         self.current_position = 0;
         self.has_more_data = true;
         Ok(())
@@ -228,25 +513,62 @@ impl Source for CsvSource {
     fn has_more(&self) -> bool {
         self.has_more_data && self.reader.is_some()
     }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
 }
 
 pub struct CsvTarget {
     file_path: PathBuf,
-    writer: Option<csv::Writer<std::fs::File>>,
+    options: CsvOptions,
+    compression: Compression,
+    writer: Option<csv::Writer<EncodingWriter<CompressedWriter>>>,
+    /// Writer given to `with_output`, used in place of the file
+    output: Option<Box<dyn Write + Send + Sync>>,
     headers_written: bool,
     column_order: Vec<String>,
 }
 
 impl CsvTarget {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
         Ok(Self {
             file_path: PathBuf::from(file_path),
+            options: CsvOptions::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
             writer: None,
+            output: None,
             headers_written: false,
             column_order: Vec::new(),
         })
     }
 
+    /// Write to `output`, such as standard output, instead of creating the
+    /// file. The file name still tells the compression.
+    pub fn with_output<W: Write + Send + Sync + 'static>(mut self, output: W) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    /// Create the file, compressing and encoding what is written to it
+    fn open_writer(&mut self) -> Result<csv::Writer<EncodingWriter<CompressedWriter>>> {
+        let file: Box<dyn Write + Send + Sync> = match self.output.take() {
+            Some(output) => output,
+            None => Box::new(File::create(&self.file_path)?),
+        };
+        let file = self.compression.compress(file)?;
+        Ok(self
+            .options
+            .writer_builder()
+            .from_writer(self.options.encoding.encode(file)))
+    }
+
     fn value_to_string(value: &Value) -> String {
         match value {
             Value::String(s) => s.clone(),
@@ -268,8 +590,7 @@ impl Target for CsvTarget {
             std::fs::create_dir_all(parent)?;
         }
 
-        let file = std::fs::File::create(&self.file_path)?;
-        self.writer = Some(WriterBuilder::new().from_writer(file));
+        self.writer = Some(self.open_writer()?);
         Ok(())
     }
 
@@ -283,7 +604,9 @@ impl Target for CsvTarget {
 
         // Write headers
         if let Some(ref mut writer) = self.writer {
-            writer.write_record(&self.column_order)?;
+            if self.options.has_header {
+                writer.write_record(&self.column_order)?;
+            }
             self.headers_written = true;
         }
 
@@ -331,8 +654,11 @@ impl Target for CsvTarget {
     }
 
     async fn finalize(&mut self) -> Result<()> {
-        if let Some(ref mut writer) = self.writer {
-            writer.flush()?;
+        if let Some(writer) = self.writer.take() {
+            let encoder = writer
+                .into_inner()
+                .map_err(|e| TinyEtlError::Io(e.into_error()))?;
+            encoder.into_inner().finish()?;
         }
         Ok(())
     }
@@ -343,8 +669,7 @@ impl Target for CsvTarget {
 
     async fn truncate(&mut self, _table_name: &str) -> Result<()> {
         // For CSV files, truncation means recreating the file
-        let file = std::fs::File::create(&self.file_path)?;
-        self.writer = Some(WriterBuilder::new().from_writer(file));
+        self.writer = Some(self.open_writer()?);
         self.headers_written = false;
         Ok(())
     }
@@ -359,6 +684,7 @@ impl Target for CsvTarget {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::connectors::options;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -451,6 +777,12 @@ mod tests {
 
         // Test string fallback
         assert!(matches!(CsvSource::parse_value("hello"), Value::String(s) if s == "hello"));
+
+        // Leading zeros and scientific notation stay strings
+        assert!(matches!(CsvSource::parse_value("01234"), Value::String(s) if s == "01234"));
+        assert!(matches!(CsvSource::parse_value("1e5"), Value::String(s) if s == "1e5"));
+        assert!(matches!(CsvSource::parse_value("0"), Value::Integer(0)));
+        assert!(matches!(CsvSource::parse_value("0.5"), Value::Decimal(_)));
     }
 
     #[tokio::test]
@@ -474,6 +806,38 @@ mod tests {
         assert_eq!(count, Some(2)); // 2 data rows (excluding header)
     }
 
+    #[tokio::test]
+    async fn test_csv_source_estimates_large_files_from_sample() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "id,name").unwrap();
+        for i in 0..200_000 {
+            writeln!(temp_file, "{:06},name_{:06}", i, i).unwrap();
+        }
+
+        let mut source = CsvSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+
+        let count = source.estimated_row_count().await.unwrap().unwrap();
+        assert!((199_000..=201_000).contains(&count), "estimate {}", count);
+    }
+
+    #[tokio::test]
+    async fn test_csv_source_progress_bytes() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "id,name").unwrap();
+        writeln!(temp_file, "1,Alice").unwrap();
+        writeln!(temp_file, "2,Bob").unwrap();
+        let file_size = temp_file.as_file().metadata().unwrap().len();
+
+        let mut source = CsvSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        assert_eq!(source.progress_bytes(), None);
+        source.connect().await.unwrap();
+        source.reset().await.unwrap();
+        source.read_batch(10).await.unwrap();
+
+        assert_eq!(source.progress_bytes(), Some((file_size, file_size)));
+    }
+
     #[tokio::test]
     async fn test_csv_source_has_more() {
         let mut temp_file = NamedTempFile::new().unwrap();
@@ -577,6 +941,240 @@ mod tests {
         assert_eq!(written, 1);
     }
 
+    #[tokio::test]
+    async fn test_csv_source_headerless_pipe_delimited() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "VENDOR EXPORT v2").unwrap();
+        writeln!(temp_file, "generated 2024-01-01").unwrap();
+        writeln!(temp_file, "1|Alice|'a|b'").unwrap();
+        writeln!(temp_file, "# skipped comment").unwrap();
+        writeln!(temp_file, "2|Bob|x").unwrap();
+        writeln!(temp_file, "TRAILER|2").unwrap();
+
+        let mut source = CsvSource::with_options(
+            temp_file.path().to_str().unwrap(),
+            &options(&[
+                ("delimiter", "|"),
+                ("quote", "'"),
+                ("comment", "#"),
+                ("has_header", "false"),
+                ("skip_rows", "2"),
+                ("skip_footer", "1"),
+            ]),
+        )
+        .unwrap();
+        source.connect().await.unwrap();
+        assert_eq!(source.headers, vec!["column_1", "column_2", "column_3"]);
+
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert!(matches!(rows[0].get("column_3"), Some(Value::String(s)) if s == "a|b"));
+        assert!(matches!(rows[1].get("column_2"), Some(Value::String(s)) if s == "Bob"));
+        assert!(!source.has_more());
+
+        // Supplied names replace the generated ones
+        let mut source = CsvSource::with_options(
+            temp_file.path().to_str().unwrap(),
+            &options(&[
+                ("delimiter", "|"),
+                ("quote", "'"),
+                ("comment", "#"),
+                ("has_header", "false"),
+                ("skip_rows", "2"),
+                ("skip_footer", "1"),
+                ("columns", "id, name, code"),
+            ]),
+        )
+        .unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(10).await.unwrap();
+        assert_eq!(schema.columns[1].name, "name");
+    }
+
+    #[tokio::test]
+    async fn test_csv_compressed_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.csv.gz");
+        let path = path.to_str().unwrap();
+
+        let mut target = CsvTarget::new(path).unwrap();
+        let schema = Schema {
+            columns: vec![crate::schema::Column {
+                name: "id".to_string(),
+                data_type: crate::schema::DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        target.create_table("orders", &schema).await.unwrap();
+        let rows: Vec<Row> = (0..1000)
+            .map(|i| HashMap::from([("id".to_string(), Value::Integer(i))]))
+            .collect();
+        target.write_batch(&rows).await.unwrap();
+        target.finalize().await.unwrap();
+
+        // gzip magic bytes
+        assert_eq!(&std::fs::read(path).unwrap()[..2], &[0x1f, 0x8b]);
+
+        let mut source = CsvSource::new(path).unwrap();
+        source.connect().await.unwrap();
+        assert_eq!(source.estimated_row_count().await.unwrap(), Some(1000));
+        let batch = source.read_batch(2000).await.unwrap();
+        assert_eq!(batch.len(), 1000);
+        assert_eq!(batch[999].get("id"), Some(&Value::Integer(999)));
+
+        // The option overrides the suffix
+        let mut source =
+            CsvSource::with_options(path, &options(&[("compression", "none")])).unwrap();
+        assert!(source.connect().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_csv_target_dialect() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut target = CsvTarget::with_options(
+            temp_file.path().to_str().unwrap(),
+            &options(&[
+                ("delimiter", "\\t"),
+                ("quote_style", "always"),
+                ("line_terminator", "crlf"),
+            ]),
+        )
+        .unwrap();
+
+        let schema = Schema {
+            columns: vec![
+                crate::schema::Column {
+                    name: "id".to_string(),
+                    data_type: crate::schema::DataType::Integer,
+                    nullable: false,
+                },
+                crate::schema::Column {
+                    name: "name".to_string(),
+                    data_type: crate::schema::DataType::String,
+                    nullable: false,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        target.create_table("test", &schema).await.unwrap();
+
+        let mut row = HashMap::new();
+        row.insert("id".to_string(), Value::Integer(1));
+        row.insert("name".to_string(), Value::String("Alice".to_string()));
+        target.write_batch(&[row]).await.unwrap();
+        target.finalize().await.unwrap();
+
+        let output = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert_eq!(output, "\"id\"\t\"name\"\r\n\"1\"\t\"Alice\"\r\n");
+    }
+
+    #[tokio::test]
+    async fn test_csv_typed_parsing() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "zip,id,amount,code,note").unwrap();
+        writeln!(temp_file, "01234,1e5,1.50,7,NA").unwrap();
+        writeln!(temp_file, "98765,2e5,-,8,fine").unwrap();
+        writeln!(temp_file, "12345,3e5,n/a,x9,\\N").unwrap();
+
+        let mut source = CsvSource::with_options(
+            temp_file.path().to_str().unwrap(),
+            &options(&[("null_values", "NA,\\N,-"), ("string_columns", "code")]),
+        )
+        .unwrap();
+        source.connect().await.unwrap();
+
+        let schema = source.infer_schema(2).await.unwrap();
+        let types: Vec<_> = schema.columns.iter().map(|c| &c.data_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                &DataType::String,
+                &DataType::String,
+                &DataType::Decimal,
+                &DataType::String,
+                &DataType::String
+            ]
+        );
+
+        let rows = source.read_batch(10).await.unwrap();
+        assert!(matches!(rows[0].get("zip"), Some(Value::String(s)) if s == "01234"));
+        assert!(matches!(rows[1].get("zip"), Some(Value::String(s)) if s == "98765"));
+        assert!(matches!(rows[0].get("id"), Some(Value::String(s)) if s == "1e5"));
+        assert!(matches!(rows[0].get("code"), Some(Value::String(s)) if s == "7"));
+        assert!(matches!(rows[0].get("note"), Some(Value::Null)));
+        assert!(matches!(rows[1].get("amount"), Some(Value::Null)));
+        assert!(matches!(rows[2].get("note"), Some(Value::Null)));
+        // Cells that don't fit the column type are kept for validation
+        assert!(matches!(rows[2].get("amount"), Some(Value::String(s)) if s == "n/a"));
+
+        // Declared types win over inferred ones
+        source.set_schema(&Schema {
+            columns: vec![crate::schema::Column {
+                name: "zip".to_string(),
+                data_type: DataType::Integer,
+                nullable: true,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        });
+        source.reset().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert!(matches!(rows[0].get("zip"), Some(Value::Integer(1234))));
+    }
+
+    #[tokio::test]
+    async fn test_csv_encodings() {
+        // A UTF-8 BOM must not end up in the first header name
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file
+            .write_all(b"\xEF\xBB\xBFid,name\n1,Alice\n")
+            .unwrap();
+        let mut source = CsvSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        assert_eq!(source.headers, vec!["id", "name"]);
+
+        // Latin-1 in, Windows-1252 out
+        let mut temp_file = NamedTempFile::new().unwrap();
+        temp_file.write_all(b"id,name\n1,Jos\xE9\n").unwrap();
+        let mut source = CsvSource::with_options(
+            temp_file.path().to_str().unwrap(),
+            &options(&[("encoding", "latin1")]),
+        )
+        .unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(10).await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert!(matches!(rows[0].get("name"), Some(Value::String(s)) if s == "José"));
+
+        let target_file = NamedTempFile::new().unwrap();
+        let mut target = CsvTarget::with_options(
+            target_file.path().to_str().unwrap(),
+            &options(&[("encoding", "windows-1252")]),
+        )
+        .unwrap();
+        target.create_table("test", &schema).await.unwrap();
+        target.write_batch(&rows).await.unwrap();
+        target.finalize().await.unwrap();
+        assert_eq!(
+            std::fs::read(target_file.path()).unwrap(),
+            b"id,name\n1,Jos\xE9\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_options_validation() {
+        let parsed = CsvOptions::from_options(&options(&[("delimiter", "tab")])).unwrap();
+        assert_eq!(parsed.delimiter, b'\t');
+        assert!(CsvOptions::from_options(&options(&[("delimiter", "||")])).is_err());
+        assert!(CsvOptions::from_options(&options(&[("has_header", "nope")])).is_err());
+        assert!(CsvOptions::from_options(&options(&[("quote_style", "sometimes")])).is_err());
+        // Keys for the protocol or the target table are ignored
+        assert!(CsvOptions::from_options(&options(&[("auth.bearer", "token")])).is_ok());
+    }
+
     #[tokio::test]
     async fn test_csv_column_order_debug() {
         // Create source CSV with specific header order
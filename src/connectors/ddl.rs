@@ -0,0 +1,407 @@
+use crate::schema::{Column, DataType, TableDdl, Value};
+
+/// SQL dialect used to render the parts of a [`TableDdl`] that differ between databases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SqlDialect {
+    Postgres,
+    Mysql,
+    /// SQL Server, also used for ODBC targets
+    Mssql,
+    Sqlite,
+    Duckdb,
+}
+
+impl SqlDialect {
+    pub(crate) fn quote(&self, identifier: &str) -> String {
+        match self {
+            SqlDialect::Mysql => format!("`{}`", identifier),
+            SqlDialect::Mssql => format!("[{}]", identifier),
+            _ => format!("\"{}\"", identifier),
+        }
+    }
+
+    /// Column type used for a data type when the schema file doesn't give one
+    pub(crate) fn column_type(&self, data_type: &DataType) -> &'static str {
+        match self {
+            SqlDialect::Postgres => match data_type {
+                DataType::String => "TEXT",
+                DataType::Integer => "BIGINT",
+                DataType::Decimal => "DECIMAL(18,6)",
+                DataType::Boolean => "BOOLEAN",
+                DataType::Date | DataType::DateTime => "TIMESTAMP WITH TIME ZONE",
+                DataType::Json => "JSONB", // PostgreSQL native JSON type
+                DataType::Null => "TEXT",  // Default to TEXT for null columns
+            },
+            SqlDialect::Mysql => match data_type {
+                DataType::Integer => "BIGINT",
+                DataType::Decimal => "DECIMAL(65,30)",
+                DataType::String => "TEXT",
+                DataType::Boolean => "BOOLEAN",
+                DataType::Date => "DATE",
+                DataType::DateTime => "DATETIME",
+                DataType::Json => "JSON", // MySQL native JSON type
+                DataType::Null => "TEXT",
+            },
+            SqlDialect::Mssql => match data_type {
+                DataType::Integer => "BIGINT",
+                DataType::Decimal => "DECIMAL(18,6)",
+                DataType::String => "NVARCHAR(MAX)",
+                DataType::Boolean => "BIT",
+                DataType::Date => "DATE",
+                DataType::DateTime => "DATETIME2",
+                DataType::Json => "NVARCHAR(MAX)", // MSSQL stores JSON as NVARCHAR
+                DataType::Null => "NVARCHAR(MAX)", // Default to string for null type
+            },
+            SqlDialect::Sqlite => match data_type {
+                DataType::Integer => "INTEGER",
+                DataType::Decimal => "REAL",
+                DataType::String => "TEXT",
+                DataType::Boolean => "INTEGER", // SQLite uses INTEGER for boolean
+                DataType::Date => "TEXT",
+                DataType::DateTime => "TEXT",
+                DataType::Json => "TEXT", // SQLite stores JSON as TEXT
+                DataType::Null => "TEXT",
+            },
+            SqlDialect::Duckdb => match data_type {
+                DataType::Integer => "BIGINT",
+                DataType::Decimal => "DOUBLE",
+                DataType::String => "VARCHAR",
+                DataType::Boolean => "BOOLEAN",
+                DataType::Date => "DATE",
+                DataType::DateTime => "TIMESTAMP",
+                DataType::Json => "JSON", // DuckDB has native JSON type
+                DataType::Null => "VARCHAR",
+            },
+        }
+    }
+
+    pub(crate) fn string_literal(&self, value: &str) -> String {
+        match self {
+            // MySQL treats backslashes as escapes, so escape them the way mysqldump does
+            SqlDialect::Mysql => format!(
+                "'{}'",
+                value
+                    .replace('\\', "\\\\")
+                    .replace('\'', "''")
+                    .replace('\0', "\\0")
+            ),
+            SqlDialect::Mssql => format!("N'{}'", value.replace('\'', "''")),
+            // PostgreSQL text can't hold NUL characters
+            SqlDialect::Postgres => format!("'{}'", value.replace('\0', "").replace('\'', "''")),
+            _ => format!("'{}'", value.replace('\'', "''")),
+        }
+    }
+
+    /// Literal for a value in an INSERT statement, for a column of `data_type`
+    pub(crate) fn value_literal(&self, value: &Value, data_type: &DataType) -> String {
+        match value {
+            Value::Null => "NULL".to_string(),
+            Value::String(s) => self.string_literal(s),
+            Value::Integer(i) => i.to_string(),
+            Value::Decimal(d) => d.to_string(),
+            Value::Boolean(b) => match self {
+                SqlDialect::Mssql | SqlDialect::Sqlite => if *b { "1" } else { "0" }.to_string(),
+                _ => if *b { "TRUE" } else { "FALSE" }.to_string(),
+            },
+            Value::Date(dt) => {
+                let text = match (self, data_type) {
+                    (SqlDialect::Postgres | SqlDialect::Sqlite, _) => dt.to_rfc3339(),
+                    (_, DataType::Date) => dt.format("%Y-%m-%d").to_string(),
+                    (SqlDialect::Mssql, _) => dt.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+                    _ => dt.format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+                };
+                self.string_literal(&text)
+            }
+            Value::Json(j) => self.string_literal(&j.to_string()),
+        }
+    }
+
+    fn quote_list(&self, columns: &[String]) -> String {
+        columns
+            .iter()
+            .map(|c| self.quote(c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// ` NULL` or ` NOT NULL` for a column definition. Primary key columns are
+    /// always NOT NULL, since SQL Server rejects a key on a column declared NULL.
+    pub(crate) fn null_clause(&self, ddl: &TableDdl, column: &Column) -> &'static str {
+        if !column.nullable || ddl.primary_key.contains(&column.name) {
+            " NOT NULL"
+        } else if *self == SqlDialect::Mssql {
+            " NULL"
+        } else {
+            ""
+        }
+    }
+
+    /// DEFAULT (and for MySQL, COMMENT) clauses appended to a column definition
+    pub(crate) fn column_clauses(&self, ddl: &TableDdl, column: &str) -> String {
+        let mut clauses = String::new();
+        if let Some(default) = ddl.column_defaults.get(column) {
+            clauses.push_str(&format!(" DEFAULT {}", default));
+        }
+        if *self == SqlDialect::Mysql {
+            if let Some(comment) = ddl.column_comments.get(column) {
+                clauses.push_str(&format!(" COMMENT {}", self.string_literal(comment)));
+            }
+        }
+        clauses
+    }
+
+    /// PRIMARY KEY and UNIQUE constraints to append to the column definitions
+    pub(crate) fn table_constraints(&self, ddl: &TableDdl) -> Vec<String> {
+        let mut constraints = Vec::new();
+        if !ddl.primary_key.is_empty() {
+            constraints.push(format!(
+                "PRIMARY KEY ({})",
+                self.quote_list(&ddl.primary_key)
+            ));
+        }
+        for unique in &ddl.unique {
+            constraints.push(format!("UNIQUE ({})", self.quote_list(unique)));
+        }
+        constraints
+    }
+
+    /// Table options after the closing parenthesis of CREATE TABLE
+    pub(crate) fn table_options(&self, ddl: &TableDdl) -> String {
+        match (self, &ddl.comment) {
+            (SqlDialect::Mysql, Some(comment)) => {
+                format!(" COMMENT={}", self.string_literal(comment))
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// Statements that set table and column comments after CREATE TABLE.
+    /// MySQL comments are inline and SQLite has no comments, so both return nothing.
+    pub(crate) fn comment_statements(&self, ddl: &TableDdl, table_name: &str) -> Vec<String> {
+        let mut column_comments: Vec<(&String, &String)> = ddl.column_comments.iter().collect();
+        column_comments.sort();
+
+        match self {
+            SqlDialect::Postgres | SqlDialect::Duckdb => {
+                let table = self.quote(table_name);
+                ddl.comment
+                    .iter()
+                    .map(|comment| {
+                        format!(
+                            "COMMENT ON TABLE {} IS {}",
+                            table,
+                            self.string_literal(comment)
+                        )
+                    })
+                    .chain(column_comments.into_iter().map(|(column, comment)| {
+                        format!(
+                            "COMMENT ON COLUMN {}.{} IS {}",
+                            table,
+                            self.quote(column),
+                            self.string_literal(comment)
+                        )
+                    }))
+                    .collect()
+            }
+            SqlDialect::Mssql => {
+                let property = |comment: &str, column: Option<&str>| {
+                    let mut sql = format!(
+                        "EXEC sp_addextendedproperty @name = N'MS_Description', @value = {}, \
+                         @level0type = N'SCHEMA', @level0name = N'dbo', \
+                         @level1type = N'TABLE', @level1name = {}",
+                        self.string_literal(comment),
+                        self.string_literal(table_name)
+                    );
+                    if let Some(column) = column {
+                        sql.push_str(&format!(
+                            ", @level2type = N'COLUMN', @level2name = {}",
+                            self.string_literal(column)
+                        ));
+                    }
+                    sql
+                };
+                ddl.comment
+                    .iter()
+                    .map(|comment| property(comment, None))
+                    .chain(
+                        column_comments
+                            .into_iter()
+                            .map(|(column, comment)| property(comment, Some(column))),
+                    )
+                    .collect()
+            }
+            SqlDialect::Mysql | SqlDialect::Sqlite => Vec::new(),
+        }
+    }
+
+    /// CREATE INDEX statements, paired with the index name.
+    /// MySQL has no `IF NOT EXISTS` for indexes, so its connector checks the names first.
+    pub(crate) fn index_statements(
+        &self,
+        ddl: &TableDdl,
+        table_name: &str,
+    ) -> Vec<(String, String)> {
+        ddl.indexes
+            .iter()
+            .map(|index| {
+                let name = index.name_for(table_name);
+                let unique = if index.unique { "UNIQUE " } else { "" };
+                let columns = self.quote_list(&index.columns);
+                let table = self.quote(table_name);
+
+                let sql = match self {
+                    SqlDialect::Mysql => format!(
+                        "CREATE {}INDEX {} ON {} ({})",
+                        unique,
+                        self.quote(&name),
+                        table,
+                        columns
+                    ),
+                    SqlDialect::Mssql => format!(
+                        "IF NOT EXISTS (SELECT * FROM sys.indexes WHERE name = {} AND object_id = OBJECT_ID({})) \
+                         CREATE {}INDEX {} ON {} ({})",
+                        self.string_literal(&name),
+                        self.string_literal(table_name),
+                        unique,
+                        self.quote(&name),
+                        table,
+                        columns
+                    ),
+                    _ => format!(
+                        "CREATE {}INDEX IF NOT EXISTS {} ON {} ({})",
+                        unique,
+                        self.quote(&name),
+                        table,
+                        columns
+                    ),
+                };
+                (name, sql)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::IndexDefinition;
+
+    fn ddl() -> TableDdl {
+        let mut ddl = TableDdl {
+            primary_key: vec!["id".to_string()],
+            unique: vec![vec!["email".to_string()]],
+            indexes: vec![IndexDefinition {
+                name: None,
+                columns: vec!["last_name".to_string(), "first_name".to_string()],
+                unique: false,
+            }],
+            comment: Some("Customer master".to_string()),
+            ..Default::default()
+        };
+        ddl.column_defaults
+            .insert("created_at".to_string(), "CURRENT_TIMESTAMP".to_string());
+        ddl.column_comments
+            .insert("email".to_string(), "Owner's address".to_string());
+        ddl
+    }
+
+    #[test]
+    fn test_constraints_per_dialect() {
+        let ddl = ddl();
+        assert_eq!(
+            SqlDialect::Postgres.table_constraints(&ddl),
+            vec!["PRIMARY KEY (\"id\")", "UNIQUE (\"email\")"]
+        );
+        assert_eq!(
+            SqlDialect::Mysql.table_constraints(&ddl),
+            vec!["PRIMARY KEY (`id`)", "UNIQUE (`email`)"]
+        );
+        assert_eq!(
+            SqlDialect::Mssql.table_constraints(&ddl),
+            vec!["PRIMARY KEY ([id])", "UNIQUE ([email])"]
+        );
+    }
+
+    #[test]
+    fn test_null_clause() {
+        let ddl = ddl();
+        let column = |name: &str, nullable: bool| Column {
+            name: name.to_string(),
+            data_type: DataType::String,
+            nullable,
+        };
+        assert_eq!(
+            SqlDialect::Mssql.null_clause(&ddl, &column("id", true)),
+            " NOT NULL"
+        );
+        assert_eq!(
+            SqlDialect::Mssql.null_clause(&ddl, &column("email", true)),
+            " NULL"
+        );
+        assert_eq!(
+            SqlDialect::Postgres.null_clause(&ddl, &column("email", true)),
+            ""
+        );
+        assert_eq!(
+            SqlDialect::Sqlite.null_clause(&ddl, &column("email", false)),
+            " NOT NULL"
+        );
+    }
+
+    #[test]
+    fn test_column_clauses() {
+        let ddl = ddl();
+        assert_eq!(
+            SqlDialect::Sqlite.column_clauses(&ddl, "created_at"),
+            " DEFAULT CURRENT_TIMESTAMP"
+        );
+        assert_eq!(SqlDialect::Postgres.column_clauses(&ddl, "email"), "");
+        assert_eq!(
+            SqlDialect::Mysql.column_clauses(&ddl, "email"),
+            " COMMENT 'Owner''s address'"
+        );
+        assert_eq!(
+            SqlDialect::Mysql.table_options(&ddl),
+            " COMMENT='Customer master'"
+        );
+        assert_eq!(SqlDialect::Postgres.table_options(&ddl), "");
+    }
+
+    #[test]
+    fn test_comment_statements() {
+        let ddl = ddl();
+        assert_eq!(
+            SqlDialect::Postgres.comment_statements(&ddl, "customers"),
+            vec![
+                "COMMENT ON TABLE \"customers\" IS 'Customer master'",
+                "COMMENT ON COLUMN \"customers\".\"email\" IS 'Owner''s address'",
+            ]
+        );
+        let mssql = SqlDialect::Mssql.comment_statements(&ddl, "customers");
+        assert_eq!(mssql.len(), 2);
+        assert!(mssql[1].contains("@level2name = N'email'"));
+        assert!(SqlDialect::Mysql
+            .comment_statements(&ddl, "customers")
+            .is_empty());
+        assert!(SqlDialect::Sqlite
+            .comment_statements(&ddl, "customers")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_index_statements() {
+        let ddl = ddl();
+        let (name, sql) = &SqlDialect::Duckdb.index_statements(&ddl, "customers")[0];
+        assert_eq!(name, "idx_customers_last_name_first_name");
+        assert_eq!(
+            sql,
+            "CREATE INDEX IF NOT EXISTS \"idx_customers_last_name_first_name\" ON \"customers\" (\"last_name\", \"first_name\")"
+        );
+
+        let (_, sql) = &SqlDialect::Mysql.index_statements(&ddl, "customers")[0];
+        assert!(!sql.contains("IF NOT EXISTS"));
+
+        let (_, sql) = &SqlDialect::Mssql.index_statements(&ddl, "customers")[0];
+        assert!(sql.starts_with("IF NOT EXISTS (SELECT * FROM sys.indexes"));
+    }
+}
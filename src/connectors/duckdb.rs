@@ -7,8 +7,8 @@ use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 use crate::{
-    connectors::{Source, Target},
-    schema::{Column as SchemaColumn, DataType, Row, Schema, Value},
+    connectors::{ddl::SqlDialect, Source, Target},
+    schema::{Column as SchemaColumn, DataType, Row, Schema, TableDdl, Value},
     Result, TinyEtlError,
 };
 
@@ -206,6 +206,11 @@ impl Source for DuckdbSource {
         })
     }
 
+    async fn catalog_schema(&mut self) -> Result<Option<Schema>> {
+        // The schema is read with DESCRIBE, not sampled rows
+        self.infer_schema(0).await.map(Some)
+    }
+
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
         if self.connection.is_none() {
             self.connect().await?;
@@ -359,6 +364,7 @@ pub struct DuckdbTarget {
     connection: Option<Arc<Mutex<Connection>>>,
     table_name: String,
     schema: Option<Schema>, // Cache the schema to avoid re-inference
+    ddl: TableDdl,
 }
 
 impl DuckdbTarget {
@@ -386,6 +392,7 @@ impl DuckdbTarget {
             connection: None,
             table_name: table.to_string(),
             schema: None,
+            ddl: TableDdl::default(),
         })
     }
 
@@ -393,17 +400,17 @@ impl DuckdbTarget {
         Ok(PathBuf::from(&self.connection_string))
     }
 
-    fn map_data_type_to_duckdb(&self, data_type: &DataType) -> &'static str {
-        match data_type {
-            DataType::Integer => "BIGINT",
-            DataType::Decimal => "DOUBLE",
-            DataType::String => "VARCHAR",
-            DataType::Boolean => "BOOLEAN",
-            DataType::Date => "DATE",
-            DataType::DateTime => "TIMESTAMP",
-            DataType::Json => "JSON", // DuckDB has native JSON type
-            DataType::Null => "VARCHAR",
+    fn execute_ddl(conn: &Connection, statements: &[String]) -> Result<()> {
+        for statement in statements {
+            conn.execute(statement, []).map_err(|e| {
+                TinyEtlError::DataTransfer(format!("Failed to execute '{}': {}", statement, e))
+            })?;
         }
+        Ok(())
+    }
+
+    fn map_data_type_to_duckdb(&self, data_type: &DataType) -> &'static str {
+        SqlDialect::Duckdb.column_type(data_type)
     }
 }
 
@@ -451,15 +458,20 @@ impl Target for DuckdbTarget {
         self.schema = Some(schema.clone());
 
         // Build CREATE TABLE statement with IF NOT EXISTS (append-first philosophy)
-        let column_definitions: Vec<String> = schema
+        let mut column_definitions: Vec<String> = schema
             .columns
             .iter()
             .map(|col| {
-                let duckdb_type = self.map_data_type_to_duckdb(&col.data_type);
+                let duckdb_type = self
+                    .ddl
+                    .sql_type(col)
+                    .unwrap_or_else(|| self.map_data_type_to_duckdb(&col.data_type));
                 let nullable = if col.nullable { "" } else { " NOT NULL" };
-                format!("\"{}\" {}{}", col.name, duckdb_type, nullable)
+                let clauses = SqlDialect::Duckdb.column_clauses(&self.ddl, &col.name);
+                format!("\"{}\" {}{}{}", col.name, duckdb_type, nullable, clauses)
             })
             .collect();
+        column_definitions.extend(SqlDialect::Duckdb.table_constraints(&self.ddl));
 
         // Use CREATE TABLE IF NOT EXISTS to support append-first philosophy
         let create_sql = format!(
@@ -471,6 +483,17 @@ impl Target for DuckdbTarget {
         conn.execute(&create_sql, [])
             .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to create table: {}", e)))?;
 
+        let mut statements = SqlDialect::Duckdb.comment_statements(&self.ddl, &actual_table_name);
+        if !self.ddl.indexes_after_load {
+            statements.extend(
+                SqlDialect::Duckdb
+                    .index_statements(&self.ddl, &actual_table_name)
+                    .into_iter()
+                    .map(|(_, sql)| sql),
+            );
+        }
+        Self::execute_ddl(&conn, &statements)?;
+
         Ok(())
     }
 
@@ -504,7 +527,18 @@ impl Target for DuckdbTarget {
     }
 
     async fn finalize(&mut self) -> Result<()> {
-        // DuckDB doesn't require explicit finalization
+        // Indexes are cheaper to build once than to maintain row by row
+        if self.ddl.indexes_after_load {
+            if let Some(conn) = &self.connection {
+                let conn = conn.lock().unwrap();
+                let statements: Vec<String> = SqlDialect::Duckdb
+                    .index_statements(&self.ddl, &self.table_name)
+                    .into_iter()
+                    .map(|(_, sql)| sql)
+                    .collect();
+                Self::execute_ddl(&conn, &statements)?;
+            }
+        }
         Ok(())
     }
 
@@ -547,6 +581,10 @@ impl Target for DuckdbTarget {
         // DuckDB databases support appending new rows
         true
     }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
 }
 
 #[cfg(test)]
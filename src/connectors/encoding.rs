@@ -0,0 +1,258 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+
+use crate::{Result, TinyEtlError};
+
+/// Bytes sampled for `encoding: auto` detection
+const DETECTION_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Character encoding of a text file, from the `encoding` option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEncoding {
+    /// UTF-8, or UTF-16 when the file starts with a UTF-16 byte order mark
+    Default,
+    /// Byte order mark, then a statistical guess over the first 64 KiB
+    Auto,
+    Explicit(&'static Encoding),
+}
+
+impl TextEncoding {
+    /// Parse the `encoding` option: `auto` or a WHATWG label such as `latin1`,
+    /// `windows-1252`, `utf-16le` or `shift_jis`
+    pub fn from_options(options: &HashMap<String, String>) -> Result<Self> {
+        match options.get("encoding").map(|e| e.trim()) {
+            None | Some("") => Ok(TextEncoding::Default),
+            Some(label) if label.eq_ignore_ascii_case("auto") => Ok(TextEncoding::Auto),
+            Some(label) => Encoding::for_label(label.as_bytes())
+                .map(TextEncoding::Explicit)
+                .ok_or_else(|| {
+                    TinyEtlError::Configuration(format!("Unknown encoding '{}'", label))
+                }),
+        }
+    }
+
+    /// Wrap a reader so it yields UTF-8, with any byte order mark removed
+    pub fn decode<R>(&self, mut reader: R) -> Result<Box<dyn Read + Send + Sync>>
+    where
+        R: Read + Send + Sync + 'static,
+    {
+        let mut builder = DecodeReaderBytesBuilder::new();
+        // A BOM always wins over the configured or detected encoding
+        builder
+            .strip_bom(true)
+            .bom_override(true)
+            .utf8_passthru(true);
+
+        match self {
+            TextEncoding::Default => Ok(Box::new(builder.build(reader))),
+            TextEncoding::Explicit(encoding) => {
+                Ok(Box::new(builder.encoding(Some(encoding)).build(reader)))
+            }
+            TextEncoding::Auto => {
+                let mut sample = Vec::with_capacity(DETECTION_SAMPLE_SIZE);
+                (&mut reader)
+                    .take(DETECTION_SAMPLE_SIZE as u64)
+                    .read_to_end(&mut sample)?;
+
+                let encoding = Self::detect(&sample);
+                tracing::debug!("Detected text encoding: {}", encoding.name());
+                let reader = Cursor::new(sample).chain(reader);
+                Ok(Box::new(builder.encoding(Some(encoding)).build(reader)))
+            }
+        }
+    }
+
+    fn detect(sample: &[u8]) -> &'static Encoding {
+        if let Some((encoding, _)) = Encoding::for_bom(sample) {
+            return encoding;
+        }
+        // The sample may end in the middle of a multi-byte character
+        match std::str::from_utf8(sample) {
+            Ok(_) => return UTF_8,
+            Err(e) if e.error_len().is_none() => return UTF_8,
+            Err(_) => {}
+        }
+        let mut detector = chardetng::EncodingDetector::new();
+        detector.feed(sample, sample.len() < DETECTION_SAMPLE_SIZE);
+        detector.guess(None, false)
+    }
+
+    /// Wrap a writer so UTF-8 written to it is stored in this encoding
+    pub fn encode<W: Write>(&self, writer: W) -> EncodingWriter<W> {
+        match self {
+            TextEncoding::Explicit(encoding) => EncodingWriter::new(writer, encoding),
+            _ => EncodingWriter::new(writer, UTF_8),
+        }
+    }
+}
+
+/// Transcodes UTF-8 to a target encoding, passing UTF-8 through as it is.
+/// encoding_rs has no UTF-16 encoders, so UTF-16 is encoded here, starting
+/// with a byte order mark.
+pub struct EncodingWriter<W: Write> {
+    inner: W,
+    encoding: &'static Encoding,
+    /// Trailing bytes of a character split across `write` calls
+    pending: Vec<u8>,
+    bom_written: bool,
+    unmappable_warned: bool,
+}
+
+impl<W: Write> EncodingWriter<W> {
+    fn new(inner: W, encoding: &'static Encoding) -> Self {
+        Self {
+            inner,
+            encoding,
+            pending: Vec::new(),
+            bom_written: false,
+            unmappable_warned: false,
+        }
+    }
+
+    /// The underlying writer, to finish it once everything is written
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn write_str(&mut self, text: &str) -> std::io::Result<()> {
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            let little_endian = self.encoding == UTF_16LE;
+            let mut bytes = Vec::with_capacity(text.len() * 2 + 2);
+            let units = (!self.bom_written)
+                .then_some(0xFEFF)
+                .into_iter()
+                .chain(text.encode_utf16());
+            for unit in units {
+                if little_endian {
+                    bytes.extend_from_slice(&unit.to_le_bytes());
+                } else {
+                    bytes.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+            self.bom_written = true;
+            return self.inner.write_all(&bytes);
+        }
+
+        let (bytes, _, unmappable) = self.encoding.encode(text);
+        if unmappable && !self.unmappable_warned {
+            tracing::warn!(
+                "Some characters cannot be represented in {} and were written as numeric character references",
+                self.encoding.name()
+            );
+            self.unmappable_warned = true;
+        }
+        self.inner.write_all(&bytes)
+    }
+}
+
+impl<W: Write> Write for EncodingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.encoding == UTF_8 {
+            return self.inner.write(buf);
+        }
+        self.pending.extend_from_slice(buf);
+        let valid_up_to = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+            }
+        };
+
+        let rest = self.pending.split_off(valid_up_to);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        // Validated above
+        let text = String::from_utf8(complete)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.write_str(&text)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_all(encoding: TextEncoding, bytes: &[u8]) -> String {
+        let mut text = String::new();
+        encoding
+            .decode(Cursor::new(bytes.to_vec()))
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn test_from_options() {
+        let options = |value: &str| HashMap::from([("encoding".to_string(), value.to_string())]);
+        assert_eq!(
+            TextEncoding::from_options(&HashMap::new()).unwrap(),
+            TextEncoding::Default
+        );
+        assert_eq!(
+            TextEncoding::from_options(&options("AUTO")).unwrap(),
+            TextEncoding::Auto
+        );
+        assert_eq!(
+            TextEncoding::from_options(&options("latin1")).unwrap(),
+            TextEncoding::Explicit(encoding_rs::WINDOWS_1252)
+        );
+        assert!(TextEncoding::from_options(&options("klingon")).is_err());
+    }
+
+    #[test]
+    fn test_decode_strips_bom() {
+        assert_eq!(
+            decode_all(TextEncoding::Default, b"\xEF\xBB\xBFid,name\n"),
+            "id,name\n"
+        );
+        // UTF-16LE with BOM
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain("id,näme\n".encode_utf16().flat_map(|u| u.to_le_bytes()))
+            .collect();
+        assert_eq!(decode_all(TextEncoding::Default, &utf16), "id,näme\n");
+        assert_eq!(decode_all(TextEncoding::Auto, &utf16), "id,näme\n");
+    }
+
+    #[test]
+    fn test_decode_latin1() {
+        let latin1 = b"name\nJos\xE9 M\xFCller\n";
+        let explicit = TextEncoding::Explicit(encoding_rs::WINDOWS_1252);
+        assert_eq!(decode_all(explicit, latin1), "name\nJosé Müller\n");
+        assert_eq!(
+            decode_all(TextEncoding::Auto, latin1),
+            "name\nJosé Müller\n"
+        );
+        assert_eq!(
+            decode_all(TextEncoding::Auto, "name\nJosé\n".as_bytes()),
+            "name\nJosé\n"
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut output = Vec::new();
+        {
+            let mut writer = EncodingWriter::new(&mut output, encoding_rs::WINDOWS_1252);
+            let text = "José".as_bytes();
+            // Split inside the two-byte 'é'
+            writer.write_all(&text[..4]).unwrap();
+            writer.write_all(&text[4..]).unwrap();
+        }
+        assert_eq!(output, b"Jos\xE9");
+
+        let mut output = Vec::new();
+        EncodingWriter::new(&mut output, UTF_16LE)
+            .write_all(b"id")
+            .unwrap();
+        assert_eq!(output, vec![0xFF, 0xFE, b'i', 0, b'd', 0]);
+    }
+}
@@ -0,0 +1,1052 @@
+use async_trait::async_trait;
+use calamine::{open_workbook_auto, Data, Range, Reader};
+use rust_decimal::Decimal;
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::{
+    connectors::{csv::CsvSource, Source, Target},
+    date_parser::DateParser,
+    schema::{Column, DataType, Row, Schema, SchemaInferer, Value},
+    Result, TinyEtlError,
+};
+
+/// Column holding the sheet name when every sheet is read
+const SHEET_COLUMN: &str = "_sheet";
+
+const EXTENSIONS: [&str; 5] = [".xlsx", ".xlsm", ".xlsb", ".xls", ".ods"];
+
+/// Whether the path is a workbook the Excel source reads
+pub fn is_workbook(path: &str) -> bool {
+    let path = path.to_lowercase();
+    EXTENSIONS.iter().any(|extension| path.ends_with(extension))
+}
+
+/// Reads worksheets of `.xlsx`, `.xlsm`, `.xlsb`, `.xls` and `.ods` workbooks.
+/// Sheets are loaded whole by `connect`, as the formats can't be streamed.
+pub struct ExcelSource {
+    file_path: PathBuf,
+    /// Sheet name or zero-based index from the `sheet` option, `*` for all
+    sheet: Option<String>,
+    range: Option<CellRange>,
+    /// One-based row of the column names, 0 for none, from `header_row`
+    header_row: Option<u32>,
+    sheets: Vec<Sheet>,
+    /// Index of the sheet being read and the next row of it
+    position: (usize, u32),
+    has_more: bool,
+    /// Column types from `set_schema`, e.g. a schema file
+    declared_types: HashMap<String, DataType>,
+    /// Column types from the last `infer_schema`
+    inferred_types: HashMap<String, DataType>,
+}
+
+/// Cells to read, from the `range` option, as zero-based (row, column).
+/// Without an end, the range runs to the last used cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellRange {
+    start: (u32, u32),
+    end: Option<(u32, u32)>,
+}
+
+impl CellRange {
+    fn parse(range: &str) -> Result<Self> {
+        let invalid = || {
+            TinyEtlError::Configuration(format!(
+                "Invalid range '{}': expected cells such as A3:F200, or A3 to read from there on",
+                range
+            ))
+        };
+        let (start, end) = match range.split_once(':') {
+            Some((start, end)) => (start, Some(end)),
+            None => (range, None),
+        };
+        let start = parse_cell(start.trim()).ok_or_else(invalid)?;
+        let end = match end {
+            Some(end) => Some(parse_cell(end.trim()).ok_or_else(invalid)?),
+            None => None,
+        };
+        if end.is_some_and(|end| end.0 < start.0 || end.1 < start.1) {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// A cell reference such as `B3` as zero-based (row, column)
+fn parse_cell(reference: &str) -> Option<(u32, u32)> {
+    let digits = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, row) = reference.split_at(digits);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let column = letters.chars().try_fold(0u32, |column, c| {
+        let digit = c.to_ascii_uppercase() as u32 - 'A' as u32 + 1;
+        column.checked_mul(26)?.checked_add(digit)
+    })?;
+    let row: u32 = row.parse().ok()?;
+    if row == 0 {
+        return None;
+    }
+    Some((row - 1, column - 1))
+}
+
+/// A loaded worksheet and the part of it holding records
+struct Sheet {
+    name: String,
+    cells: Range<Data>,
+    headers: Vec<String>,
+    first_column: u32,
+    /// Rows of records, as a half-open range of absolute row numbers
+    rows: (u32, u32),
+}
+
+impl Sheet {
+    fn new(
+        name: String,
+        cells: Range<Data>,
+        range: Option<CellRange>,
+        header_row: Option<u32>,
+    ) -> Self {
+        let (Some(used_start), Some(used_end)) = (cells.start(), cells.end()) else {
+            return Self {
+                name,
+                cells,
+                headers: Vec::new(),
+                first_column: 0,
+                rows: (0, 0),
+            };
+        };
+        let start = range.map_or(used_start, |r| r.start);
+        let end = match range.and_then(|r| r.end) {
+            Some(end) => (end.0.min(used_end.0), end.1.min(used_end.1)),
+            None => used_end,
+        };
+
+        let header = match header_row {
+            Some(0) => None,
+            Some(row) => Some(row - 1),
+            None => Some(start.0),
+        };
+        let first_row = header.map_or(start.0, |h| start.0.max(h + 1));
+
+        let mut headers: Vec<String> = Vec::new();
+        for (i, column) in (start.1..=end.1.max(start.1)).enumerate() {
+            let name = header
+                .and_then(|row| cells.get_value((row, column)))
+                .map(|cell| cell.to_string().trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("column_{}", i + 1));
+            // Repeated names get a numeric suffix
+            let mut unique = name.clone();
+            let mut n = 1;
+            while headers.contains(&unique) {
+                n += 1;
+                unique = format!("{}_{}", name, n);
+            }
+            headers.push(unique);
+        }
+
+        Self {
+            name,
+            cells,
+            headers,
+            first_column: start.1,
+            rows: (first_row, (end.0 + 1).max(first_row)),
+        }
+    }
+
+    /// The record in a row, or None when every cell is empty
+    fn row(&self, row: u32, with_sheet_name: bool) -> Option<Row> {
+        let mut record = Row::new();
+        let mut empty = true;
+        for (column, header) in (self.first_column..).zip(&self.headers) {
+            let value = self
+                .cells
+                .get_value((row, column))
+                .map_or(Value::Null, cell_value);
+            empty &= value == Value::Null;
+            record.insert(header.clone(), value);
+        }
+        if empty {
+            return None;
+        }
+        if with_sheet_name {
+            record.insert(SHEET_COLUMN.to_string(), Value::String(self.name.clone()));
+        }
+        Some(record)
+    }
+}
+
+/// Numbers without a fraction are integers, as spreadsheets store every
+/// number as a float. Date cells become dates, or text for times of day
+/// and durations. Error cells such as `#N/A` are null.
+fn cell_value(cell: &Data) -> Value {
+    match cell {
+        Data::Int(i) => Value::Integer(*i),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 9_007_199_254_740_992.0 => {
+            Value::Integer(*f as i64)
+        }
+        Data::Float(f) => match Decimal::try_from(*f) {
+            Ok(d) => Value::Decimal(d.normalize()),
+            Err(_) => Value::String(f.to_string()),
+        },
+        Data::String(s) if s.is_empty() => Value::Null,
+        Data::String(s) => Value::String(s.clone()),
+        Data::Bool(b) => Value::Boolean(*b),
+        Data::DateTime(dt) if dt.is_duration() => {
+            let seconds = (dt.as_f64() * 86_400.0).round() as i64;
+            Value::String(format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds % 3600 / 60,
+                seconds % 60
+            ))
+        }
+        Data::DateTime(dt) => match dt.as_datetime() {
+            // Times without a date are stored as a fraction of a day
+            Some(datetime) if dt.as_f64() < 1.0 => {
+                Value::String(datetime.format("%H:%M:%S%.f").to_string())
+            }
+            Some(datetime) => Value::Date(datetime.and_utc()),
+            None => Value::String(dt.as_f64().to_string()),
+        },
+        Data::DateTimeIso(s) => {
+            DateParser::try_parse(s).unwrap_or_else(|| Value::String(s.clone()))
+        }
+        Data::DurationIso(s) => Value::String(s.clone()),
+        Data::Error(_) | Data::Empty => Value::Null,
+    }
+}
+
+/// A cell's value as a column of `data_type`, so every value of a column has
+/// its type: whole numbers in a decimal column are decimals, and numbers,
+/// booleans and dates in a text column are text
+fn coerce(value: Value, data_type: &DataType) -> Value {
+    match (value, data_type) {
+        (Value::Integer(i), DataType::Decimal) => Value::Decimal(Decimal::from(i)),
+        (Value::Decimal(d), DataType::Integer) if d.fract().is_zero() => {
+            i64::try_from(d).map_or(Value::Decimal(d), Value::Integer)
+        }
+        (Value::Integer(i), DataType::String) => Value::String(i.to_string()),
+        (Value::Decimal(d), DataType::String) => Value::String(d.to_string()),
+        (Value::Boolean(b), DataType::String) => Value::String(b.to_string()),
+        (Value::Date(dt), DataType::String) => Value::String(dt.to_rfc3339()),
+        (Value::String(s), DataType::Integer | DataType::Decimal | DataType::Boolean) => {
+            CsvSource::parse_as(&s, Some(data_type))
+        }
+        (value, _) => value,
+    }
+}
+
+impl ExcelSource {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `sheet` is a sheet name or zero-based index, or `*` to read every sheet
+    /// with its name in a `_sheet` column. `range` limits the cells read, e.g.
+    /// `A3:F200`, and `header_row` is the one-based row holding column names
+    /// (0 for none), by default the first row of the range.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let sheet = options
+            .get("sheet")
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+        let range = match options.get("range").map(|s| s.trim()) {
+            None | Some("") => None,
+            Some(range) => Some(CellRange::parse(range)?),
+        };
+        let header_row = match options.get("header_row").map(|s| s.trim()) {
+            None | Some("") => None,
+            Some(row) => Some(row.parse::<u32>().map_err(|_| {
+                TinyEtlError::Configuration(format!(
+                    "Invalid header_row '{}': expected a row number, or 0 for none",
+                    row
+                ))
+            })?),
+        };
+
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            sheet,
+            range,
+            header_row,
+            sheets: Vec::new(),
+            position: (0, 0),
+            has_more: true,
+            declared_types: HashMap::new(),
+            inferred_types: HashMap::new(),
+        })
+    }
+
+    fn all_sheets(&self) -> bool {
+        self.sheet.as_deref() == Some("*")
+    }
+
+    /// Load the selected sheets from the workbook
+    fn load_sheets(&self) -> Result<Vec<Sheet>> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "Excel file not found: {}",
+                self.file_path.display()
+            )));
+        }
+        let mut workbook = open_workbook_auto(&self.file_path).map_err(|e| {
+            TinyEtlError::Connection(format!(
+                "Cannot open workbook {}: {}",
+                self.file_path.display(),
+                e
+            ))
+        })?;
+
+        let sheet_names = workbook.sheet_names();
+        let selected: Vec<String> = match self.sheet.as_deref() {
+            Some("*") => sheet_names.clone(),
+            Some(sheet) => {
+                let name = if sheet_names.iter().any(|name| name == sheet) {
+                    Some(sheet.to_string())
+                } else {
+                    sheet
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| sheet_names.get(index).cloned())
+                };
+                vec![name.ok_or_else(|| {
+                    TinyEtlError::Configuration(format!(
+                        "Sheet '{}' not found in {}. Sheets: {}",
+                        sheet,
+                        self.file_path.display(),
+                        sheet_names.join(", ")
+                    ))
+                })?]
+            }
+            None => sheet_names.first().cloned().into_iter().collect(),
+        };
+
+        selected
+            .into_iter()
+            .map(|name| {
+                let cells = workbook.worksheet_range(&name).map_err(|e| {
+                    TinyEtlError::DataTransfer(format!("Cannot read sheet '{}': {}", name, e))
+                })?;
+                Ok(Sheet::new(name, cells, self.range, self.header_row))
+            })
+            .collect()
+    }
+
+    fn rewind(&mut self) {
+        self.position = (0, self.sheets.first().map_or(0, |sheet| sheet.rows.0));
+        self.has_more = true;
+    }
+}
+
+#[async_trait]
+impl Source for ExcelSource {
+    async fn connect(&mut self) -> Result<()> {
+        self.sheets = self.load_sheets()?;
+        self.rewind();
+        Ok(())
+    }
+
+    async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
+        if self.sheets.is_empty() {
+            self.connect().await?;
+        }
+        // Guess afresh rather than reading as the previous inference's types
+        self.inferred_types.clear();
+        let sample = self.read_batch(sample_size).await?;
+        self.rewind();
+
+        // Columns in sheet order, with the sheet name first
+        let mut names: Vec<&str> = Vec::new();
+        if self.all_sheets() {
+            names.push(SHEET_COLUMN);
+        }
+        let mut seen = HashSet::new();
+        for sheet in &self.sheets {
+            for header in &sheet.headers {
+                if seen.insert(header.as_str()) {
+                    names.push(header);
+                }
+            }
+        }
+
+        let columns: Vec<Column> = names
+            .into_iter()
+            .map(|name| {
+                let mut types: Vec<DataType> = sample
+                    .iter()
+                    .map(|row| {
+                        row.get(name)
+                            .map_or(DataType::Null, SchemaInferer::infer_type)
+                    })
+                    .collect();
+                // Whole numbers in a column of fractions are decimals too
+                if types.contains(&DataType::Decimal) {
+                    for data_type in types.iter_mut() {
+                        if *data_type == DataType::Integer {
+                            *data_type = DataType::Decimal;
+                        }
+                    }
+                }
+                let (data_type, nullable) = SchemaInferer::resolve_column_type(&types);
+                Column {
+                    name: name.to_string(),
+                    data_type,
+                    nullable,
+                }
+            })
+            .collect();
+
+        // Read the rows as the inferred types, so whole numbers in a decimal
+        // column aren't integers
+        self.inferred_types = columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        Ok(Schema {
+            columns,
+            estimated_rows: self.estimated_row_count().await?,
+            primary_key_candidate: None,
+        })
+    }
+
+    fn set_schema(&mut self, schema: &Schema) {
+        self.declared_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        if self.sheets.is_empty() && self.has_more {
+            self.connect().await?;
+        }
+
+        let with_sheet_name = self.all_sheets();
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            let (index, row) = self.position;
+            let Some(sheet) = self.sheets.get(index) else {
+                self.has_more = false;
+                break;
+            };
+            if row >= sheet.rows.1 {
+                let next = self.sheets.get(index + 1).map_or(0, |sheet| sheet.rows.0);
+                self.position = (index + 1, next);
+                continue;
+            }
+            self.position.1 += 1;
+            if let Some(mut record) = sheet.row(row, with_sheet_name) {
+                for (column, value) in record.iter_mut() {
+                    let data_type = self
+                        .declared_types
+                        .get(column)
+                        .or_else(|| self.inferred_types.get(column));
+                    if let Some(data_type) = data_type {
+                        *value = coerce(std::mem::replace(value, Value::Null), data_type);
+                    }
+                }
+                rows.push(record);
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        Ok(Some(
+            self.sheets
+                .iter()
+                .map(|sheet| (sheet.rows.1 - sheet.rows.0) as usize)
+                .sum(),
+        ))
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.rewind();
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more
+    }
+}
+
+/// Rows in an Excel worksheet, including the header
+const EXCEL_MAX_ROWS: usize = 1_048_576;
+
+/// Columns in an Excel worksheet, A to XFD
+const EXCEL_MAX_COLUMNS: usize = 16_384;
+
+/// Widest column set by `auto_width`, in characters
+const MAX_AUTO_WIDTH: usize = 80;
+
+/// Writes `.xlsx` workbooks. Values become native cells: numbers, booleans,
+/// and dates formatted as dates. Rows past the per-sheet limit continue on
+/// extra sheets. Worksheets are written in constant memory mode, so only the
+/// current row is held until the file is saved by `finalize`.
+pub struct ExcelTarget {
+    file_path: PathBuf,
+    sheet_name: String,
+    bold_header: bool,
+    freeze_header: bool,
+    auto_width: bool,
+    /// Records per sheet, below the header
+    rows_per_sheet: usize,
+    columns: Vec<Column>,
+    /// Behind a mutex only so the target is `Sync`; reached through `get_mut`
+    workbook: Option<Mutex<Workbook>>,
+    /// Records on the current (last) sheet
+    sheet_rows: usize,
+    /// Widest value of each column, in characters
+    widths: Vec<usize>,
+    date_format: Format,
+    datetime_format: Format,
+}
+
+impl ExcelTarget {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `sheet` names the worksheet, `bold_header`, `freeze_header` and
+    /// `auto_width` (all on by default) style it, and `rows_per_sheet` caps
+    /// the records per sheet, at most Excel's limit
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let mut target = Self {
+            file_path: PathBuf::from(file_path),
+            sheet_name: "Sheet1".to_string(),
+            bold_header: true,
+            freeze_header: true,
+            auto_width: true,
+            rows_per_sheet: EXCEL_MAX_ROWS - 1,
+            columns: Vec::new(),
+            workbook: None,
+            sheet_rows: 0,
+            widths: Vec::new(),
+            date_format: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime_format: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+        };
+
+        for (key, value) in options {
+            let value = value.trim();
+            match key.as_str() {
+                "sheet" if !value.is_empty() => target.sheet_name = value.to_string(),
+                "bold_header" => target.bold_header = Self::parse_bool(key, value)?,
+                "freeze_header" => target.freeze_header = Self::parse_bool(key, value)?,
+                "auto_width" => target.auto_width = Self::parse_bool(key, value)?,
+                "rows_per_sheet" => {
+                    target.rows_per_sheet = match value.parse::<usize>() {
+                        Ok(rows) if (1..EXCEL_MAX_ROWS).contains(&rows) => rows,
+                        _ => {
+                            return Err(Self::invalid(
+                                key,
+                                value,
+                                &format!("expected a number from 1 to {}", EXCEL_MAX_ROWS - 1),
+                            ))
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(target)
+    }
+
+    fn parse_bool(key: &str, value: &str) -> Result<bool> {
+        value
+            .parse()
+            .map_err(|_| Self::invalid(key, value, "expected true or false"))
+    }
+
+    fn invalid(key: &str, value: &str, expected: &str) -> TinyEtlError {
+        TinyEtlError::Configuration(format!(
+            "Invalid value '{}' for Excel option '{}': {}",
+            value, key, expected
+        ))
+    }
+
+    /// Add a worksheet and write the header row to it. Extra sheets are
+    /// numbered, e.g. `Orders (2)`.
+    fn add_sheet(&mut self) -> Result<()> {
+        let Some(workbook) = self.workbook.as_mut() else {
+            return Ok(());
+        };
+        let workbook = workbook.get_mut().unwrap_or_else(|e| e.into_inner());
+        let sheet_count = workbook.worksheets().len();
+        let name = if sheet_count == 0 {
+            self.sheet_name.clone()
+        } else {
+            // Sheet names are limited to 31 characters
+            let suffix = format!(" ({})", sheet_count + 1);
+            let base: String = self
+                .sheet_name
+                .chars()
+                .take(31 - suffix.chars().count())
+                .collect();
+            format!("{}{}", base, suffix)
+        };
+
+        let worksheet = workbook.add_worksheet_with_constant_memory();
+        worksheet.set_name(&name).map_err(|e| {
+            TinyEtlError::Configuration(format!("Invalid sheet name '{}': {}", name, e))
+        })?;
+        let header_format = if self.bold_header {
+            Format::new().set_bold()
+        } else {
+            Format::new()
+        };
+        for (i, column) in self.columns.iter().enumerate() {
+            worksheet
+                .write_string_with_format(0, i as u16, &column.name, &header_format)
+                .map_err(xlsx_error)?;
+        }
+        if self.freeze_header {
+            worksheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+        }
+        self.sheet_rows = 0;
+        Ok(())
+    }
+}
+
+/// Write one value as a native cell. Nulls are left blank.
+fn write_cell(
+    worksheet: &mut Worksheet,
+    row: u32,
+    column: u16,
+    data_type: &DataType,
+    value: &Value,
+    date_format: &Format,
+    datetime_format: &Format,
+) -> Result<()> {
+    match value {
+        Value::Null => return Ok(()),
+        // Excel numbers are doubles; larger integers would lose digits
+        Value::Integer(i) if i.unsigned_abs() > 1 << 53 => {
+            worksheet.write_string(row, column, i.to_string())
+        }
+        Value::Integer(i) => worksheet.write_number(row, column, *i as f64),
+        Value::Decimal(d) => worksheet.write_number(row, column, f64::try_from(*d).unwrap_or(0.0)),
+        Value::Boolean(b) => worksheet.write_boolean(row, column, *b),
+        Value::Date(dt) if *data_type == DataType::Date => {
+            worksheet.write_datetime_with_format(row, column, dt.naive_utc(), date_format)
+        }
+        Value::Date(dt) => {
+            worksheet.write_datetime_with_format(row, column, dt.naive_utc(), datetime_format)
+        }
+        Value::String(s) => worksheet.write_string(row, column, s),
+        Value::Json(json) => worksheet.write_string(row, column, json.to_string()),
+    }
+    .map_err(xlsx_error)?;
+    Ok(())
+}
+
+/// Width of a value as displayed, in characters
+fn cell_width(data_type: &DataType, value: &Value) -> usize {
+    match value {
+        Value::Null => 0,
+        Value::Date(_) if *data_type == DataType::Date => 10,
+        Value::Date(_) => 19,
+        Value::String(s) => s.chars().count(),
+        Value::Json(json) => json.to_string().chars().count(),
+        Value::Integer(i) => i.to_string().len(),
+        Value::Decimal(d) => d.to_string().len(),
+        Value::Boolean(b) => b.to_string().len(),
+    }
+}
+
+fn xlsx_error(e: XlsxError) -> TinyEtlError {
+    TinyEtlError::DataTransfer(format!("Failed to write Excel file: {}", e))
+}
+
+#[async_trait]
+impl Target for ExcelTarget {
+    async fn connect(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
+        if schema.columns.len() > EXCEL_MAX_COLUMNS {
+            return Err(TinyEtlError::Configuration(format!(
+                "Excel sheets hold at most {} columns, not {}",
+                EXCEL_MAX_COLUMNS,
+                schema.columns.len()
+            )));
+        }
+        self.columns = schema.columns.clone();
+        self.widths = self
+            .columns
+            .iter()
+            .map(|c| c.name.chars().count())
+            .collect();
+        self.workbook = Some(Mutex::new(Workbook::new()));
+        self.add_sheet()
+    }
+
+    async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
+        if self.workbook.is_none() {
+            return Err(TinyEtlError::Configuration(
+                "Schema not initialized. Call create_table() first.".to_string(),
+            ));
+        }
+
+        for row in rows {
+            if self.sheet_rows == self.rows_per_sheet {
+                self.add_sheet()?;
+            }
+            let row_number = self.sheet_rows as u32 + 1;
+            let worksheet = match self.workbook.as_mut() {
+                Some(workbook) => workbook
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .worksheets_mut()
+                    .last_mut(),
+                None => None,
+            }
+            .ok_or_else(|| TinyEtlError::DataTransfer("Excel workbook has no sheet".to_string()))?;
+
+            for (i, column) in self.columns.iter().enumerate() {
+                let value = row.get(&column.name).unwrap_or(&Value::Null);
+                write_cell(
+                    worksheet,
+                    row_number,
+                    i as u16,
+                    &column.data_type,
+                    value,
+                    &self.date_format,
+                    &self.datetime_format,
+                )?;
+                self.widths[i] = self.widths[i].max(cell_width(&column.data_type, value));
+            }
+            self.sheet_rows += 1;
+        }
+        Ok(rows.len())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        let Some(workbook) = self.workbook.take() else {
+            return Ok(());
+        };
+        let mut workbook = workbook.into_inner().unwrap_or_else(|e| e.into_inner());
+        if self.auto_width {
+            for worksheet in workbook.worksheets_mut() {
+                for (i, width) in self.widths.iter().enumerate() {
+                    // Room for the filter button and padding
+                    let width = (*width).min(MAX_AUTO_WIDTH) as f64 + 2.0;
+                    worksheet
+                        .set_column_width(i as u16, width)
+                        .map_err(xlsx_error)?;
+                }
+            }
+        }
+        workbook.save(&self.file_path).map_err(xlsx_error)
+    }
+
+    async fn exists(&self, _table_name: &str) -> Result<bool> {
+        Ok(self.file_path.exists())
+    }
+
+    async fn truncate(&mut self, _table_name: &str) -> Result<()> {
+        // The file is replaced when the workbook is saved
+        self.workbook = None;
+        Ok(())
+    }
+
+    fn supports_append(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::options;
+    use rust_xlsxwriter::ExcelDateTime;
+    use tempfile::TempDir;
+
+    /// A workbook with an orders sheet, a title above the table, and a
+    /// second sheet of returns
+    fn create_workbook(dir: &TempDir) -> String {
+        let path = dir.path().join("orders.xlsx");
+        let mut workbook = Workbook::new();
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+
+        let orders = workbook.add_worksheet().set_name("Orders").unwrap();
+        orders.write_string(0, 0, "Order report").unwrap();
+        for (column, header) in ["id", "customer", "amount", "paid", "ordered"]
+            .iter()
+            .enumerate()
+        {
+            orders.write_string(2, column as u16, *header).unwrap();
+        }
+        let data = [
+            (1, "Ann", 12.5, true, "2024-03-01"),
+            (2, "Bob", 40.0, false, "2024-03-02"),
+        ];
+        for (i, (id, customer, amount, paid, ordered)) in data.iter().enumerate() {
+            let row = 3 + i as u32;
+            orders.write_number(row, 0, *id).unwrap();
+            orders.write_string(row, 1, *customer).unwrap();
+            orders.write_number(row, 2, *amount).unwrap();
+            orders.write_boolean(row, 3, *paid).unwrap();
+            let date = ExcelDateTime::parse_from_str(ordered).unwrap();
+            orders
+                .write_datetime_with_format(row, 4, &date, &date_format)
+                .unwrap();
+        }
+        // A blank row, then a footer outside the table
+        orders.write_string(6, 0, "Total").unwrap();
+
+        let returns = workbook.add_worksheet().set_name("Returns").unwrap();
+        returns.write_string(0, 0, "id").unwrap();
+        returns.write_string(0, 1, "reason").unwrap();
+        returns.write_number(1, 0, 2).unwrap();
+        returns.write_string(1, 1, "damaged").unwrap();
+
+        workbook.save(&path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            CellRange::parse("A3:F200").unwrap(),
+            CellRange {
+                start: (2, 0),
+                end: Some((199, 5))
+            }
+        );
+        assert_eq!(CellRange::parse("aa10").unwrap().start, (9, 26));
+        assert_eq!(CellRange::parse("B2").unwrap().end, None);
+        assert!(CellRange::parse("A0").is_err());
+        assert!(CellRange::parse("3A").is_err());
+        assert!(CellRange::parse("C3:A1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_excel_source_sheet_range_and_types() {
+        let dir = TempDir::new().unwrap();
+        let path = create_workbook(&dir);
+
+        let mut source = ExcelSource::with_options(&path, &options(&[("range", "A3:E5")])).unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(100).await.unwrap();
+        let columns: Vec<(&str, &DataType)> = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), &c.data_type))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("id", &DataType::Integer),
+                ("customer", &DataType::String),
+                ("amount", &DataType::Decimal),
+                ("paid", &DataType::Boolean),
+                ("ordered", &DataType::DateTime),
+            ]
+        );
+
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["id"], Value::Integer(1));
+        assert_eq!(rows[0]["amount"], Value::Decimal(Decimal::new(125, 1)));
+        assert_eq!(rows[1]["amount"], Value::Decimal(Decimal::from(40)));
+        assert_eq!(rows[0]["paid"], Value::Boolean(true));
+        assert_eq!(
+            rows[1]["ordered"],
+            DateParser::try_parse("2024-03-02").unwrap()
+        );
+        assert!(!source.has_more());
+
+        // The header row can be given instead, and blank rows are skipped
+        let mut source =
+            ExcelSource::with_options(&path, &options(&[("sheet", "Orders"), ("header_row", "3")]))
+                .unwrap();
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2]["id"], Value::String("Total".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_excel_to_parquet() {
+        use crate::connectors::parquet::{ParquetSource, ParquetTarget};
+
+        let dir = TempDir::new().unwrap();
+        let path = create_workbook(&dir);
+        let mut source = ExcelSource::with_options(&path, &options(&[("range", "A3:E5")])).unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(100).await.unwrap();
+        let rows = source.read_batch(100).await.unwrap();
+
+        // 40.0 is a whole number in a decimal column, which Parquet must not null
+        let parquet_path = dir.path().join("orders.parquet");
+        let parquet_path = parquet_path.to_str().unwrap();
+        let mut target = ParquetTarget::new(parquet_path).unwrap();
+        target.connect().await.unwrap();
+        target.create_table("orders", &schema).await.unwrap();
+        target.write_batch(&rows).await.unwrap();
+        target.finalize().await.unwrap();
+
+        let mut source = ParquetSource::new(parquet_path).unwrap();
+        source.connect().await.unwrap();
+        let rows = source.read_batch(100).await.unwrap();
+        let amounts: Vec<&Value> = rows.iter().map(|row| &row["amount"]).collect();
+        assert_eq!(
+            amounts,
+            [
+                &Value::Decimal(Decimal::new(125, 1)),
+                &Value::Decimal(Decimal::from(40))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_excel_source_sheets() {
+        let dir = TempDir::new().unwrap();
+        let path = create_workbook(&dir);
+
+        let mut source = ExcelSource::with_options(&path, &options(&[("sheet", "1")])).unwrap();
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["reason"], Value::String("damaged".to_string()));
+
+        // Without a header row, columns are numbered
+        let mut source = ExcelSource::with_options(
+            &path,
+            &options(&[("sheet", "Returns"), ("header_row", "0")]),
+        )
+        .unwrap();
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["column_2"], Value::String("reason".to_string()));
+
+        let mut source =
+            ExcelSource::with_options(&path, &options(&[("sheet", "*"), ("header_row", "1")]))
+                .unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(100).await.unwrap();
+        assert_eq!(schema.columns[0].name, SHEET_COLUMN);
+        let rows = source.read_batch(2).await.unwrap();
+        assert_eq!(rows[0][SHEET_COLUMN], Value::String("Orders".to_string()));
+        let rows = source.read_batch(100).await.unwrap();
+        let last = rows.last().unwrap();
+        assert_eq!(last[SHEET_COLUMN], Value::String("Returns".to_string()));
+        assert_eq!(last["reason"], Value::String("damaged".to_string()));
+
+        let mut source =
+            ExcelSource::with_options(&path, &options(&[("sheet", "Refunds")])).unwrap();
+        let err = source.connect().await.unwrap_err();
+        assert!(err.to_string().contains("Orders, Returns"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_excel_target_native_cells_and_extra_sheets() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("report.xlsx");
+        let path = path.to_str().unwrap();
+
+        let column = |name: &str, data_type: DataType| Column {
+            name: name.to_string(),
+            data_type,
+            nullable: true,
+        };
+        let schema = Schema {
+            columns: vec![
+                column("id", DataType::Integer),
+                column("amount", DataType::Decimal),
+                column("paid", DataType::Boolean),
+                column("due", DataType::Date),
+                column("updated", DataType::DateTime),
+                column("note", DataType::String),
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let due = DateParser::try_parse("2024-03-01").unwrap();
+        let updated = DateParser::try_parse("2024-03-01 14:30:00").unwrap();
+        let rows: Vec<Row> = (1..=5)
+            .map(|i| {
+                HashMap::from([
+                    ("id".to_string(), Value::Integer(i)),
+                    (
+                        "amount".to_string(),
+                        Value::Decimal(Decimal::new(i * 125, 2)),
+                    ),
+                    ("paid".to_string(), Value::Boolean(i % 2 == 0)),
+                    ("due".to_string(), due.clone()),
+                    ("updated".to_string(), updated.clone()),
+                    ("note".to_string(), Value::Null),
+                ])
+            })
+            .collect();
+
+        let mut target = ExcelTarget::with_options(
+            path,
+            &options(&[("sheet", "Orders"), ("rows_per_sheet", "2")]),
+        )
+        .unwrap();
+        target.connect().await.unwrap();
+        target.create_table("orders", &schema).await.unwrap();
+        target.write_batch(&rows[..3]).await.unwrap();
+        target.write_batch(&rows[3..]).await.unwrap();
+        target.finalize().await.unwrap();
+
+        let mut source = ExcelSource::with_options(path, &options(&[("sheet", "*")])).unwrap();
+        source.connect().await.unwrap();
+        let read = source.read_batch(100).await.unwrap();
+        let sheets: Vec<&Value> = read.iter().map(|row| &row[SHEET_COLUMN]).collect();
+        let sheet = |name: &str| Value::String(name.to_string());
+        assert_eq!(
+            sheets,
+            [
+                &sheet("Orders"),
+                &sheet("Orders"),
+                &sheet("Orders (2)"),
+                &sheet("Orders (2)"),
+                &sheet("Orders (3)")
+            ]
+        );
+        assert_eq!(read[4]["id"], Value::Integer(5));
+        assert_eq!(read[1]["amount"], Value::Decimal(Decimal::new(25, 1)));
+        assert_eq!(read[1]["paid"], Value::Boolean(true));
+        assert_eq!(read[0]["due"], due);
+        assert_eq!(read[0]["updated"], updated);
+        assert_eq!(read[0]["note"], Value::Null);
+
+        assert!(ExcelTarget::with_options(path, &options(&[("rows_per_sheet", "0")])).is_err());
+        assert!(ExcelTarget::with_options(path, &options(&[("bold_header", "yes")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_excel_target_column_limit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("wide.xlsx");
+        let schema = |columns: usize| Schema {
+            columns: (0..columns)
+                .map(|i| Column {
+                    name: format!("c{}", i),
+                    data_type: DataType::Integer,
+                    nullable: true,
+                })
+                .collect(),
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+
+        let mut target = ExcelTarget::new(path.to_str().unwrap()).unwrap();
+        assert!(target
+            .create_table("wide", &schema(EXCEL_MAX_COLUMNS))
+            .await
+            .is_ok());
+        let result = target
+            .create_table("wide", &schema(EXCEL_MAX_COLUMNS + 1))
+            .await;
+        assert!(matches!(result, Err(TinyEtlError::Configuration(_))));
+    }
+}
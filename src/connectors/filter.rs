@@ -0,0 +1,269 @@
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use crate::{
+    date_parser::DateParser,
+    schema::{Row, Value},
+    Result, TinyEtlError,
+};
+
+/// A row filter from the `filter` option: comparisons of a column with a
+/// literal joined by `and`, e.g. `date >= '2024-01-01' and status = 'paid'`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Filter {
+    pub(crate) conditions: Vec<Condition>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Condition {
+    pub(crate) column: String,
+    operator: Operator,
+    literal: Value,
+    /// The literal read as a date, for comparing with date columns
+    date: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+impl Operator {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::NotEq => ordering != Ordering::Equal,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::LtEq => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::GtEq => ordering != Ordering::Less,
+        }
+    }
+}
+
+impl Filter {
+    pub(crate) fn parse(expression: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            TinyEtlError::Configuration(format!("Invalid filter '{}': {}", expression, reason))
+        };
+
+        let mut conditions = Vec::new();
+        let mut rest = expression.trim();
+        loop {
+            // Column name, optionally in double quotes
+            let (column, after) = if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').ok_or_else(|| invalid("missing '\"'"))?;
+                (&quoted[..end], &quoted[end + 1..])
+            } else {
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                    .unwrap_or(rest.len());
+                rest.split_at(end)
+            };
+            if column.is_empty() {
+                return Err(invalid("expected a column name"));
+            }
+
+            let after = after.trim_start();
+            let (operator, after) = [
+                (">=", Operator::GtEq),
+                ("<=", Operator::LtEq),
+                ("!=", Operator::NotEq),
+                ("<>", Operator::NotEq),
+                ("==", Operator::Eq),
+                ("=", Operator::Eq),
+                (">", Operator::Gt),
+                ("<", Operator::Lt),
+            ]
+            .into_iter()
+            .find_map(|(symbol, op)| after.strip_prefix(symbol).map(|after| (op, after)))
+            .ok_or_else(|| invalid("expected one of =, !=, <, <=, >, >="))?;
+
+            let after = after.trim_start();
+            let (literal, after) = if let Some(quoted) = after.strip_prefix('\'') {
+                // '' inside a quoted string is a quote
+                let mut text = String::new();
+                let mut chars = quoted.char_indices();
+                let end = loop {
+                    match chars.next() {
+                        Some((i, '\'')) => {
+                            if quoted[i + 1..].starts_with('\'') {
+                                text.push('\'');
+                                chars.next();
+                            } else {
+                                break i + 1;
+                            }
+                        }
+                        Some((_, c)) => text.push(c),
+                        None => return Err(invalid("missing closing quote")),
+                    }
+                };
+                (Value::String(text), &quoted[end..])
+            } else {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                let (token, after) = after.split_at(end);
+                let literal = if let Ok(i) = token.parse::<i64>() {
+                    Value::Integer(i)
+                } else if let Ok(d) = Decimal::from_str(token) {
+                    Value::Decimal(d)
+                } else if token.eq_ignore_ascii_case("true") {
+                    Value::Boolean(true)
+                } else if token.eq_ignore_ascii_case("false") {
+                    Value::Boolean(false)
+                } else {
+                    return Err(invalid(
+                        "values must be numbers, true, false or quoted strings",
+                    ));
+                };
+                (literal, after)
+            };
+
+            let date = match &literal {
+                Value::String(s) => {
+                    DateParser::try_parse(s).filter(|v| matches!(v, Value::Date(_)))
+                }
+                _ => None,
+            };
+            conditions.push(Condition {
+                column: column.to_string(),
+                operator,
+                literal,
+                date,
+            });
+
+            rest = after.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            rest = match rest.get(..4) {
+                Some(and) if and.eq_ignore_ascii_case("and ") => rest[4..].trim_start(),
+                _ => return Err(invalid("conditions must be joined with 'and'")),
+            };
+        }
+
+        Ok(Self { conditions })
+    }
+
+    /// Whether the row satisfies every condition. Nulls and values that can't
+    /// be compared with the literal never match.
+    pub(crate) fn matches(&self, row: &Row) -> bool {
+        self.conditions.iter().all(|condition| {
+            row.get(&condition.column)
+                .is_some_and(|value| condition.matches(value))
+        })
+    }
+
+    pub(crate) fn columns(&self) -> impl Iterator<Item = &str> {
+        self.conditions.iter().map(|c| c.column.as_str())
+    }
+}
+
+impl Condition {
+    pub(crate) fn matches(&self, value: &Value) -> bool {
+        compare(value, self.literal_for(value)).is_some_and(|o| self.operator.holds(o))
+    }
+
+    /// Whether any value between `min` and `max` could satisfy the condition,
+    /// e.g. from the column statistics of a block of rows. True when unsure.
+    pub(crate) fn may_match_range(&self, min: &Value, max: &Value) -> bool {
+        let literal = self.literal_for(min);
+        let (Some(low), Some(high)) = (compare(min, literal), compare(max, literal)) else {
+            return true;
+        };
+        match self.operator {
+            Operator::Eq => low != Ordering::Greater && high != Ordering::Less,
+            Operator::NotEq => !(low == Ordering::Equal && high == Ordering::Equal),
+            Operator::Lt => low == Ordering::Less,
+            Operator::LtEq => low != Ordering::Greater,
+            Operator::Gt => high == Ordering::Greater,
+            Operator::GtEq => high != Ordering::Less,
+        }
+    }
+
+    fn literal_for(&self, value: &Value) -> &Value {
+        match (value, &self.date) {
+            (Value::Date(_), Some(date)) => date,
+            _ => &self.literal,
+        }
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => Some(a.cmp(b)),
+        (Value::Integer(a), Value::Decimal(b)) => Some(Decimal::from(*a).cmp(b)),
+        (Value::Decimal(a), Value::Integer(b)) => Some(a.cmp(&Decimal::from(*b))),
+        (Value::Decimal(a), Value::Decimal(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Date(a), Value::Date(b)) => Some(a.cmp(b)),
+        (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pairs: &[(&str, Value)]) -> Row {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let filter =
+            Filter::parse("date >= '2024-01-01' AND qty<10 and name != 'O''Brien'").unwrap();
+        assert_eq!(
+            filter.columns().collect::<Vec<_>>(),
+            ["date", "qty", "name"]
+        );
+
+        let date = |s: &str| DateParser::try_parse(s).unwrap();
+        let matching = row(&[
+            ("date", date("2024-03-01")),
+            ("qty", Value::Decimal(Decimal::new(95, 1))),
+            ("name", Value::String("Smith".to_string())),
+        ]);
+        assert!(filter.matches(&matching));
+
+        let mut earlier = matching.clone();
+        earlier.insert("date".to_string(), date("2023-12-31"));
+        assert!(!filter.matches(&earlier));
+        let mut quoted = matching.clone();
+        quoted.insert("name".to_string(), Value::String("O'Brien".to_string()));
+        assert!(!filter.matches(&quoted));
+        let mut null = matching;
+        null.insert("qty".to_string(), Value::Null);
+        assert!(!filter.matches(&null));
+
+        assert!(Filter::parse("qty").is_err());
+        assert!(Filter::parse("qty > ten").is_err());
+        assert!(Filter::parse("qty > 1 or qty < 0").is_err());
+        assert!(Filter::parse("name = 'open").is_err());
+    }
+
+    #[test]
+    fn test_may_match_range() {
+        let condition = |expression: &str| Filter::parse(expression).unwrap().conditions[0].clone();
+        let (min, max) = (Value::Integer(10), Value::Integer(20));
+
+        assert!(condition("id = 15").may_match_range(&min, &max));
+        assert!(!condition("id = 25").may_match_range(&min, &max));
+        assert!(!condition("id < 10").may_match_range(&min, &max));
+        assert!(condition("id <= 10").may_match_range(&min, &max));
+        assert!(!condition("id > 20").may_match_range(&min, &max));
+        assert!(condition("id != 15").may_match_range(&min, &max));
+        assert!(!condition("id != 10").may_match_range(&min, &min));
+        // Values that don't compare can't rule anything out
+        assert!(condition("id = 'x'").may_match_range(&min, &max));
+    }
+}
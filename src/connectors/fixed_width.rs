@@ -0,0 +1,549 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::{
+    connectors::{
+        compression::Compression, csv::CsvSource, encoding::TextEncoding, stream, Source,
+    },
+    schema::{Column, DataType, Row, Schema, SchemaFile, SourceColumnRef, Value},
+    Result, TinyEtlError,
+};
+
+/// A field of a fixed-width record
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    name: String,
+    /// Zero-based character offset
+    start: usize,
+    width: usize,
+    data_type: DataType,
+    /// Digits after the implied decimal point, from a `V` in the picture
+    scale: u32,
+}
+
+/// Width and implied decimals of a COBOL picture such as `S9(7)V99` or `X(10)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Picture {
+    width: usize,
+    scale: u32,
+}
+
+impl Picture {
+    fn parse(picture: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            TinyEtlError::Configuration(format!("Invalid picture '{}': {}", picture, reason))
+        };
+
+        let text = picture.trim().to_uppercase();
+        let text = text
+            .strip_prefix("PIC ")
+            .or_else(|| text.strip_prefix("PICTURE "))
+            .unwrap_or(&text)
+            .trim();
+        let mut chars = text.chars().peekable();
+        let (mut width, mut scale, mut after_point) = (0usize, 0u32, false);
+        while let Some(symbol) = chars.next() {
+            // A symbol repeats once, or the number of times in parentheses
+            let count = if chars.peek() == Some(&'(') {
+                chars.next();
+                let digits: String = chars.by_ref().take_while(|c| *c != ')').collect();
+                digits
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| invalid("expected a count in parentheses"))?
+            } else {
+                1
+            };
+            match symbol {
+                '9' | 'X' | 'A' => {
+                    width += count;
+                    if after_point {
+                        if symbol != '9' {
+                            return Err(invalid("only 9 may follow V"));
+                        }
+                        scale += count as u32;
+                    }
+                }
+                'V' if !after_point => after_point = true,
+                // The sign is carried in the digits or a sign character in the data
+                'S' if width == 0 => {}
+                _ => return Err(invalid("expected 9, X, A, S or V")),
+            }
+        }
+        if width == 0 {
+            return Err(invalid("no characters"));
+        }
+        Ok(Self { width, scale })
+    }
+}
+
+/// Fields of the records, from the `start`, `width`, `end` and `picture` of
+/// the schema file's columns. Columns without any of them are not read.
+fn layout_fields(schema_file: &SchemaFile) -> Result<Vec<Field>> {
+    let mut fields = Vec::new();
+    let mut next_start = 1;
+    for column in &schema_file.columns {
+        if column.start.is_none()
+            && column.width.is_none()
+            && column.end.is_none()
+            && column.picture.is_none()
+        {
+            continue;
+        }
+        let invalid = |reason: String| {
+            TinyEtlError::Configuration(format!(
+                "Invalid fixed-width layout for column '{}': {}",
+                column.name, reason
+            ))
+        };
+
+        let start = column.start.unwrap_or(next_start);
+        if start == 0 {
+            return Err(invalid("start counts from 1".to_string()));
+        }
+        let picture = column.picture.as_deref().map(Picture::parse).transpose()?;
+        let width = match (column.width, column.end, picture) {
+            (Some(width), None, _) => width,
+            (None, Some(end), _) if end >= start => end - start + 1,
+            (None, Some(end), _) => {
+                return Err(invalid(format!("end {} is before start {}", end, start)))
+            }
+            (None, None, Some(picture)) => picture.width,
+            (Some(_), Some(_), _) => {
+                return Err(invalid("give either width or end, not both".to_string()))
+            }
+            (None, None, None) => return Err(invalid("give a width, end or picture".to_string())),
+        };
+        if width == 0 {
+            return Err(invalid("width must be at least 1".to_string()));
+        }
+        if let Some(picture) = picture.filter(|p| p.width != width) {
+            return Err(invalid(format!(
+                "picture is {} characters but the field is {}",
+                picture.width, width
+            )));
+        }
+
+        let name = match &column.source {
+            Some(SourceColumnRef::Name(name)) => name.clone(),
+            _ => column.name.clone(),
+        };
+        // Columns with a format stay text so the schema file can cast them
+        let data_type = if column.format.is_some() {
+            DataType::String
+        } else {
+            column.schema_data_type()
+        };
+        fields.push(Field {
+            name,
+            start: start - 1,
+            width,
+            data_type,
+            scale: picture.map_or(0, |p| p.scale),
+        });
+        next_start = start + width;
+    }
+    Ok(fields)
+}
+
+impl Field {
+    /// The characters of the field in a record; short records are padded
+    fn slice<'a>(&self, line: &'a str) -> &'a str {
+        if line.is_ascii() {
+            let end = (self.start + self.width).min(line.len());
+            return line.get(self.start..end).unwrap_or("");
+        }
+        let mut indices = line.char_indices().map(|(i, _)| i).chain([line.len()]);
+        let from = indices.nth(self.start).unwrap_or(line.len());
+        let to = indices.nth(self.width - 1).unwrap_or(line.len());
+        &line[from..to]
+    }
+
+    /// Trim the padding and parse the text as the column's type. Text that
+    /// doesn't parse is kept so schema validation can report it.
+    fn parse(&self, text: &str) -> Value {
+        let text = text.trim();
+        if text.is_empty() {
+            return Value::Null;
+        }
+        match self.data_type {
+            DataType::Integer | DataType::Decimal => {
+                let number = leading_sign(text);
+                if self.scale > 0 && !number.contains('.') {
+                    if let Some(decimal) = number
+                        .parse::<i128>()
+                        .ok()
+                        .and_then(|n| Decimal::try_from_i128_with_scale(n, self.scale).ok())
+                    {
+                        return Value::Decimal(decimal);
+                    }
+                }
+                CsvSource::parse_as(&number, Some(&self.data_type))
+            }
+            _ => CsvSource::parse_as(text, Some(&self.data_type)),
+        }
+    }
+}
+
+/// Move a trailing sign, as in `123-`, to the front and drop a `+`
+fn leading_sign(text: &str) -> Cow<'_, str> {
+    if let Some(digits) = text.strip_suffix('-') {
+        Cow::Owned(format!("-{}", digits.trim()))
+    } else if let Some(digits) = text.strip_suffix('+').or_else(|| text.strip_prefix('+')) {
+        Cow::Borrowed(digits.trim())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Fixed-width text source: each line is a record, cut into fields by the
+/// positions in the schema file named by the `layout` option
+pub struct FixedWidthSource {
+    file_path: PathBuf,
+    encoding: TextEncoding,
+    compression: Compression,
+    fields: Vec<Field>,
+    /// Header records to skip
+    skip_rows: usize,
+    /// Trailer records to skip
+    skip_footer: usize,
+    reader: Option<BufReader<Box<dyn Read + Send + Sync>>>,
+    /// Records held back so the last `skip_footer` are never returned
+    footer_buffer: VecDeque<String>,
+    has_more_data: bool,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
+}
+
+impl FixedWidthSource {
+    /// `layout` names the schema file giving each column's position. The
+    /// first `skip_rows` and last `skip_footer` records are skipped.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let layout = options.get("layout").ok_or_else(|| {
+            TinyEtlError::Configuration(format!(
+                "Fixed-width source {} needs a `layout` option naming the schema file with the column positions",
+                file_path
+            ))
+        })?;
+        let fields = layout_fields(&SchemaFile::from_file(layout)?)?;
+        if fields.is_empty() {
+            return Err(TinyEtlError::Configuration(format!(
+                "Layout {} has no fixed-width columns: give columns a start and a width, end or picture",
+                layout
+            )));
+        }
+
+        let count = |key: &str| -> Result<usize> {
+            match options.get(key) {
+                None => Ok(0),
+                Some(value) => value.trim().parse().map_err(|_| {
+                    TinyEtlError::Configuration(format!(
+                        "Invalid value '{}' for fixed-width option '{}': expected a number",
+                        value, key
+                    ))
+                }),
+            }
+        };
+
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            encoding: TextEncoding::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
+            fields,
+            skip_rows: count("skip_rows")?,
+            skip_footer: count("skip_footer")?,
+            reader: None,
+            footer_buffer: VecDeque::new(),
+            has_more_data: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
+        })
+    }
+
+    /// Open the file positioned after the `skip_rows` header records
+    fn open_reader(&self) -> Result<BufReader<Box<dyn Read + Send + Sync>>> {
+        let file = stream::open_file(&self.file_path, self.compression, &self.bytes_read)?;
+        let mut reader = BufReader::new(self.encoding.decode(file)?);
+        let mut line = Vec::new();
+        for _ in 0..self.skip_rows {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+        }
+        Ok(reader)
+    }
+
+    /// Next non-blank record, holding back `skip_footer` records until the
+    /// end of the file
+    fn next_record(&mut self) -> Result<Option<String>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line)? == 0 {
+                // Whatever is still buffered is the trailer
+                self.footer_buffer.clear();
+                return Ok(None);
+            }
+            let record = line.trim_end_matches(['\n', '\r']);
+            if record.trim().is_empty() {
+                continue;
+            }
+            line.truncate(record.len());
+            self.footer_buffer.push_back(line);
+            if self.footer_buffer.len() > self.skip_footer {
+                return Ok(self.footer_buffer.pop_front());
+            }
+        }
+    }
+
+    fn record_to_row(&self, record: &str) -> Row {
+        self.fields
+            .iter()
+            .map(|field| (field.name.clone(), field.parse(field.slice(record))))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Source for FixedWidthSource {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "Fixed-width file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.reset().await
+    }
+
+    async fn infer_schema(&mut self, _sample_size: usize) -> Result<Schema> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        // The layout declares every column and type
+        Ok(Schema {
+            columns: self
+                .fields
+                .iter()
+                .map(|field| Column {
+                    name: field.name.clone(),
+                    data_type: if field.scale > 0 && field.data_type == DataType::Integer {
+                        DataType::Decimal
+                    } else {
+                        field.data_type.clone()
+                    },
+                    nullable: true,
+                })
+                .collect(),
+            estimated_rows: self.estimated_row_count().await?,
+            primary_key_candidate: None,
+        })
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            match self.next_record()? {
+                Some(record) => rows.push(self.record_to_row(&record)),
+                None => {
+                    self.has_more_data = false;
+                    break;
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        let line_count = stream::estimate_line_count(&self.file_path, self.compression)?;
+        Ok(Some(
+            line_count.saturating_sub(self.skip_rows + self.skip_footer),
+        ))
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.reader = Some(self.open_reader()?);
+        self.footer_buffer.clear();
+        self.has_more_data = true;
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more_data && self.reader.is_some()
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const LAYOUT: &str = r#"
+columns:
+  - name: "account"
+    type: "String"
+    nullable: false
+    start: 1
+    width: 8
+  - name: "name"
+    type: "String"
+    nullable: true
+    start: 9
+    end: 20
+  - name: "balance"
+    type: "Decimal"
+    nullable: true
+    picture: "S9(7)V99"
+  - name: "opened"
+    type: "Date"
+    nullable: true
+    width: 8
+    format: "%Y%m%d"
+  - name: "source_system"
+    type: "String"
+    nullable: false
+    default: "mainframe"
+"#;
+
+    fn temp_file(suffix: &str, content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::with_suffix(suffix).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn test_picture() {
+        assert_eq!(
+            Picture::parse("9(7)V99").unwrap(),
+            Picture { width: 9, scale: 2 }
+        );
+        assert_eq!(
+            Picture::parse("PIC S999V9(3)").unwrap(),
+            Picture { width: 6, scale: 3 }
+        );
+        assert_eq!(
+            Picture::parse("x(10)").unwrap(),
+            Picture {
+                width: 10,
+                scale: 0
+            }
+        );
+        assert!(Picture::parse("9(7)V99V9").is_err());
+        assert!(Picture::parse("9(x)").is_err());
+        assert!(Picture::parse("Z(5)").is_err());
+    }
+
+    #[test]
+    fn test_layout_fields() {
+        let schema_file: SchemaFile = serde_yaml::from_str(LAYOUT).unwrap();
+        let fields = layout_fields(&schema_file).unwrap();
+        let positions: Vec<(&str, usize, usize)> = fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.start, f.width))
+            .collect();
+        assert_eq!(
+            positions,
+            [
+                ("account", 0, 8),
+                ("name", 8, 12),
+                ("balance", 20, 9),
+                ("opened", 29, 8)
+            ]
+        );
+        assert_eq!(fields[3].data_type, DataType::String);
+
+        let invalid = |column: &str| {
+            let yaml = format!(
+                "columns:\n  - {{ name: a, type: String, nullable: true, {} }}",
+                column
+            );
+            layout_fields(&serde_yaml::from_str(&yaml).unwrap()).is_err()
+        };
+        assert!(invalid("start: 0, width: 2"));
+        assert!(invalid("start: 5, end: 4"));
+        assert!(invalid("width: 2, end: 4"));
+        assert!(invalid("width: 3, picture: '9(4)'"));
+    }
+
+    #[tokio::test]
+    async fn test_fixed_width_source() {
+        let layout = temp_file(".yaml", LAYOUT);
+        let data = temp_file(
+            ".fwf",
+            concat!(
+                "HDR 2024-05-31 ACCOUNTS\n",
+                "00012345Ada Lovelace00001234520240102\r\n",
+                "\n",
+                "00067890Bob         00000050-19991231\n",
+                "00000042Zoë Ünicode 000000000\n",
+                "TRL 0000000003\n",
+            ),
+        );
+        let options = HashMap::from([
+            (
+                "layout".to_string(),
+                layout.path().to_str().unwrap().to_string(),
+            ),
+            ("skip_rows".to_string(), "1".to_string()),
+            ("skip_footer".to_string(), "1".to_string()),
+        ]);
+        let mut source =
+            FixedWidthSource::with_options(data.path().to_str().unwrap(), &options).unwrap();
+        source.connect().await.unwrap();
+
+        let schema = source.infer_schema(10).await.unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["account", "name", "balance", "opened"]);
+
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0]["account"], Value::String("00012345".to_string()));
+        assert_eq!(rows[0]["name"], Value::String("Ada Lovelace".to_string()));
+        assert_eq!(rows[0]["balance"], Value::Decimal(Decimal::new(12345, 2)));
+        assert_eq!(rows[0]["opened"], Value::String("20240102".to_string()));
+        assert_eq!(rows[1]["name"], Value::String("Bob".to_string()));
+        assert_eq!(rows[1]["balance"], Value::Decimal(Decimal::new(-50, 2)));
+        assert_eq!(rows[2]["name"], Value::String("Zoë Ünicode".to_string()));
+        assert_eq!(rows[2]["balance"], Value::Decimal(Decimal::new(0, 2)));
+        assert_eq!(rows[2]["opened"], Value::Null);
+        assert!(!source.has_more());
+
+        // Values are then cast and validated by the schema file
+        let schema_file = SchemaFile::from_file(layout.path()).unwrap();
+        let mut row = rows[0].clone();
+        schema_file.validate_and_transform_row(&mut row).unwrap();
+        assert!(matches!(row["opened"], Value::Date(_)));
+        assert_eq!(row["source_system"], Value::String("mainframe".to_string()));
+
+        let missing =
+            FixedWidthSource::with_options(data.path().to_str().unwrap(), &HashMap::new());
+        assert!(missing.is_err());
+    }
+}
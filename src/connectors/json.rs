@@ -1,31 +1,164 @@
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde_json;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 use crate::{
-    connectors::{Source, Target},
+    connectors::{
+        compression::{CompressedWriter, Compression},
+        encoding::TextEncoding,
+        json_reader::{JsonArrayReader, JsonRoot},
+        stream, Source, Target,
+    },
     date_parser::DateParser,
     schema::{Row, Schema, SchemaInferer, Value},
     Result, TinyEtlError,
 };
 
+/// Nested-object flattening, from the `flatten` and `flatten_separator` options
+#[derive(Debug, Clone)]
+pub(crate) struct Flatten {
+    /// Levels of nested objects expanded into columns; 0 keeps them as JSON text
+    depth: usize,
+    separator: String,
+}
+
+impl Flatten {
+    /// `flatten` is `true` for every level, a number of levels, or `false`
+    pub(crate) fn from_options(options: &HashMap<String, String>) -> Result<Self> {
+        let depth = match options.get("flatten").map(|f| f.trim().to_lowercase()) {
+            None => 0,
+            Some(value) => match value.as_str() {
+                "" | "false" | "no" => 0,
+                "true" | "yes" => usize::MAX,
+                depth => depth.parse().map_err(|_| {
+                    TinyEtlError::Configuration(format!(
+                        "Invalid flatten '{}': expected true, false or a depth",
+                        depth
+                    ))
+                })?,
+            },
+        };
+        Ok(Self {
+            depth,
+            separator: options
+                .get("flatten_separator")
+                .cloned()
+                .unwrap_or_else(|| "_".to_string()),
+        })
+    }
+
+    /// Convert a JSON object to a row, expanding nested objects into
+    /// `parent_child` columns. Arrays are kept as JSON text.
+    pub(crate) fn object_to_row(&self, obj: &serde_json::Map<String, serde_json::Value>) -> Row {
+        let mut row = Row::new();
+        for (key, value) in obj {
+            self.insert_value(&mut row, key.clone(), value, &mut |_, value| {
+                JsonSource::json_value_to_value(value)
+            });
+        }
+        row
+    }
+
+    /// Insert a value under `column`, expanding nested objects into columns
+    /// down to the flatten depth. Everything else goes through `convert`,
+    /// which is given the column name.
+    pub(crate) fn insert_value(
+        &self,
+        row: &mut Row,
+        column: String,
+        value: &serde_json::Value,
+        convert: &mut dyn FnMut(&str, &serde_json::Value) -> Value,
+    ) {
+        self.insert_nested(row, column, value, self.depth, convert);
+    }
+
+    fn insert_nested(
+        &self,
+        row: &mut Row,
+        column: String,
+        value: &serde_json::Value,
+        depth: usize,
+        convert: &mut dyn FnMut(&str, &serde_json::Value) -> Value,
+    ) {
+        match value {
+            serde_json::Value::Object(nested) if depth > 0 => {
+                for (key, value) in nested {
+                    let column = format!("{}{}{}", column, self.separator, key);
+                    self.insert_nested(row, column, value, depth - 1, convert);
+                }
+            }
+            _ => {
+                let value = convert(&column, value);
+                row.insert(column, value);
+            }
+        }
+    }
+}
+
+/// JSON source that streams the elements of an array, the whole document or
+/// the one at the `root` option, so large files are never held in memory
 pub struct JsonSource {
     file_path: PathBuf,
-    data: Vec<serde_json::Value>,
-    current_index: usize,
+    encoding: TextEncoding,
+    compression: Compression,
+    root: JsonRoot,
+    flatten: Flatten,
+    reader: Option<JsonArrayReader<BufReader<Box<dyn Read + Send + Sync>>>>,
+    has_more_data: bool,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
 }
 
 impl JsonSource {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
         Ok(Self {
             file_path: PathBuf::from(file_path),
-            data: Vec::new(),
-            current_index: 0,
+            encoding: TextEncoding::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
+            root: JsonRoot::parse(options.get("root").map(String::as_str).unwrap_or(""))?,
+            flatten: Flatten::from_options(options)?,
+            reader: None,
+            has_more_data: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
         })
     }
 
-    fn json_value_to_value(&self, json_val: &serde_json::Value) -> Value {
+    /// Open the file positioned inside the record array
+    fn open_reader(
+        &self,
+        bytes_read: &Arc<AtomicU64>,
+    ) -> Result<JsonArrayReader<BufReader<Box<dyn Read + Send + Sync>>>> {
+        let file = stream::open_file(&self.file_path, self.compression, bytes_read)?;
+        JsonArrayReader::new(BufReader::new(self.encoding.decode(file)?), &self.root)
+    }
+
+    /// The next object in the array as a row. Elements that aren't objects are skipped.
+    fn next_row(&mut self) -> Result<Option<Row>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+        while let Some(value) = reader.next_value()? {
+            if let serde_json::Value::Object(obj) = value {
+                return Ok(Some(self.flatten.object_to_row(&obj)));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn json_value_to_value(json_val: &serde_json::Value) -> Value {
         match json_val {
             serde_json::Value::String(s) => {
                 // Try to parse string as date first, fall back to string
@@ -67,90 +200,309 @@ impl Source for JsonSource {
             )));
         }
 
-        let content = std::fs::read_to_string(&self.file_path)?;
-        let json: serde_json::Value = serde_json::from_str(&content)?;
-
-        // Expect an array of objects
-        if let serde_json::Value::Array(array) = json {
-            self.data = array;
-        } else {
-            return Err(TinyEtlError::Configuration(
-                "JSON file must contain an array of objects".to_string(),
-            ));
-        }
-
-        Ok(())
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.reset().await
     }
 
     async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
-        if self.data.is_empty() {
+        if self.reader.is_none() {
             self.connect().await?;
         }
 
-        let sample_data = self.data.iter().take(sample_size).collect::<Vec<_>>();
-
         let mut rows = Vec::new();
-        for json_obj in sample_data {
-            if let serde_json::Value::Object(obj) = json_obj {
-                let mut row = Row::new();
-                for (key, value) in obj {
-                    row.insert(key.clone(), self.json_value_to_value(value));
-                }
-                rows.push(row);
+        while rows.len() < sample_size {
+            match self.next_row()? {
+                Some(row) => rows.push(row),
+                None => break,
             }
         }
 
+        // Reset for future reading
+        self.reset().await?;
+
         SchemaInferer::infer_from_rows(&rows)
     }
 
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
-        let mut rows = Vec::new();
-        let end_index = std::cmp::min(self.current_index + batch_size, self.data.len());
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
 
-        for i in self.current_index..end_index {
-            if let serde_json::Value::Object(obj) = &self.data[i] {
-                let mut row = Row::new();
-                for (key, value) in obj {
-                    row.insert(key.clone(), self.json_value_to_value(value));
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            match self.next_row()? {
+                Some(row) => rows.push(row),
+                None => {
+                    self.has_more_data = false;
+                    break;
                 }
-                rows.push(row);
             }
         }
 
-        self.current_index = end_index;
         Ok(rows)
     }
 
     async fn estimated_row_count(&self) -> Result<Option<usize>> {
-        Ok(Some(self.data.len()))
+        // Count the elements in a sample and scale by the share of the file
+        // it took up. Arrays that end within the sample are counted exactly.
+        let bytes_read = Arc::new(AtomicU64::new(0));
+        let mut reader = self.open_reader(&bytes_read)?;
+        let mut count = 0usize;
+        while bytes_read.load(Ordering::Relaxed) < stream::ESTIMATE_SAMPLE_SIZE {
+            if !reader.skip_value()? {
+                return Ok(Some(count));
+            }
+            count += 1;
+        }
+
+        let consumed = bytes_read.load(Ordering::Relaxed);
+        let file_size = std::fs::metadata(&self.file_path)?.len();
+        Ok(Some(
+            (count as f64 * file_size as f64 / consumed as f64).round() as usize,
+        ))
     }
 
     async fn reset(&mut self) -> Result<()> {
-        self.current_index = 0;
+        self.reader = Some(self.open_reader(&self.bytes_read)?);
+        self.has_more_data = true;
         Ok(())
     }
 
     fn has_more(&self) -> bool {
-        self.current_index < self.data.len()
+        self.has_more_data && self.reader.is_some()
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+/// Writes a JSON array one element at a time
+struct JsonArrayWriter {
+    writer: BufWriter<CompressedWriter>,
+    pretty: bool,
+    /// Whether the array has elements, so the next one needs a comma
+    has_elements: bool,
+}
+
+impl JsonArrayWriter {
+    /// Start a new array
+    fn create(writer: CompressedWriter, pretty: bool) -> Result<Self> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(b"[")?;
+        Ok(Self {
+            writer,
+            pretty,
+            has_elements: false,
+        })
+    }
+
+    fn start_element(&mut self) -> Result<()> {
+        if self.has_elements {
+            self.writer.write_all(b",")?;
+        }
+        if self.pretty {
+            self.writer.write_all(b"\n  ")?;
+        }
+        self.has_elements = true;
+        Ok(())
+    }
+
+    /// Copy an element of an existing array as it was written
+    fn write_raw(&mut self, element: &[u8]) -> Result<()> {
+        self.start_element()?;
+        self.writer.write_all(element)?;
+        Ok(())
+    }
+
+    fn write_row(&mut self, columns: &[String], row: &Row) -> Result<()> {
+        self.start_element()?;
+        // Written by hand to keep the columns in schema order
+        let pretty = self.pretty;
+        let writer = &mut self.writer;
+        writer.write_all(b"{")?;
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            if pretty {
+                writer.write_all(b"\n    ")?;
+            }
+            serde_json::to_writer(&mut *writer, column)?;
+            writer.write_all(if pretty { b": " } else { b":" })?;
+            let value = row
+                .get(column)
+                .map(JsonTarget::value_to_json)
+                .unwrap_or(serde_json::Value::Null);
+            if pretty {
+                // Only nested JSON values span lines, and they are indented to match
+                let text = serde_json::to_string_pretty(&value)?;
+                writer.write_all(text.replace('\n', "\n    ").as_bytes())?;
+            } else {
+                serde_json::to_writer(&mut *writer, &value)?;
+            }
+        }
+        if pretty && !columns.is_empty() {
+            writer.write_all(b"\n  ")?;
+        }
+        writer.write_all(b"}")?;
+        Ok(())
+    }
+
+    /// Close the array and end any compressed stream
+    fn finish(mut self) -> Result<()> {
+        if self.pretty && self.has_elements {
+            self.writer.write_all(b"\n")?;
+        }
+        self.writer.write_all(b"]\n")?;
+        CompressedWriter::finish_buffered(self.writer)
     }
 }
 
 pub struct JsonTarget {
     file_path: PathBuf,
-    accumulated_rows: Vec<Row>,
-    schema: Option<Schema>,
+    compression: Compression,
+    pretty: bool,
+    writer: Option<JsonArrayWriter>,
+    /// Where the file is rewritten when appending, moved over the original
+    /// in finalize so a failed run leaves it as it was
+    staging_path: Option<PathBuf>,
+    /// Writer given to `with_output`, used in place of the file
+    output: Option<Box<dyn Write + Send + Sync>>,
+    column_order: Vec<String>,
 }
 
 impl JsonTarget {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `pretty: false` writes the array on one line instead of indenting it
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let pretty = match options.get("pretty").map(|p| p.trim().to_lowercase()) {
+            None => true,
+            Some(value) => match value.as_str() {
+                "" | "true" | "yes" => true,
+                "false" | "no" => false,
+                _ => {
+                    return Err(TinyEtlError::Configuration(format!(
+                        "Invalid pretty '{}': expected true or false",
+                        value
+                    )))
+                }
+            },
+        };
+
         Ok(Self {
             file_path: PathBuf::from(file_path),
-            accumulated_rows: Vec::new(),
-            schema: None,
+            compression: Compression::from_options(options, file_path)?,
+            pretty,
+            writer: None,
+            staging_path: None,
+            output: None,
+            column_order: Vec::new(),
         })
     }
 
-    fn value_to_json(&self, value: &Value) -> serde_json::Value {
+    /// Write to `output`, such as standard output, instead of creating the
+    /// file. The file name still tells the compression.
+    pub fn with_output<W: Write + Send + Sync + 'static>(mut self, output: W) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    fn create_writer(&self, path: &Path) -> Result<JsonArrayWriter> {
+        JsonArrayWriter::create(self.compression.compress(File::create(path)?)?, self.pretty)
+    }
+
+    /// Reopen the array in an existing file so new rows extend it. The file
+    /// is copied to a staging file that replaces it in `finalize`. Only the
+    /// end of an uncompressed file is read: the copy stops at the closing
+    /// bracket and writing carries on from there. Compressed files are
+    /// decompressed and their elements streamed into the staging file.
+    fn open_for_append(&mut self) -> Result<JsonArrayWriter> {
+        if self.compression != Compression::None {
+            return self.rewrite_for_append();
+        }
+
+        let mut file = File::open(&self.file_path)?;
+        let len = file.metadata()?.len();
+        let close = match Self::last_non_whitespace(&mut file, len)? {
+            // An empty file gets a new array
+            None => {
+                drop(file);
+                return self.create_writer(&self.file_path);
+            }
+            Some((position, b']')) => position,
+            Some(_) => return Err(self.not_an_array()),
+        };
+        let has_elements = match Self::last_non_whitespace(&mut file, close)? {
+            Some((_, b'[')) => false,
+            Some(_) => true,
+            None => return Err(self.not_an_array()),
+        };
+
+        let staging = self.staging_path();
+        let mut output = File::create(&staging)?;
+        file.seek(SeekFrom::Start(0))?;
+        std::io::copy(&mut file.take(close), &mut output)?;
+        self.staging_path = Some(staging);
+        Ok(JsonArrayWriter {
+            writer: BufWriter::new(self.compression.compress(output)?),
+            pretty: self.pretty,
+            has_elements,
+        })
+    }
+
+    fn rewrite_for_append(&mut self) -> Result<JsonArrayWriter> {
+        let staging = self.staging_path();
+        let mut writer = self.create_writer(&staging)?;
+        let input = self.compression.decompress(File::open(&self.file_path)?)?;
+        let mut reader = JsonArrayReader::new(BufReader::new(input), &JsonRoot::default())?;
+        let mut element = Vec::new();
+        while reader.next_raw(&mut element)? {
+            writer.write_raw(&element)?;
+            element.clear();
+        }
+
+        self.staging_path = Some(staging);
+        Ok(writer)
+    }
+
+    /// `items.json.tmp` next to `items.json`, so the rename stays on one file system
+    fn staging_path(&self) -> PathBuf {
+        let mut staging = self.file_path.clone().into_os_string();
+        staging.push(".tmp");
+        PathBuf::from(staging)
+    }
+
+    /// The last byte before `end` that isn't whitespace, and its position,
+    /// reading back from `end` a block at a time
+    fn last_non_whitespace(file: &mut File, mut end: u64) -> Result<Option<(u64, u8)>> {
+        let mut block = [0u8; 4096];
+        while end > 0 {
+            let start = end.saturating_sub(block.len() as u64);
+            let block = &mut block[..(end - start) as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(block)?;
+            if let Some(i) = block.iter().rposition(|b| !b.is_ascii_whitespace()) {
+                return Ok(Some((start + i as u64, block[i])));
+            }
+            end = start;
+        }
+        Ok(None)
+    }
+
+    fn not_an_array(&self) -> TinyEtlError {
+        TinyEtlError::DataValidation(format!(
+            "Cannot append to {}: it does not end with a JSON array",
+            self.file_path.display()
+        ))
+    }
+
+    pub(crate) fn value_to_json(value: &Value) -> serde_json::Value {
         match value {
             Value::String(s) => serde_json::Value::String(s.clone()),
             Value::Integer(i) => serde_json::Value::Number(serde_json::Number::from(*i)),
@@ -173,34 +525,6 @@ impl JsonTarget {
             Value::Null => serde_json::Value::Null,
         }
     }
-
-    fn json_to_value(json_val: &serde_json::Value) -> Value {
-        match json_val {
-            serde_json::Value::String(s) => {
-                // Try to parse as date first
-                if let Some(date_value) = crate::date_parser::DateParser::try_parse(s) {
-                    date_value
-                } else {
-                    Value::String(s.clone())
-                }
-            }
-            serde_json::Value::Number(n) => {
-                if let Some(i) = n.as_i64() {
-                    Value::Integer(i)
-                } else if let Some(f) = n.as_f64() {
-                    match rust_decimal::Decimal::try_from(f) {
-                        Ok(d) => Value::Decimal(d),
-                        Err(_) => Value::String(n.to_string()),
-                    }
-                } else {
-                    Value::String(n.to_string())
-                }
-            }
-            serde_json::Value::Bool(b) => Value::Boolean(*b),
-            serde_json::Value::Null => Value::Null,
-            _ => Value::String(json_val.to_string()), // Fallback for arrays/objects
-        }
-    }
 }
 
 #[async_trait]
@@ -214,69 +538,45 @@ impl Target for JsonTarget {
     }
 
     async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
-        self.schema = Some(schema.clone());
-
-        // If file exists and we support append, load existing data
-        if self.file_path.exists() && self.supports_append() {
-            if let Ok(content) = std::fs::read_to_string(&self.file_path) {
-                if let Ok(existing_json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(array) = existing_json.as_array() {
-                        // Convert existing JSON objects back to Row format
-                        for json_obj in array {
-                            if let Some(obj) = json_obj.as_object() {
-                                let mut row = Row::new();
-                                for (key, json_val) in obj {
-                                    let value = Self::json_to_value(json_val);
-                                    row.insert(key.clone(), value);
-                                }
-                                self.accumulated_rows.push(row);
-                            }
-                        }
-                    }
-                }
-            }
+        self.column_order = schema.columns.iter().map(|c| c.name.clone()).collect();
+        if self.writer.is_none() {
+            self.writer = Some(if let Some(output) = self.output.take() {
+                JsonArrayWriter::create(self.compression.compress(output)?, self.pretty)?
+            } else if self.file_path.exists() {
+                self.open_for_append()?
+            } else {
+                self.create_writer(&self.file_path)?
+            });
         }
-
         Ok(())
     }
 
     async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
-        // Accumulate rows - we'll write them all at once in finalize()
-        self.accumulated_rows.extend_from_slice(rows);
-        Ok(rows.len())
-    }
-
-    async fn finalize(&mut self) -> Result<()> {
-        // Convert all accumulated rows to JSON and write to file
-        let mut json_objects = Vec::new();
-
-        for row in &self.accumulated_rows {
-            let mut json_obj = serde_json::Map::new();
-
-            // Use schema order if available, otherwise use row keys
-            if let Some(schema) = &self.schema {
-                for column in &schema.columns {
-                    let json_value = row
-                        .get(&column.name)
-                        .map(|v| self.value_to_json(v))
-                        .unwrap_or(serde_json::Value::Null);
-                    json_obj.insert(column.name.clone(), json_value);
-                }
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Writer not connected".to_string()))?;
+
+        for row in rows {
+            if self.column_order.is_empty() {
+                let mut keys: Vec<String> = row.keys().cloned().collect();
+                keys.sort();
+                writer.write_row(&keys, row)?;
             } else {
-                // Fallback: iterate over row keys
-                for (key, value) in row {
-                    json_obj.insert(key.clone(), self.value_to_json(value));
-                }
+                writer.write_row(&self.column_order, row)?;
             }
-
-            json_objects.push(serde_json::Value::Object(json_obj));
         }
 
-        // Write JSON array to file
-        let json_array = serde_json::Value::Array(json_objects);
-        let json_string = serde_json::to_string_pretty(&json_array)?;
-        std::fs::write(&self.file_path, json_string)?;
+        Ok(rows.len())
+    }
 
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finish()?;
+        }
+        if let Some(staging) = self.staging_path.take() {
+            std::fs::rename(staging, &self.file_path)?;
+        }
         Ok(())
     }
 
@@ -285,13 +585,13 @@ impl Target for JsonTarget {
     }
 
     async fn truncate(&mut self, _table_name: &str) -> Result<()> {
-        // For JSON files, truncation means clearing accumulated rows
-        self.accumulated_rows.clear();
+        self.staging_path = None;
+        self.writer = Some(self.create_writer(&self.file_path)?);
         Ok(())
     }
 
     fn supports_append(&self) -> bool {
-        // JSON arrays support append - we can merge existing data with new data
+        // New rows extend the array at the end of the file
         true
     }
 }
@@ -299,6 +599,7 @@ impl Target for JsonTarget {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::connectors::options;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -317,7 +618,23 @@ mod tests {
         let mut source = JsonSource::new(temp_file.path().to_str().unwrap()).unwrap();
         let result = source.connect().await;
         assert!(result.is_ok());
-        assert_eq!(source.data.len(), 2);
+        assert_eq!(source.estimated_row_count().await.unwrap(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_json_source_utf16_with_bom() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        let json = r#"[{"id": 1, "name": "Zoë"}]"#;
+        let bytes: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(json.encode_utf16().flat_map(|u| u.to_le_bytes()))
+            .collect();
+        temp_file.write_all(&bytes).unwrap();
+
+        let mut source = JsonSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert!(matches!(rows[0].get("name"), Some(Value::String(s)) if s == "Zoë"));
     }
 
     #[tokio::test]
@@ -378,6 +695,90 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_json_source_root_and_flatten() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            r#"{{
+                "meta": {{"page": 1}},
+                "data": {{"items": [
+                    {{"id": 1, "user": {{"name": "Alice", "address": {{"city": "Oslo"}}}}}},
+                    {{"id": 2, "user": {{"name": "Bob"}}, "tags": ["a"]}}
+                ]}}
+            }}"#
+        )
+        .unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let mut source = JsonSource::with_options(
+            path,
+            &options(&[("root", "$.data.items"), ("flatten", "1")]),
+        )
+        .unwrap();
+        source.connect().await.unwrap();
+        assert_eq!(source.estimated_row_count().await.unwrap(), Some(2));
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].get("user_name"),
+            Some(&Value::String("Alice".to_string()))
+        );
+        // Deeper than the flatten depth stays JSON text
+        assert_eq!(
+            rows[0].get("user_address"),
+            Some(&Value::String(r#"{"city":"Oslo"}"#.to_string()))
+        );
+        assert_eq!(
+            rows[1].get("tags"),
+            Some(&Value::String(r#"["a"]"#.to_string()))
+        );
+
+        let mut source = JsonSource::with_options(
+            path,
+            &options(&[
+                ("root", "/data/items"),
+                ("flatten", "true"),
+                ("flatten_separator", "."),
+            ]),
+        )
+        .unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(
+            rows[0].get("user.address.city"),
+            Some(&Value::String("Oslo".to_string()))
+        );
+
+        let mut source =
+            JsonSource::with_options(path, &options(&[("root", "$.data.missing")])).unwrap();
+        assert!(source.connect().await.is_err());
+        assert!(JsonSource::with_options(path, &options(&[("flatten", "deep")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_source_streams_large_array() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "[").unwrap();
+        for i in 0..50_000 {
+            if i > 0 {
+                write!(temp_file, ",").unwrap();
+            }
+            write!(temp_file, r#"{{"id": {}, "name": "name_{:05}"}}"#, i, i).unwrap();
+        }
+        write!(temp_file, "]").unwrap();
+
+        let mut source = JsonSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let count = source.estimated_row_count().await.unwrap().unwrap();
+        assert!((49_000..=51_000).contains(&count), "estimate {}", count);
+
+        let mut total = 0;
+        while source.has_more() {
+            total += source.read_batch(10_000).await.unwrap().len();
+        }
+        assert_eq!(total, 50_000);
+    }
+
     #[tokio::test]
     async fn test_json_target_creation() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -450,24 +851,25 @@ mod tests {
 
     #[tokio::test]
     async fn test_json_target_value_conversion() {
-        let target = JsonTarget::new("/tmp/test.json").unwrap();
-
         assert_eq!(
-            target.value_to_json(&Value::String("test".to_string())),
+            JsonTarget::value_to_json(&Value::String("test".to_string())),
             serde_json::Value::String("test".to_string())
         );
         assert_eq!(
-            target.value_to_json(&Value::Integer(42)),
+            JsonTarget::value_to_json(&Value::Integer(42)),
             serde_json::Value::Number(serde_json::Number::from(42))
         );
         assert_eq!(
-            target.value_to_json(&Value::Boolean(true)),
+            JsonTarget::value_to_json(&Value::Boolean(true)),
             serde_json::Value::Bool(true)
         );
-        assert_eq!(target.value_to_json(&Value::Null), serde_json::Value::Null);
+        assert_eq!(
+            JsonTarget::value_to_json(&Value::Null),
+            serde_json::Value::Null
+        );
 
         // Test decimal conversion
-        let decimal_val = target.value_to_json(&Value::Decimal(Decimal::new(314, 2)));
+        let decimal_val = JsonTarget::value_to_json(&Value::Decimal(Decimal::new(314, 2)));
         assert!(decimal_val.is_number());
     }
 
@@ -509,4 +911,160 @@ mod tests {
         assert_eq!(array[0]["name"], "Alice");
         assert_eq!(array[1]["name"], "Bob");
     }
+
+    async fn write_ids(path: &str, options: &HashMap<String, String>, ids: &[i64]) -> Result<()> {
+        let schema = Schema {
+            columns: vec![
+                crate::schema::Column {
+                    name: "id".to_string(),
+                    data_type: crate::schema::DataType::Integer,
+                    nullable: false,
+                },
+                crate::schema::Column {
+                    name: "tags".to_string(),
+                    data_type: crate::schema::DataType::Json,
+                    nullable: true,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let rows: Vec<Row> = ids
+            .iter()
+            .map(|&id| {
+                HashMap::from([
+                    ("id".to_string(), Value::Integer(id)),
+                    ("tags".to_string(), Value::Json(serde_json::json!(["a"]))),
+                ])
+            })
+            .collect();
+
+        let mut target = JsonTarget::with_options(path, options)?;
+        target.connect().await?;
+        target.create_table("items", &schema).await?;
+        target.write_batch(&rows).await?;
+        target.finalize().await
+    }
+
+    #[tokio::test]
+    async fn test_json_target_pretty_and_compact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("items.json");
+        let path = path.to_str().unwrap();
+
+        write_ids(path, &HashMap::new(), &[1, 2]).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "[\n  {\n    \"id\": 1,\n    \"tags\": [\n      \"a\"\n    ]\n  },\n  {\n    \"id\": 2,\n    \"tags\": [\n      \"a\"\n    ]\n  }\n]\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+        let compact = HashMap::from([("pretty".to_string(), "false".to_string())]);
+        write_ids(path, &compact, &[1, 2]).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "[{\"id\":1,\"tags\":[\"a\"]},{\"id\":2,\"tags\":[\"a\"]}]\n"
+        );
+
+        std::fs::remove_file(path).unwrap();
+        write_ids(path, &compact, &[]).await.unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "[]\n");
+
+        let invalid = HashMap::from([("pretty".to_string(), "maybe".to_string())]);
+        assert!(JsonTarget::with_options(path, &invalid).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_target_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("items.json");
+        let path = path.to_str().unwrap();
+        let ids = |path: &str| -> Vec<i64> {
+            let content = std::fs::read_to_string(path).unwrap();
+            let array: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+            array
+                .iter()
+                .map(|obj| obj["id"].as_i64().unwrap())
+                .collect()
+        };
+
+        // Existing elements are kept as written, whatever their formatting
+        std::fs::write(path, "[{\"id\": 1, \"extra\": \"]\"}]  \n\n").unwrap();
+        write_ids(path, &HashMap::new(), &[2, 3]).await.unwrap();
+        let compact = HashMap::from([("pretty".to_string(), "false".to_string())]);
+        write_ids(path, &compact, &[4]).await.unwrap();
+        assert_eq!(ids(path), vec![1, 2, 3, 4]);
+        assert!(std::fs::read_to_string(path)
+            .unwrap()
+            .starts_with("[{\"id\": 1, \"extra\": \"]\"},\n  {"));
+
+        // A run that fails before finalize leaves the file as it was
+        let before = std::fs::read_to_string(path).unwrap();
+        let mut target = JsonTarget::new(path).unwrap();
+        target.connect().await.unwrap();
+        let schema = Schema {
+            columns: vec![crate::schema::Column {
+                name: "id".to_string(),
+                data_type: crate::schema::DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        target.create_table("items", &schema).await.unwrap();
+        let row = HashMap::from([("id".to_string(), Value::Integer(5))]);
+        target.write_batch(&[row]).await.unwrap();
+        drop(target);
+        assert_eq!(std::fs::read_to_string(path).unwrap(), before);
+
+        std::fs::write(path, "[ ]").unwrap();
+        write_ids(path, &HashMap::new(), &[5]).await.unwrap();
+        assert_eq!(ids(path), vec![5]);
+
+        std::fs::write(path, "").unwrap();
+        write_ids(path, &HashMap::new(), &[6]).await.unwrap();
+        assert_eq!(ids(path), vec![6]);
+
+        std::fs::write(path, "{\"id\": 1}").unwrap();
+        let err = write_ids(path, &HashMap::new(), &[7]).await.unwrap_err();
+        assert!(
+            err.to_string().contains("does not end with a JSON array"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_compressed_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.json.zst");
+        let path = path.to_str().unwrap();
+        let schema = Schema {
+            columns: vec![crate::schema::Column {
+                name: "id".to_string(),
+                data_type: crate::schema::DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+
+        // The second run appends to what the first wrote
+        for id in 1..=2 {
+            let mut target = JsonTarget::new(path).unwrap();
+            target.connect().await.unwrap();
+            target.create_table("events", &schema).await.unwrap();
+            let row = HashMap::from([("id".to_string(), Value::Integer(id))]);
+            target.write_batch(&[row]).await.unwrap();
+            target.finalize().await.unwrap();
+        }
+        assert!(std::fs::read_to_string(path).is_err());
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        let mut source = JsonSource::new(path).unwrap();
+        source.connect().await.unwrap();
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].get("id"), Some(&Value::Integer(2)));
+    }
 }
@@ -0,0 +1,383 @@
+use std::fmt;
+use std::io::BufRead;
+
+use crate::{Result, TinyEtlError};
+
+/// Location of the record array in a JSON document, from the `root` option.
+/// Accepts JSONPath-style paths (`$.data.items`, `$['data'][0]`, `data.items`)
+/// and JSON pointers (`/data/items`). Array indexes select one element.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct JsonRoot {
+    expression: String,
+    segments: Vec<String>,
+}
+
+impl JsonRoot {
+    pub(crate) fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let invalid = |reason: &str| {
+            TinyEtlError::Configuration(format!("Invalid JSON root '{}': {}", expression, reason))
+        };
+
+        let mut segments = Vec::new();
+        if let Some(pointer) = expression.strip_prefix('/') {
+            segments = pointer
+                .split('/')
+                .map(|s| s.replace("~1", "/").replace("~0", "~"))
+                .collect();
+        } else {
+            let path = expression.strip_prefix('$').unwrap_or(expression);
+            let mut rest = path;
+            // `data.items` is read as `$.data.items`
+            if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                segments.push(rest[..end].to_string());
+                rest = &rest[end..];
+            }
+            while !rest.is_empty() {
+                if let Some(after) = rest.strip_prefix('.') {
+                    let end = after.find(['.', '[']).unwrap_or(after.len());
+                    if end == 0 {
+                        return Err(invalid("empty key"));
+                    }
+                    segments.push(after[..end].to_string());
+                    rest = &after[end..];
+                } else if let Some(after) = rest.strip_prefix('[') {
+                    let close = after.find(']').ok_or_else(|| invalid("missing ']'"))?;
+                    let inner = after[..close].trim();
+                    let quoted = inner.len() >= 2
+                        && ((inner.starts_with('\'') && inner.ends_with('\''))
+                            || (inner.starts_with('"') && inner.ends_with('"')));
+                    if quoted {
+                        segments.push(inner[1..inner.len() - 1].to_string());
+                    } else if inner == "*" || inner.parse::<usize>().is_ok() {
+                        segments.push(inner.to_string());
+                    } else {
+                        return Err(invalid("brackets must hold an index, '*' or a quoted key"));
+                    }
+                    rest = &after[close + 1..];
+                } else {
+                    return Err(invalid("expected '.' or '['"));
+                }
+            }
+        }
+
+        // `$.items[*]` selects each item of the array, which is what records are anyway
+        if segments.last().is_some_and(|s| s == "*") {
+            segments.pop();
+        }
+        if segments.iter().any(|s| s == "*") {
+            return Err(invalid("wildcards are only supported at the end"));
+        }
+
+        Ok(Self {
+            expression: expression.to_string(),
+            segments,
+        })
+    }
+
+    pub(crate) fn is_document(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+impl fmt::Display for JsonRoot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// Reads the elements of one array in a JSON document, one at a time, without
+/// parsing or holding the rest of the document
+pub(crate) struct JsonArrayReader<R: BufRead> {
+    reader: R,
+    /// Bytes consumed, for error messages
+    position: usize,
+    started: bool,
+    finished: bool,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> JsonArrayReader<R> {
+    /// Position the reader inside the array at `root`
+    pub(crate) fn new(reader: R, root: &JsonRoot) -> Result<Self> {
+        let mut this = Self {
+            reader,
+            position: 0,
+            started: false,
+            finished: false,
+            buffer: Vec::new(),
+        };
+
+        for segment in &root.segments {
+            if !this.descend(segment)? {
+                return Err(TinyEtlError::Configuration(format!(
+                    "JSON root '{}' was not found",
+                    root
+                )));
+            }
+        }
+        if this.skip_whitespace()? != Some(b'[') {
+            return Err(TinyEtlError::Configuration(if root.is_document() {
+                "JSON file must contain an array of objects, or set the `root` option to the path of one".to_string()
+            } else {
+                format!("JSON root '{}' is not an array", root)
+            }));
+        }
+        this.bump();
+        Ok(this)
+    }
+
+    /// The next element of the array
+    pub(crate) fn next_value(&mut self) -> Result<Option<serde_json::Value>> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.clear();
+        let value = if self.next_element(Some(&mut buffer))? {
+            Some(serde_json::from_slice(&buffer)?)
+        } else {
+            None
+        };
+        self.buffer = buffer;
+        Ok(value)
+    }
+
+    /// Append the text of the next element to `out` without parsing it,
+    /// returning false at the end of the array
+    pub(crate) fn next_raw(&mut self, out: &mut Vec<u8>) -> Result<bool> {
+        self.next_element(Some(out))
+    }
+
+    /// Skip the next element, returning false at the end of the array
+    pub(crate) fn skip_value(&mut self) -> Result<bool> {
+        self.next_element(None)
+    }
+
+    fn next_element(&mut self, out: Option<&mut Vec<u8>>) -> Result<bool> {
+        if self.finished {
+            return Ok(false);
+        }
+        match self.skip_whitespace()? {
+            Some(b']') => {
+                self.bump();
+                self.finished = true;
+                return Ok(false);
+            }
+            Some(b',') if self.started => self.bump(),
+            _ if self.started => return Err(self.error("expected ',' or ']'")),
+            _ => {}
+        }
+        self.started = true;
+        self.copy_value(out)?;
+        Ok(true)
+    }
+
+    /// Move into the value under `segment`, a key of an object or an index of
+    /// an array. Returns false when there is no such value.
+    fn descend(&mut self, segment: &str) -> Result<bool> {
+        match self.skip_whitespace()? {
+            Some(b'{') => {
+                self.bump();
+                loop {
+                    match self.skip_whitespace()? {
+                        Some(b'}') => return Ok(false),
+                        Some(b'"') => {}
+                        _ => return Err(self.error("expected an object key")),
+                    }
+                    let mut key = Vec::new();
+                    self.copy_value(Some(&mut key))?;
+                    let key: String = serde_json::from_slice(&key)?;
+                    if self.skip_whitespace()? != Some(b':') {
+                        return Err(self.error("expected ':'"));
+                    }
+                    self.bump();
+                    if key == segment {
+                        return Ok(true);
+                    }
+                    self.copy_value(None)?;
+                    match self.skip_whitespace()? {
+                        Some(b',') => self.bump(),
+                        Some(b'}') => return Ok(false),
+                        _ => return Err(self.error("expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'[') => {
+                let index: usize = match segment.parse() {
+                    Ok(index) => index,
+                    Err(_) => return Ok(false),
+                };
+                self.bump();
+                let mut current = 0;
+                loop {
+                    match self.skip_whitespace()? {
+                        Some(b']') => return Ok(false),
+                        None => return Err(self.error("unexpected end of file")),
+                        Some(_) if current == index => return Ok(true),
+                        Some(_) => {}
+                    }
+                    self.copy_value(None)?;
+                    match self.skip_whitespace()? {
+                        Some(b',') => self.bump(),
+                        Some(b']') => return Ok(false),
+                        _ => return Err(self.error("expected ',' or ']'")),
+                    }
+                    current += 1;
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Copy one complete value (string, number, literal, object or array) to
+    /// `out`, or skip it when `out` is None
+    fn copy_value(&mut self, mut out: Option<&mut Vec<u8>>) -> Result<()> {
+        let first = match self.skip_whitespace()? {
+            Some(byte) => byte,
+            None => return Err(self.error("unexpected end of file")),
+        };
+        let scalar = !matches!(first, b'{' | b'[' | b'"');
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        loop {
+            let buf = self.reader.fill_buf()?;
+            if buf.is_empty() {
+                return Err(self.error("unexpected end of file"));
+            }
+
+            let mut end = None;
+            for (i, &byte) in buf.iter().enumerate() {
+                if scalar {
+                    if matches!(byte, b',' | b']' | b'}') || byte.is_ascii_whitespace() {
+                        end = Some(i);
+                        break;
+                    }
+                } else if in_string {
+                    if escaped {
+                        escaped = false;
+                    } else if byte == b'\\' {
+                        escaped = true;
+                    } else if byte == b'"' {
+                        in_string = false;
+                        if depth == 0 {
+                            end = Some(i + 1);
+                            break;
+                        }
+                    }
+                } else {
+                    match byte {
+                        b'"' => in_string = true,
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth = depth.saturating_sub(1);
+                            if depth == 0 {
+                                end = Some(i + 1);
+                                break;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            let n = end.unwrap_or(buf.len());
+            if let Some(out) = out.as_deref_mut() {
+                out.extend_from_slice(&buf[..n]);
+            }
+            self.reader.consume(n);
+            self.position += n;
+            if end.is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Skip whitespace and return the next byte without consuming it
+    fn skip_whitespace(&mut self) -> Result<Option<u8>> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            let Some(&byte) = buf.first() else {
+                return Ok(None);
+            };
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.bump();
+        }
+    }
+
+    fn bump(&mut self) {
+        self.reader.consume(1);
+        self.position += 1;
+    }
+
+    fn error(&self, message: &str) -> TinyEtlError {
+        TinyEtlError::Json(serde::de::Error::custom(format!(
+            "{} at byte {}",
+            message, self.position
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn root(expression: &str) -> Vec<String> {
+        JsonRoot::parse(expression).unwrap().segments
+    }
+
+    fn elements(json: &str, expression: &str) -> Result<Vec<serde_json::Value>> {
+        let root = JsonRoot::parse(expression)?;
+        let mut reader = JsonArrayReader::new(Cursor::new(json.as_bytes().to_vec()), &root)?;
+        let mut values = Vec::new();
+        while let Some(value) = reader.next_value()? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    #[test]
+    fn test_parse_root() {
+        assert!(root("").is_empty());
+        assert!(root("$").is_empty());
+        assert_eq!(root("$.data.items"), vec!["data", "items"]);
+        assert_eq!(root("data.items[*]"), vec!["data", "items"]);
+        assert_eq!(root("$['odd key'][2].x"), vec!["odd key", "2", "x"]);
+        assert_eq!(root("/data/a~1b"), vec!["data", "a/b"]);
+        assert!(JsonRoot::parse("$.items[*].id").is_err());
+        assert!(JsonRoot::parse("$.items[").is_err());
+    }
+
+    #[test]
+    fn test_reads_nested_array() {
+        let json = r#"{
+            "meta": {"skip": [1, {"items": "no"}], "s": "a\"]}"},
+            "data": {"count": 2, "items": [{"id": 1, "tags": ["x"]}, {"id": 2, "name": "]},"}]},
+            "trailer": true
+        }"#;
+        let values = elements(json, "$.data.items").unwrap();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1]["name"], "]},");
+
+        assert_eq!(elements("[1, -2.5e3, null]", "").unwrap().len(), 3);
+        assert!(elements("[]", "").unwrap().is_empty());
+        assert_eq!(
+            elements(r#"{"pages": [[], [{"id": 7}]]}"#, "$.pages[1]").unwrap()[0]["id"],
+            7
+        );
+    }
+
+    #[test]
+    fn test_root_errors() {
+        let err = elements(r#"{"data": {}}"#, "$.data.items").unwrap_err();
+        assert!(err.to_string().contains("was not found"), "{}", err);
+        let err = elements(r#"{"data": {"items": 5}}"#, "$.data.items").unwrap_err();
+        assert!(err.to_string().contains("is not an array"), "{}", err);
+        assert!(elements(r#"{"not": "an array"}"#, "").is_err());
+        assert!(elements(r#"[{"id": 1} {"id": 2}]"#, "").is_err());
+        assert!(elements(r#"[{"id": 1}"#, "").is_err());
+    }
+}
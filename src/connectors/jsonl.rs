@@ -0,0 +1,443 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::{
+    connectors::{
+        compression::{CompressedWriter, Compression},
+        encoding::TextEncoding,
+        json::{Flatten, JsonTarget},
+        stream, Source, Target,
+    },
+    schema::{Row, Schema, SchemaInferer},
+    Result, TinyEtlError,
+};
+
+/// JSON Lines (NDJSON) source: one JSON object per line, read as the file is streamed
+pub struct JsonlSource {
+    file_path: PathBuf,
+    encoding: TextEncoding,
+    compression: Compression,
+    flatten: Flatten,
+    reader: Option<BufReader<Box<dyn Read + Send + Sync>>>,
+    /// Line number of the last line read, for error messages
+    line_number: usize,
+    has_more_data: bool,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
+}
+
+impl JsonlSource {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            encoding: TextEncoding::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
+            flatten: Flatten::from_options(options)?,
+            reader: None,
+            line_number: 0,
+            has_more_data: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
+        })
+    }
+
+    fn open_reader(&self) -> Result<BufReader<Box<dyn Read + Send + Sync>>> {
+        let file = stream::open_file(&self.file_path, self.compression, &self.bytes_read)?;
+        Ok(BufReader::new(self.encoding.decode(file)?))
+    }
+
+    /// Parse the next non-blank line into a row
+    fn next_row(&mut self) -> Result<Option<Row>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.line_number += 1;
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+
+        let json: serde_json::Value = serde_json::from_str(line.trim()).map_err(|e| {
+            TinyEtlError::DataValidation(format!(
+                "Invalid JSON on line {} of {}: {}",
+                self.line_number,
+                self.file_path.display(),
+                e
+            ))
+        })?;
+        match json {
+            serde_json::Value::Object(obj) => Ok(Some(self.flatten.object_to_row(&obj))),
+            _ => Err(TinyEtlError::DataValidation(format!(
+                "Line {} of {} is not a JSON object",
+                self.line_number,
+                self.file_path.display()
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Source for JsonlSource {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "JSON Lines file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.reset().await
+    }
+
+    async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        let mut sample_rows = Vec::new();
+        while sample_rows.len() < sample_size {
+            match self.next_row()? {
+                Some(row) => sample_rows.push(row),
+                None => break,
+            }
+        }
+
+        // Reset for future reading
+        self.reset().await?;
+
+        SchemaInferer::infer_from_rows(&sample_rows)
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            match self.next_row()? {
+                Some(row) => rows.push(row),
+                None => {
+                    self.has_more_data = false;
+                    break;
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        Ok(Some(stream::estimate_line_count(
+            &self.file_path,
+            self.compression,
+        )?))
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.reader = Some(self.open_reader()?);
+        self.line_number = 0;
+        self.has_more_data = true;
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more_data && self.reader.is_some()
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+/// JSON Lines (NDJSON) target: writes one JSON object per line as batches arrive,
+/// appending to an existing file
+pub struct JsonlTarget {
+    file_path: PathBuf,
+    compression: Compression,
+    writer: Option<BufWriter<CompressedWriter>>,
+    /// Writer given to `with_output`, used in place of the file
+    output: Option<Box<dyn Write + Send + Sync>>,
+    column_order: Vec<String>,
+}
+
+impl JsonlTarget {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            compression: Compression::from_options(options, file_path)?,
+            writer: None,
+            output: None,
+            column_order: Vec::new(),
+        })
+    }
+
+    /// Write to `output`, such as standard output, instead of creating the
+    /// file. The file name still tells the compression.
+    pub fn with_output<W: Write + Send + Sync + 'static>(mut self, output: W) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    /// Open the file for appending, or recreate it. Compressed files are
+    /// appended as a new stream, which decompressors read as one.
+    fn open_writer(&mut self, append: bool) -> Result<BufWriter<CompressedWriter>> {
+        if let Some(output) = self.output.take() {
+            return Ok(BufWriter::new(self.compression.compress(output)?));
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&self.file_path)?;
+
+        // Don't join the first new line onto an unterminated last line
+        let mut needs_newline = false;
+        if append && self.compression == Compression::None && file.metadata()?.len() > 0 {
+            let mut last = [0u8];
+            let mut reader = std::fs::File::open(&self.file_path)?;
+            reader.seek(SeekFrom::End(-1))?;
+            reader.read_exact(&mut last)?;
+            needs_newline = last[0] != b'\n';
+        }
+        if needs_newline {
+            file.write_all(b"\n")?;
+        }
+
+        Ok(BufWriter::new(self.compression.compress(file)?))
+    }
+
+    fn write_row<W: Write>(writer: &mut W, columns: &[String], row: &Row) -> Result<()> {
+        // Written by hand to keep the columns in schema order
+        writer.write_all(b"{")?;
+        for (i, column) in columns.iter().enumerate() {
+            if i > 0 {
+                writer.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut *writer, column)?;
+            writer.write_all(b":")?;
+            let value = row
+                .get(column)
+                .map(JsonTarget::value_to_json)
+                .unwrap_or(serde_json::Value::Null);
+            serde_json::to_writer(&mut *writer, &value)?;
+        }
+        writer.write_all(b"}\n")?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Target for JsonlTarget {
+    async fn connect(&mut self) -> Result<()> {
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = self.file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    }
+
+    async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
+        self.column_order = schema.columns.iter().map(|c| c.name.clone()).collect();
+        if self.writer.is_none() {
+            self.writer = Some(self.open_writer(true)?);
+        }
+        Ok(())
+    }
+
+    async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Writer not connected".to_string()))?;
+
+        for row in rows {
+            if self.column_order.is_empty() {
+                let mut keys: Vec<String> = row.keys().cloned().collect();
+                keys.sort();
+                Self::write_row(writer, &keys, row)?;
+            } else {
+                Self::write_row(writer, &self.column_order, row)?;
+            }
+        }
+
+        Ok(rows.len())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            CompressedWriter::finish_buffered(writer)?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, _table_name: &str) -> Result<bool> {
+        Ok(self.file_path.exists())
+    }
+
+    async fn truncate(&mut self, _table_name: &str) -> Result<()> {
+        self.writer = Some(self.open_writer(false)?);
+        Ok(())
+    }
+
+    fn supports_append(&self) -> bool {
+        // New lines are simply added to the end of the file
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Column, DataType, Value};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn schema() -> Schema {
+        Schema {
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                Column {
+                    name: "level".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        }
+    }
+
+    fn row(id: i64, level: &str) -> Row {
+        HashMap::from([
+            ("id".to_string(), Value::Integer(id)),
+            ("level".to_string(), Value::String(level.to_string())),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_source_reads_lines() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(
+            temp_file,
+            "{{\"id\": 1, \"level\": \"info\"}}\r\n\n{{\"id\": 2, \"level\": \"warn\", \"extra\": [1]}}"
+        )
+        .unwrap();
+
+        let mut source = JsonlSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let schema = source.infer_schema(10).await.unwrap();
+        assert_eq!(schema.columns.len(), 3);
+
+        let rows = source.read_batch(1).await.unwrap();
+        assert_eq!(rows[0].get("id"), Some(&Value::Integer(1)));
+        assert!(source.has_more());
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].get("extra"),
+            Some(&Value::String("[1]".to_string()))
+        );
+        assert!(!source.has_more());
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_source_reports_bad_line() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "{{\"id\": 1}}").unwrap();
+        writeln!(temp_file, "[1, 2]").unwrap();
+
+        let mut source = JsonlSource::new(temp_file.path().to_str().unwrap()).unwrap();
+        source.connect().await.unwrap();
+        let err = source.read_batch(10).await.unwrap_err();
+        assert!(err.to_string().contains("Line 2"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_target_appends() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        // Existing file without a trailing newline
+        write!(temp_file, "{{\"id\":0,\"level\":\"debug\"}}").unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let mut target = JsonlTarget::new(path).unwrap();
+        target.connect().await.unwrap();
+        assert!(target.supports_append());
+        target.create_table("logs", &schema()).await.unwrap();
+        target
+            .write_batch(&[row(1, "info"), row(2, "warn")])
+            .await
+            .unwrap();
+        target.finalize().await.unwrap();
+
+        let output = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            output,
+            "{\"id\":0,\"level\":\"debug\"}\n{\"id\":1,\"level\":\"info\"}\n{\"id\":2,\"level\":\"warn\"}\n"
+        );
+
+        let mut target = JsonlTarget::new(path).unwrap();
+        target.truncate("logs").await.unwrap();
+        target.create_table("logs", &schema()).await.unwrap();
+        target.write_batch(&[row(3, "error")]).await.unwrap();
+        target.finalize().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "{\"id\":3,\"level\":\"error\"}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_compressed_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.ndjson.gz");
+        let path = path.to_str().unwrap();
+
+        for id in 1..=2 {
+            let mut target = JsonlTarget::new(path).unwrap();
+            target.connect().await.unwrap();
+            target.create_table("logs", &schema()).await.unwrap();
+            target.write_batch(&[row(id, "info")]).await.unwrap();
+            target.finalize().await.unwrap();
+        }
+
+        let mut source = JsonlSource::new(path).unwrap();
+        source.connect().await.unwrap();
+        assert_eq!(source.estimated_row_count().await.unwrap(), Some(2));
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].get("id"), Some(&Value::Integer(2)));
+        let file_size = std::fs::metadata(path).unwrap().len();
+        assert_eq!(source.progress_bytes(), Some((file_size, file_size)));
+    }
+}
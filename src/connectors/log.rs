@@ -0,0 +1,581 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::{
+    connectors::{
+        compression::Compression, csv::CsvSource, encoding::TextEncoding, stream, Source,
+    },
+    schema::{DataType, Row, Schema, SchemaInferer, Value},
+    Result, TinyEtlError,
+};
+
+/// Capture group holding the time of a line, parsed with `timestamp_format`
+const TIMESTAMP_GROUP: &str = "timestamp";
+
+/// NCSA combined log format, as written by nginx and Apache. The referer and
+/// user agent are optional so common log format lines match too.
+const COMBINED: &str = r#"^(?P<remote_addr>\S+) (?P<ident>\S+) (?P<remote_user>\S+) \[(?P<timestamp>[^\]]+)\] "(?:(?P<method>[A-Z]+) (?P<path>\S+)(?: (?P<protocol>[^"]*))?|[^"]*)" (?P<status>\d{3}) (?P<bytes>\d+|-)(?: "(?P<referer>[^"]*)" "(?P<user_agent>[^"]*)")?"#;
+
+/// BSD syslog (RFC 3164), or with an RFC 3339 timestamp as rsyslog writes it
+const SYSLOG: &str = r"^(?:<(?P<priority>\d{1,3})>)?(?P<timestamp>[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\S+) (?P<hostname>\S+) (?P<app>[^\s\[:]+)(?:\[(?P<pid>\d+)\])?: ?(?P<message>.*)$";
+
+/// How the lines of a log are split into fields
+enum LineFormat {
+    /// Named capture groups of a regex
+    Pattern(Regex),
+    /// `key=value` pairs, with double-quoted values where they contain spaces
+    Logfmt,
+}
+
+impl LineFormat {
+    /// Fields of a line, or None when it doesn't match
+    fn fields<'a>(&'a self, line: &'a str) -> Option<Vec<(&'a str, Option<String>)>> {
+        match self {
+            Self::Pattern(regex) => {
+                let captures = regex.captures(line)?;
+                let fields = regex
+                    .capture_names()
+                    .flatten()
+                    .map(|name| (name, captures.name(name).map(|m| m.as_str().to_string())))
+                    .collect();
+                Some(fields)
+            }
+            Self::Logfmt => parse_logfmt(line),
+        }
+    }
+}
+
+/// Split a logfmt line into its pairs. A key without `=` is a flag, read as
+/// `true`. Lines without any `key=value` pair don't match.
+fn parse_logfmt(line: &str) -> Option<Vec<(&str, Option<String>)>> {
+    let mut fields = Vec::new();
+    let mut has_value = false;
+    let mut rest = line.trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        if key.is_empty() || key.contains('"') {
+            return None;
+        }
+        rest = &rest[key_end..];
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            has_value = true;
+            if let Some(quoted) = after.strip_prefix('"') {
+                let mut value = String::new();
+                let mut chars = quoted.char_indices();
+                let end = loop {
+                    match chars.next()? {
+                        (i, '"') => break i,
+                        (_, '\\') => match chars.next()?.1 {
+                            'n' => value.push('\n'),
+                            't' => value.push('\t'),
+                            escaped => value.push(escaped),
+                        },
+                        (_, c) => value.push(c),
+                    }
+                };
+                rest = &quoted[end + 1..];
+                Some(value)
+            } else {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                rest = &after[end..];
+                Some(after[..end].to_string()).filter(|value| !value.is_empty())
+            }
+        } else {
+            Some("true".to_string())
+        };
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        fields.push((key, value));
+        rest = rest.trim_start();
+    }
+    has_value.then_some(fields)
+}
+
+/// Built-in line formats
+struct Preset {
+    format: LineFormat,
+    timestamp_format: Option<&'static str>,
+    /// Text standing for a missing value
+    null_token: Option<&'static str>,
+}
+
+impl Preset {
+    fn named(name: &str) -> Result<Self> {
+        let pattern = |pattern| LineFormat::Pattern(Regex::new(pattern).expect("valid preset"));
+        let preset = match name.trim().to_lowercase().as_str() {
+            "combined" | "common" | "nginx" | "apache" => Self {
+                format: pattern(COMBINED),
+                timestamp_format: Some("%d/%b/%Y:%H:%M:%S %z"),
+                null_token: Some("-"),
+            },
+            "syslog" => Self {
+                format: pattern(SYSLOG),
+                timestamp_format: Some("%b %e %H:%M:%S"),
+                null_token: None,
+            },
+            "logfmt" => Self {
+                format: LineFormat::Logfmt,
+                timestamp_format: None,
+                null_token: None,
+            },
+            _ => {
+                return Err(TinyEtlError::Configuration(format!(
+                "Invalid value '{}' for log option 'preset': expected combined, syslog or logfmt",
+                name
+            )))
+            }
+        };
+        Ok(preset)
+    }
+}
+
+/// Parse a timestamp in `format`, with or without a UTC offset. Formats
+/// without a year, as in syslog, are read as the current year.
+fn parse_timestamp(text: &str, format: &str) -> Option<Value> {
+    if let Ok(time) = DateTime::parse_from_str(text, format) {
+        return Some(Value::Date(time.with_timezone(&Utc)));
+    }
+    let time = if format.contains("%Y") || format.contains("%y") {
+        NaiveDateTime::parse_from_str(text, format).ok()?
+    } else {
+        let with_year = format!("{} {}", Utc::now().year(), text);
+        NaiveDateTime::parse_from_str(&with_year, &format!("%Y {}", format)).ok()?
+    };
+    Some(Value::Date(Utc.from_utc_datetime(&time)))
+}
+
+/// Line-oriented log source: each line matching a regex with named capture
+/// groups, or a built-in preset, is a row keyed by group name. Lines that
+/// don't match are skipped, or copied to the `reject_file`.
+pub struct LogSource {
+    file_path: PathBuf,
+    encoding: TextEncoding,
+    compression: Compression,
+    format: LineFormat,
+    timestamp_format: Option<String>,
+    null_token: Option<String>,
+    reject_path: Option<PathBuf>,
+    reader: Option<BufReader<Box<dyn Read + Send + Sync>>>,
+    rejects: Option<BufWriter<File>>,
+    /// Lines that didn't match since the file was opened
+    rejected_lines: usize,
+    /// Columns in the order of the pattern's groups, or as first seen
+    columns: Vec<String>,
+    /// Column types from `set_schema`, e.g. a schema file
+    declared_types: HashMap<String, DataType>,
+    /// Column types from the last `infer_schema`
+    inferred_types: HashMap<String, DataType>,
+    has_more_data: bool,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
+}
+
+impl LogSource {
+    /// `pattern` is a regex with named capture groups, or `preset` names a
+    /// built-in format: `combined`, `syslog` or `logfmt`
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let preset = match (options.get("pattern"), options.get("preset")) {
+            (Some(pattern), None) => {
+                let regex = Regex::new(pattern).map_err(|e| {
+                    TinyEtlError::Configuration(format!(
+                        "Invalid value '{}' for log option 'pattern': {}",
+                        pattern, e
+                    ))
+                })?;
+                if regex.capture_names().flatten().next().is_none() {
+                    return Err(TinyEtlError::Configuration(format!(
+                        "Invalid value '{}' for log option 'pattern': it has no named groups such as (?P<status>\\d+)",
+                        pattern
+                    )));
+                }
+                Preset {
+                    format: LineFormat::Pattern(regex),
+                    timestamp_format: None,
+                    null_token: None,
+                }
+            }
+            (None, Some(name)) => Preset::named(name)?,
+            (Some(_), Some(_)) => {
+                return Err(TinyEtlError::Configuration(
+                    "Give either a log `pattern` or a `preset`, not both".to_string(),
+                ))
+            }
+            (None, None) => {
+                return Err(TinyEtlError::Configuration(format!(
+                    "Log source {} needs a `pattern` with named groups or a `preset` (combined, syslog or logfmt)",
+                    file_path
+                )))
+            }
+        };
+
+        let columns = match &preset.format {
+            LineFormat::Pattern(regex) => regex
+                .capture_names()
+                .flatten()
+                .map(str::to_string)
+                .collect(),
+            LineFormat::Logfmt => Vec::new(),
+        };
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            encoding: TextEncoding::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
+            format: preset.format,
+            timestamp_format: options
+                .get("timestamp_format")
+                .cloned()
+                .or_else(|| preset.timestamp_format.map(str::to_string)),
+            null_token: options
+                .get("null_value")
+                .cloned()
+                .or_else(|| preset.null_token.map(str::to_string)),
+            reject_path: options.get("reject_file").map(PathBuf::from),
+            reader: None,
+            rejects: None,
+            rejected_lines: 0,
+            columns,
+            declared_types: HashMap::new(),
+            inferred_types: HashMap::new(),
+            has_more_data: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
+        })
+    }
+
+    /// Lines that didn't match since the file was last opened
+    pub fn rejected_lines(&self) -> usize {
+        self.rejected_lines
+    }
+
+    fn open_reader(&self) -> Result<BufReader<Box<dyn Read + Send + Sync>>> {
+        let file = stream::open_file(&self.file_path, self.compression, &self.bytes_read)?;
+        Ok(BufReader::new(self.encoding.decode(file)?))
+    }
+
+    fn parse(&self, column: &str, text: &str) -> Value {
+        if text.is_empty() || self.null_token.as_deref() == Some(text) {
+            return Value::Null;
+        }
+        let data_type = self
+            .declared_types
+            .get(column)
+            .or_else(|| self.inferred_types.get(column));
+        if column == TIMESTAMP_GROUP && data_type != Some(&DataType::String) {
+            if let Some(time) = self
+                .timestamp_format
+                .as_deref()
+                .and_then(|format| parse_timestamp(text, format))
+            {
+                return time;
+            }
+        }
+        CsvSource::parse_as(text, data_type)
+    }
+
+    /// Next matching line as a row, copying the others to the reject file
+    fn next_row(&mut self) -> Result<Option<Row>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let reader = match self.reader.as_mut() {
+                Some(reader) => reader,
+                None => return Ok(None),
+            };
+            if reader.read_line(&mut line)? == 0 {
+                if let Some(rejects) = self.rejects.as_mut() {
+                    rejects.flush()?;
+                }
+                return Ok(None);
+            }
+            let text = line.trim_end_matches(['\n', '\r']);
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let Some(fields) = self.format.fields(text) else {
+                self.rejected_lines += 1;
+                if let Some(rejects) = self.rejects.as_mut() {
+                    writeln!(rejects, "{}", text)?;
+                }
+                continue;
+            };
+
+            let mut row = Row::new();
+            for (column, value) in fields {
+                if !self.columns.iter().any(|c| c == column) {
+                    self.columns.push(column.to_string());
+                }
+                let value = match value {
+                    Some(text) => self.parse(column, &text),
+                    None => Value::Null,
+                };
+                row.insert(column.to_string(), value);
+            }
+            return Ok(Some(row));
+        }
+    }
+}
+
+#[async_trait]
+impl Source for LogSource {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "Log file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.reset().await
+    }
+
+    async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        // Guess afresh rather than parsing as the previous inference's types
+        self.inferred_types.clear();
+        let mut sample_rows = Vec::new();
+        while sample_rows.len() < sample_size {
+            match self.next_row()? {
+                Some(row) => sample_rows.push(row),
+                None => break,
+            }
+        }
+
+        // Reset for future reading
+        self.reset().await?;
+
+        // Columns in the order of the pattern's groups
+        let mut schema = SchemaInferer::infer_from_rows(&sample_rows)?;
+        schema
+            .columns
+            .sort_by_key(|c| self.columns.iter().position(|name| *name == c.name));
+
+        // Parse the remaining rows consistently as the inferred types
+        self.inferred_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        Ok(schema)
+    }
+
+    fn set_schema(&mut self, schema: &Schema) {
+        self.declared_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            match self.next_row()? {
+                Some(row) => rows.push(row),
+                None => {
+                    // Once per read, not for the inference sample
+                    if self.has_more_data && self.rejected_lines > 0 {
+                        tracing::warn!(
+                            "{} lines of {} didn't match the log format",
+                            self.rejected_lines,
+                            self.file_path.display()
+                        );
+                    }
+                    self.has_more_data = false;
+                    break;
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        Ok(Some(stream::estimate_line_count(
+            &self.file_path,
+            self.compression,
+        )?))
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.reader = Some(self.open_reader()?);
+        // Rejects are written afresh on every pass over the file
+        if let Some(path) = &self.reject_path {
+            self.rejects = Some(BufWriter::new(File::create(path)?));
+        }
+        self.rejected_lines = 0;
+        self.has_more_data = true;
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more_data && self.reader.is_some()
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::options;
+    use rust_decimal::Decimal;
+    use tempfile::NamedTempFile;
+
+    fn log_file(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::with_suffix(".log").unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    async fn read_all(file: &NamedTempFile, options: &HashMap<String, String>) -> Vec<Row> {
+        let mut source = LogSource::with_options(file.path().to_str().unwrap(), options).unwrap();
+        source.connect().await.unwrap();
+        source.infer_schema(100).await.unwrap();
+        source.read_batch(100).await.unwrap()
+    }
+
+    #[test]
+    fn test_parse_logfmt() {
+        let fields = parse_logfmt(r#"level=info msg="user \"ada\" signed in" ms=12.5 cached"#);
+        assert_eq!(
+            fields.unwrap(),
+            [
+                ("level", Some("info".to_string())),
+                ("msg", Some("user \"ada\" signed in".to_string())),
+                ("ms", Some("12.5".to_string())),
+                ("cached", Some("true".to_string())),
+            ]
+        );
+        assert_eq!(parse_logfmt("empty= next=1").unwrap()[0], ("empty", None));
+        assert!(parse_logfmt("just some words").is_none());
+        assert!(parse_logfmt(r#"msg="unterminated"#).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_combined_preset() {
+        let file = log_file(concat!(
+            "203.0.113.7 - - [10/Oct/2024:13:55:36 -0700] \"GET /index.html HTTP/1.1\" 200 2326 \"https://example.com/\" \"Mozilla/5.0\"\n",
+            "203.0.113.8 - frank [10/Oct/2024:13:56:01 -0700] \"POST /api/orders HTTP/2.0\" 201 -\n",
+            "not an access log line\n",
+        ));
+        let rows = read_all(&file, &options(&[("preset", "combined")])).await;
+        assert_eq!(rows.len(), 2);
+
+        let time = DateTime::parse_from_rfc3339("2024-10-10T20:55:36Z").unwrap();
+        assert_eq!(rows[0]["timestamp"], Value::Date(time.with_timezone(&Utc)));
+        assert_eq!(rows[0]["method"], Value::String("GET".to_string()));
+        assert_eq!(rows[0]["status"], Value::Integer(200));
+        assert_eq!(rows[0]["bytes"], Value::Integer(2326));
+        assert_eq!(rows[0]["remote_user"], Value::Null);
+        assert_eq!(rows[1]["remote_user"], Value::String("frank".to_string()));
+        assert_eq!(rows[1]["bytes"], Value::Null);
+        assert_eq!(rows[1]["user_agent"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_syslog_and_logfmt_presets() {
+        let file = log_file(concat!(
+            "<34>Oct  3 22:14:15 web01 sshd[4721]: Failed password for root\n",
+            "2024-10-03T22:14:16.5+02:00 web01 cron: job started\n",
+        ));
+        let rows = read_all(&file, &options(&[("preset", "syslog")])).await;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["priority"], Value::Integer(34));
+        assert!(matches!(rows[0]["timestamp"], Value::Date(_)));
+        assert!(matches!(rows[1]["timestamp"], Value::Date(_)));
+        assert_eq!(rows[0]["pid"], Value::Integer(4721));
+        assert_eq!(rows[1]["pid"], Value::Null);
+        assert_eq!(rows[1]["app"], Value::String("cron".to_string()));
+
+        let file = log_file(concat!(
+            "ts=2024-10-03T22:14:15Z level=info path=/health ms=3.0\n",
+            "ts=2024-10-03T22:14:16Z level=warn ms=12.5 retry\n",
+        ));
+        let mut source = LogSource::with_options(
+            file.path().to_str().unwrap(),
+            &options(&[("preset", "logfmt")]),
+        )
+        .unwrap();
+        let schema = source.infer_schema(100).await.unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["ts", "level", "path", "ms", "retry"]);
+        let rows = source.read_batch(100).await.unwrap();
+        assert!(matches!(rows[0]["ts"], Value::Date(_)));
+        assert_eq!(rows[0]["ms"], Value::Decimal(Decimal::new(30, 1)));
+        assert_eq!(rows[1]["ms"], Value::Decimal(Decimal::new(125, 1)));
+        assert_eq!(rows[1]["retry"], Value::Boolean(true));
+        assert!(!rows[1].contains_key("path"));
+    }
+
+    #[tokio::test]
+    async fn test_pattern_with_reject_file() {
+        let file = log_file(concat!(
+            "2024-10-03 22:14:15 ERROR [billing] invoice 42 failed\n",
+            "    at Billing.charge(Billing.java:88)\n",
+            "2024-10-03 22:14:16 INFO [api] ok\n",
+        ));
+        let rejects = NamedTempFile::new().unwrap();
+        let options = options(&[
+            (
+                "pattern",
+                r"^(?P<timestamp>\S+ \S+) (?P<level>[A-Z]+) \[(?P<module>[^\]]+)\] (?P<message>.*)$",
+            ),
+            ("reject_file", rejects.path().to_str().unwrap()),
+        ]);
+        let mut source = LogSource::with_options(file.path().to_str().unwrap(), &options).unwrap();
+        let schema = source.infer_schema(100).await.unwrap();
+        assert_eq!(schema.columns[0].name, "timestamp");
+        assert_eq!(schema.columns[0].data_type, DataType::DateTime);
+
+        let rows = source.read_batch(100).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["module"], Value::String("billing".to_string()));
+        assert_eq!(source.rejected_lines(), 1);
+        assert!(!source.has_more());
+        assert_eq!(
+            std::fs::read_to_string(rejects.path()).unwrap(),
+            "    at Billing.charge(Billing.java:88)\n"
+        );
+    }
+
+    #[test]
+    fn test_log_options() {
+        let invalid =
+            |pairs: &[(&str, &str)]| LogSource::with_options("app.log", &options(pairs)).is_err();
+        assert!(invalid(&[]));
+        assert!(invalid(&[("preset", "apache2")]));
+        assert!(invalid(&[("pattern", r"^(\d+)$")]));
+        assert!(invalid(&[("pattern", r"^(?P<a>")]));
+        assert!(invalid(&[
+            ("pattern", r"^(?P<a>.*)$"),
+            ("preset", "logfmt")
+        ]));
+        assert!(!invalid(&[("preset", "Combined")]));
+    }
+}
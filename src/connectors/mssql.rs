@@ -12,8 +12,8 @@ use tokio_util::compat::TokioAsyncWriteCompatExt;
 use url::Url;
 
 use crate::{
-    connectors::{Source, Target},
-    schema::{Column, DataType, Row, Schema, Value},
+    connectors::{ddl::SqlDialect, Source, Target},
+    schema::{Column, DataType, Row, Schema, TableDdl, Value},
     Result, TinyEtlError,
 };
 
@@ -223,6 +223,11 @@ impl Source for MssqlSource {
         Ok(schema)
     }
 
+    async fn catalog_schema(&mut self) -> Result<Option<Schema>> {
+        // The schema is read from INFORMATION_SCHEMA.COLUMNS, not sampled rows
+        self.infer_schema(0).await.map(Some)
+    }
+
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
         if self.client.is_none() {
             return Err(TinyEtlError::Connection(
@@ -319,6 +324,7 @@ pub struct MssqlTarget {
     client: Option<MssqlClient>,
     max_batch_size: usize,
     schema: Option<Schema>,
+    ddl: TableDdl,
 }
 
 impl MssqlTarget {
@@ -331,6 +337,7 @@ impl MssqlTarget {
             client: None,
             max_batch_size: 1000,
             schema: None,
+            ddl: TableDdl::default(),
         })
     }
 
@@ -354,17 +361,22 @@ impl MssqlTarget {
         MssqlSource::create_client(connection_string).await
     }
 
-    fn sql_type_from_data_type(data_type: &DataType) -> &'static str {
-        match data_type {
-            DataType::Integer => "BIGINT",
-            DataType::Decimal => "DECIMAL(18,6)",
-            DataType::String => "NVARCHAR(MAX)",
-            DataType::Boolean => "BIT",
-            DataType::Date => "DATE",
-            DataType::DateTime => "DATETIME2",
-            DataType::Json => "NVARCHAR(MAX)", // MSSQL stores JSON as NVARCHAR
-            DataType::Null => "NVARCHAR(MAX)", // Default to string for null type
+    async fn create_indexes(&mut self, table_name: &str) -> Result<()> {
+        let client = self
+            .client
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Not connected to MSSQL".to_string()))?;
+
+        for (name, index_sql) in SqlDialect::Mssql.index_statements(&self.ddl, table_name) {
+            client.execute(&index_sql, &[]).await.map_err(|e| {
+                TinyEtlError::DataTransfer(format!("Failed to create index '{}': {}", name, e))
+            })?;
         }
+        Ok(())
+    }
+
+    fn sql_type_from_data_type(data_type: &DataType) -> &'static str {
+        SqlDialect::Mssql.column_type(data_type)
     }
 
     #[cfg_attr(not(test), allow(dead_code))]
@@ -538,18 +550,33 @@ impl Target for MssqlTarget {
         // Build CREATE TABLE statement with IF NOT EXISTS logic
         let mut columns_sql = Vec::new();
         for column in &schema.columns {
-            let sql_type = Self::sql_type_from_data_type(&column.data_type);
-            let nullable = if column.nullable { "NULL" } else { "NOT NULL" };
-            columns_sql.push(format!("[{}] {} {}", column.name, sql_type, nullable));
+            let sql_type = self
+                .ddl
+                .sql_type(column)
+                .unwrap_or_else(|| Self::sql_type_from_data_type(&column.data_type));
+            let nullable = SqlDialect::Mssql.null_clause(&self.ddl, column);
+            let clauses = SqlDialect::Mssql.column_clauses(&self.ddl, &column.name);
+            columns_sql.push(format!(
+                "[{}] {}{}{}",
+                column.name, sql_type, nullable, clauses
+            ));
         }
+        columns_sql.extend(SqlDialect::Mssql.table_constraints(&self.ddl));
+
+        // Comments can only be added once, so they go inside the IF NOT EXISTS block
+        let mut create_statements = vec![format!(
+            "CREATE TABLE [{}] ({})",
+            table_name,
+            columns_sql.join(", ")
+        )];
+        create_statements.extend(SqlDialect::Mssql.comment_statements(&self.ddl, table_name));
 
         // SQL Server doesn't have CREATE TABLE IF NOT EXISTS, so we use IF NOT EXISTS wrapper
         let create_table_sql = format!(
             "IF NOT EXISTS (SELECT * FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_NAME = '{}') \
-             BEGIN CREATE TABLE [{}] ({}) END",
+             BEGIN {}; END",
             table_name.replace("'", "''"), // Escape single quotes
-            table_name,
-            columns_sql.join(", ")
+            create_statements.join("; ")
         );
 
         client
@@ -557,6 +584,10 @@ impl Target for MssqlTarget {
             .await
             .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to create table: {}", e)))?;
 
+        if !self.ddl.indexes_after_load {
+            self.create_indexes(table_name).await?;
+        }
+
         Ok(())
     }
 
@@ -647,7 +678,11 @@ impl Target for MssqlTarget {
     }
 
     async fn finalize(&mut self) -> Result<()> {
-        // MSSQL doesn't require explicit finalization for basic operations
+        // Indexes are cheaper to build once than to maintain row by row
+        if self.ddl.indexes_after_load && self.client.is_some() {
+            let table_name = self.table_name.clone();
+            self.create_indexes(&table_name).await?;
+        }
         Ok(())
     }
 
@@ -697,6 +732,10 @@ impl Target for MssqlTarget {
     fn supports_append(&self) -> bool {
         true // MSSQL supports INSERT operations on existing tables
     }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
 }
 
 #[cfg(test)]
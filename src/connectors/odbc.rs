@@ -6,8 +6,8 @@ use odbc_api::{
 use std::sync::Arc;
 
 use crate::{
-    connectors::{Source, Target},
-    schema::{Column, DataType, Row, Schema, Value},
+    connectors::{ddl::SqlDialect, Source, Target},
+    schema::{Column, DataType, Row, Schema, TableDdl, Value},
     Result, TinyEtlError,
 };
 
@@ -219,6 +219,11 @@ impl Source for OdbcSource {
         })
     }
 
+    async fn catalog_schema(&mut self) -> Result<Option<Schema>> {
+        // The schema is read from the driver's column descriptions, not sampled rows
+        self.infer_schema(0).await.map(Some)
+    }
+
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
         if self.connection.is_none() {
             self.connect().await?;
@@ -378,6 +383,7 @@ pub struct OdbcTarget {
     schema: Option<Schema>,
     // Performance optimization: Track transaction state
     in_transaction: bool,
+    ddl: TableDdl,
 }
 
 impl OdbcTarget {
@@ -390,9 +396,33 @@ impl OdbcTarget {
             connection: None,
             schema: None,
             in_transaction: false,
+            ddl: TableDdl::default(),
         })
     }
 
+    fn execute_ddl(&self, statements: &[String]) -> Result<()> {
+        let conn = self.connection.as_ref().ok_or_else(|| {
+            TinyEtlError::Connection("ODBC connection not established".to_string())
+        })?;
+        let conn = conn.connection();
+
+        for statement in statements {
+            conn.execute(statement, ()).map_err(|e| {
+                TinyEtlError::DataTransfer(format!("Failed to execute '{}': {}", statement, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn index_statements(&self, table_name: &str) -> Vec<String> {
+        // SQL Server syntax, matching the bracket quoting used for CREATE TABLE
+        SqlDialect::Mssql
+            .index_statements(&self.ddl, table_name)
+            .into_iter()
+            .map(|(_, sql)| sql)
+            .collect()
+    }
+
     fn parse_connection_string(connection_string: &str) -> Result<(String, String)> {
         let connection_string = connection_string.trim_start_matches("odbc://");
 
@@ -466,11 +496,23 @@ impl Target for OdbcTarget {
                     create_sql.push_str(", ");
                 }
 
-                let sql_type = self.map_datatype_to_sql(&col.data_type);
+                let sql_type = self
+                    .ddl
+                    .sql_type(col)
+                    .unwrap_or_else(|| self.map_datatype_to_sql(&col.data_type));
                 let nullable = if col.nullable { "" } else { " NOT NULL" };
+                let clauses = SqlDialect::Mssql.column_clauses(&self.ddl, &col.name);
 
                 // Quote column names with square brackets for SQL Server
-                create_sql.push_str(&format!("[{}] {}{}", col.name, sql_type, nullable));
+                create_sql.push_str(&format!(
+                    "[{}] {}{}{}",
+                    col.name, sql_type, nullable, clauses
+                ));
+            }
+
+            for constraint in SqlDialect::Mssql.table_constraints(&self.ddl) {
+                create_sql.push_str(", ");
+                create_sql.push_str(&constraint);
             }
 
             create_sql.push(')');
@@ -479,6 +521,12 @@ impl Target for OdbcTarget {
             conn.execute(&create_sql, ()).map_err(|e| {
                 TinyEtlError::DataTransfer(format!("Failed to create table: {}", e))
             })?;
+
+            self.execute_ddl(&SqlDialect::Mssql.comment_statements(&self.ddl, table_name))?;
+        }
+
+        if !self.ddl.indexes_after_load {
+            self.execute_ddl(&self.index_statements(table_name))?;
         }
 
         // Always set the schema regardless of whether table was created
@@ -615,6 +663,16 @@ impl Target for OdbcTarget {
                 self.in_transaction = false;
             }
         }
+
+        // Indexes are cheaper to build once than to maintain row by row
+        if self.ddl.indexes_after_load && self.connection.is_some() {
+            self.execute_ddl(&self.index_statements(&self.table_name))?;
+            if let Some(conn) = &self.connection {
+                conn.connection().commit().map_err(|e| {
+                    TinyEtlError::DataTransfer(format!("Failed to commit indexes: {}", e))
+                })?;
+            }
+        }
         Ok(())
     }
 
@@ -661,4 +719,8 @@ impl Target for OdbcTarget {
     fn supports_append(&self) -> bool {
         true
     }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
 }
@@ -2,35 +2,219 @@ use arrow::array::*;
 use arrow::datatypes::*;
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use parquet::arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter};
+use parquet::arrow::{
+    arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder},
+    ArrowWriter, ProjectionMask,
+};
+use parquet::basic::{BrotliLevel, Compression as ParquetCompression, GzipLevel, ZstdLevel};
+use parquet::errors::ParquetError;
+use parquet::file::{
+    metadata::{KeyValue, RowGroupMetaData},
+    properties::{EnabledStatistics, WriterProperties},
+    statistics::Statistics,
+};
+use parquet::schema::types::ColumnPath;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::{
-    connectors::{Source, Target},
+    connectors::{filter::Filter, Source, Target},
     schema::{Row, Schema, Value},
     Result, TinyEtlError,
 };
 
 pub struct ParquetSource {
     file_path: PathBuf,
-    all_batches: Vec<RecordBatch>,
-    current_batch_index: usize,
+    /// Columns to read, from the `columns` option; every column when empty
+    columns: Vec<String>,
+    filter: Option<Filter>,
+    /// Behind a mutex only so the source is `Sync`; reached through `get_mut`
+    reader: Option<Mutex<ParquetRecordBatchReader>>,
+    /// Schema of the rows returned, in `columns` order
+    arrow_schema: Option<SchemaRef>,
+    /// Columns read only to apply the filter, removed from the rows returned
+    filter_only_columns: Vec<String>,
+    /// Rows of the last record batch not yet returned
+    pending: VecDeque<Row>,
+    has_more_data: bool,
     total_row_count: Option<usize>,
 }
 
 impl ParquetSource {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `columns` lists the columns to read, comma separated. `filter` skips
+    /// row groups whose statistics rule it out, then drops the rows it
+    /// doesn't match.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let columns = options
+            .get("columns")
+            .map(|columns| {
+                columns
+                    .split(',')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let filter = match options.get("filter").map(|f| f.trim()) {
+            None | Some("") => None,
+            Some(filter) => Some(Filter::parse(filter)?),
+        };
+
         Ok(Self {
             file_path: PathBuf::from(file_path),
-            all_batches: Vec::new(),
-            current_batch_index: 0,
+            columns,
+            filter,
+            reader: None,
+            arrow_schema: None,
+            filter_only_columns: Vec::new(),
+            pending: VecDeque::new(),
+            has_more_data: false,
             total_row_count: None,
         })
     }
 
+    /// Open the file and build a reader over the projected columns of the
+    /// row groups the filter can match. Only the footer is read here.
+    fn open_reader(&mut self) -> Result<()> {
+        let file = std::fs::File::open(&self.file_path).map_err(TinyEtlError::Io)?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| {
+            TinyEtlError::Connection(format!("Failed to create reader builder: {}", e))
+        })?;
+        let file_schema = builder.schema().clone();
+
+        let field_index = |name: &str| {
+            file_schema.index_of(name).map_err(|_| {
+                TinyEtlError::Configuration(format!(
+                    "Column '{}' not found in {}",
+                    name,
+                    self.file_path.display()
+                ))
+            })
+        };
+        let output: Vec<usize> = if self.columns.is_empty() {
+            (0..file_schema.fields().len()).collect()
+        } else {
+            self.columns
+                .iter()
+                .map(|c| field_index(c))
+                .collect::<Result<_>>()?
+        };
+        let mut read = output.clone();
+        self.filter_only_columns.clear();
+        if let Some(filter) = &self.filter {
+            for column in filter.columns() {
+                let index = field_index(column)?;
+                if !read.contains(&index) {
+                    read.push(index);
+                    self.filter_only_columns.push(column.to_string());
+                }
+            }
+        }
+
+        let row_groups: Vec<usize> = (0..builder.metadata().num_row_groups())
+            .filter(|&i| match &self.filter {
+                Some(filter) => {
+                    Self::row_group_may_match(builder.metadata().row_group(i), &file_schema, filter)
+                }
+                None => true,
+            })
+            .collect();
+        let total_rows: usize = row_groups
+            .iter()
+            .map(|&i| builder.metadata().row_group(i).num_rows() as usize)
+            .sum();
+
+        let mask = ProjectionMask::roots(builder.parquet_schema(), read);
+        let reader = builder
+            .with_projection(mask)
+            .with_row_groups(row_groups)
+            .build()
+            .map_err(|e| {
+                TinyEtlError::DataTransfer(format!("Failed to create batch reader: {}", e))
+            })?;
+
+        self.arrow_schema = Some(Arc::new(file_schema.project(&output).map_err(|e| {
+            TinyEtlError::SchemaInference(format!("Failed to project schema: {}", e))
+        })?));
+        self.total_row_count = Some(total_rows);
+        self.reader = Some(Mutex::new(reader));
+        self.pending.clear();
+        self.has_more_data = true;
+        Ok(())
+    }
+
+    /// False when the statistics show no row of the group can match
+    fn row_group_may_match(
+        row_group: &RowGroupMetaData,
+        schema: &arrow::datatypes::Schema,
+        filter: &Filter,
+    ) -> bool {
+        filter.conditions.iter().all(|condition| {
+            let range = row_group
+                .columns()
+                .iter()
+                .find(|c| c.column_path().string() == condition.column)
+                .and_then(|c| c.statistics())
+                .zip(schema.field_with_name(&condition.column).ok())
+                .and_then(|(stats, field)| Self::statistics_range(stats, field.data_type()));
+            match range {
+                Some((min, max)) => condition.may_match_range(&min, &max),
+                None => true,
+            }
+        })
+    }
+
+    /// Minimum and maximum of a column chunk as values, for the types whose
+    /// statistics order the same way as their values
+    fn statistics_range(stats: &Statistics, data_type: &DataType) -> Option<(Value, Value)> {
+        let timestamp = |v: i64, unit: &TimeUnit| {
+            match unit {
+                TimeUnit::Second => chrono::DateTime::from_timestamp(v, 0),
+                TimeUnit::Millisecond => chrono::DateTime::from_timestamp_millis(v),
+                TimeUnit::Microsecond => chrono::DateTime::from_timestamp_micros(v),
+                TimeUnit::Nanosecond => Some(chrono::DateTime::from_timestamp_nanos(v)),
+            }
+            .map(Value::Date)
+        };
+        let decimal = |v: f64| Decimal::try_from(v).ok().map(Value::Decimal);
+
+        match (stats, data_type) {
+            (Statistics::Boolean(s), DataType::Boolean) => {
+                Some((Value::Boolean(*s.min_opt()?), Value::Boolean(*s.max_opt()?)))
+            }
+            (Statistics::Int32(s), DataType::Int8 | DataType::Int16 | DataType::Int32) => Some((
+                Value::Integer(*s.min_opt()? as i64),
+                Value::Integer(*s.max_opt()? as i64),
+            )),
+            (Statistics::Int32(s), DataType::Date32) => {
+                let date = |days: i32| timestamp(days as i64 * 86_400, &TimeUnit::Second);
+                Some((date(*s.min_opt()?)?, date(*s.max_opt()?)?))
+            }
+            (Statistics::Int64(s), DataType::Int64) => {
+                Some((Value::Integer(*s.min_opt()?), Value::Integer(*s.max_opt()?)))
+            }
+            (Statistics::Int64(s), DataType::Timestamp(unit, _)) => Some((
+                timestamp(*s.min_opt()?, unit)?,
+                timestamp(*s.max_opt()?, unit)?,
+            )),
+            (Statistics::Double(s), DataType::Float64) => {
+                Some((decimal(*s.min_opt()?)?, decimal(*s.max_opt()?)?))
+            }
+            (Statistics::ByteArray(s), DataType::Utf8 | DataType::LargeUtf8) => Some((
+                Value::String(s.min_opt()?.as_utf8().ok()?.to_string()),
+                Value::String(s.max_opt()?.as_utf8().ok()?.to_string()),
+            )),
+            _ => None,
+        }
+    }
+
     fn arrow_type_to_schema_type(arrow_type: &DataType) -> crate::schema::DataType {
         crate::schema::DataType::from_arrow(arrow_type)
     }
@@ -168,6 +352,51 @@ impl ParquetSource {
                     values.push((column_name.to_string(), value));
                 }
             }
+            DataType::Decimal128(_, scale) => {
+                let decimal_array = array
+                    .as_any()
+                    .downcast_ref::<Decimal128Array>()
+                    .ok_or_else(|| {
+                        TinyEtlError::DataTransfer(
+                            "Failed to downcast to Decimal128Array".to_string(),
+                        )
+                    })?;
+                for i in 0..decimal_array.len() {
+                    let value = if decimal_array.is_null(i) {
+                        Value::Null
+                    } else {
+                        // Exact, unless the value needs more than the 96 bits
+                        // and 28 decimal places a Decimal holds
+                        u32::try_from(*scale)
+                            .ok()
+                            .and_then(|scale| {
+                                Decimal::try_from_i128_with_scale(decimal_array.value(i), scale)
+                                    .ok()
+                            })
+                            .map(Value::Decimal)
+                            .unwrap_or_else(|| Self::decimal_text(decimal_array.value_as_string(i)))
+                    };
+                    values.push((column_name.to_string(), value));
+                }
+            }
+            DataType::Decimal256(_, _) => {
+                let decimal_array = array
+                    .as_any()
+                    .downcast_ref::<Decimal256Array>()
+                    .ok_or_else(|| {
+                        TinyEtlError::DataTransfer(
+                            "Failed to downcast to Decimal256Array".to_string(),
+                        )
+                    })?;
+                for i in 0..decimal_array.len() {
+                    let value = if decimal_array.is_null(i) {
+                        Value::Null
+                    } else {
+                        Self::decimal_text(decimal_array.value_as_string(i))
+                    };
+                    values.push((column_name.to_string(), value));
+                }
+            }
             _ => {
                 // For unsupported types, convert to string representation
                 for i in 0..array.len() {
@@ -185,7 +414,15 @@ impl ParquetSource {
         Ok(values)
     }
 
-    fn record_batch_to_rows(batch: &RecordBatch) -> Result<Vec<Row>> {
+    /// A decimal written out in full, kept as text when it doesn't fit a Decimal
+    fn decimal_text(text: String) -> Value {
+        match Decimal::from_str_exact(&text) {
+            Ok(d) => Value::Decimal(d),
+            Err(_) => Value::String(text),
+        }
+    }
+
+    pub(crate) fn record_batch_to_rows(batch: &RecordBatch) -> Result<Vec<Row>> {
         let num_rows = batch.num_rows();
         let mut rows = vec![HashMap::new(); num_rows];
 
@@ -215,45 +452,14 @@ impl Source for ParquetSource {
             )));
         }
 
-        let file = std::fs::File::open(&self.file_path).map_err(TinyEtlError::Io)?;
-
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| {
-            TinyEtlError::Connection(format!("Failed to create reader builder: {}", e))
-        })?;
-
-        // Get total row count from metadata
-        let metadata = builder.metadata();
-        let total_rows: usize = metadata
-            .row_groups()
-            .iter()
-            .map(|rg| rg.num_rows() as usize)
-            .sum();
-        self.total_row_count = Some(total_rows);
-
-        // Read all batches upfront
-        let batch_reader = builder.build().map_err(|e| {
-            TinyEtlError::DataTransfer(format!("Failed to create batch reader: {}", e))
-        })?;
-
-        for batch_result in batch_reader {
-            let batch = batch_result
-                .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to read batch: {}", e)))?;
-            self.all_batches.push(batch);
-        }
-
-        Ok(())
+        self.open_reader()
     }
 
     async fn infer_schema(&mut self, _sample_size: usize) -> Result<Schema> {
-        if self.all_batches.is_empty() {
-            return Ok(Schema {
-                columns: Vec::new(),
-                estimated_rows: Some(0),
-                primary_key_candidate: None,
-            });
-        }
-
-        let arrow_schema = self.all_batches[0].schema();
+        let arrow_schema = self
+            .arrow_schema
+            .as_ref()
+            .ok_or_else(|| TinyEtlError::Connection("Parquet file not opened".to_string()))?;
 
         let columns = arrow_schema
             .fields()
@@ -276,62 +482,206 @@ impl Source for ParquetSource {
     }
 
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
-        if self.current_batch_index >= self.all_batches.len() {
-            return Ok(Vec::new()); // No more data
-        }
-
-        let batch = &self.all_batches[self.current_batch_index];
-        self.current_batch_index += 1;
-
-        let mut rows = Self::record_batch_to_rows(batch)?;
+        while self.pending.len() < batch_size && self.has_more_data {
+            let reader = self
+                .reader
+                .as_mut()
+                .ok_or_else(|| TinyEtlError::Connection("Parquet file not opened".to_string()))?
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner());
+            let batch = match reader.next() {
+                Some(batch) => batch.map_err(|e| {
+                    TinyEtlError::DataTransfer(format!("Failed to read batch: {}", e))
+                })?,
+                None => {
+                    self.has_more_data = false;
+                    break;
+                }
+            };
 
-        // If requested batch size is smaller than the record batch, we need to split it
-        if rows.len() > batch_size {
-            rows.truncate(batch_size);
-            // For simplicity, we'll just return the first batch_size rows
-            // In a more sophisticated implementation, you'd store the remaining rows
-            // and return them in subsequent calls
+            let mut rows = Self::record_batch_to_rows(&batch)?;
+            if let Some(filter) = &self.filter {
+                rows.retain(|row| filter.matches(row));
+            }
+            for row in &mut rows {
+                for column in &self.filter_only_columns {
+                    row.remove(column);
+                }
+            }
+            self.pending.extend(rows);
         }
 
-        Ok(rows)
+        let count = batch_size.min(self.pending.len());
+        Ok(self.pending.drain(..count).collect())
     }
 
     async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        // Exact from the footer, less any row groups the filter skips
         Ok(self.total_row_count)
     }
 
     async fn reset(&mut self) -> Result<()> {
-        self.current_batch_index = 0;
-        Ok(())
+        self.open_reader()
     }
 
     fn has_more(&self) -> bool {
-        self.current_batch_index < self.all_batches.len()
+        !self.pending.is_empty() || self.has_more_data
     }
 }
 
 pub struct ParquetTarget {
     file_path: PathBuf,
+    properties: WriterProperties,
+    /// Columns given bloom filters, checked against the schema in create_table
+    bloom_filter_columns: Vec<String>,
     schema: Option<Arc<arrow::datatypes::Schema>>,
-    buffered_rows: Vec<Row>,
-    is_finalized: bool,
+    /// Behind a mutex only so the target is `Sync`; reached through `get_mut`
+    writer: Option<Mutex<ArrowWriter<std::fs::File>>>,
 }
 
 impl ParquetTarget {
     pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// Writer properties from the target options. `metadata.<key>` options
+    /// are stored as key-value metadata in the file footer.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let mut builder = WriterProperties::builder();
+        let mut bloom_filter_columns = Vec::new();
+        let mut metadata = Vec::new();
+        let mut level = None;
+
+        for (key, value) in options {
+            let value = value.trim();
+            match key.as_str() {
+                "row_group_size" => {
+                    let rows = Self::parse_count(key, value)?;
+                    if rows == 0 {
+                        return Err(Self::invalid(key, value, "expected a positive number"));
+                    }
+                    builder = builder.set_max_row_group_size(rows);
+                }
+                "compression_level" => level = Some(value),
+                "dictionary" => {
+                    builder = builder.set_dictionary_enabled(Self::parse_bool(key, value)?)
+                }
+                "statistics" => {
+                    let statistics = match value.to_lowercase().as_str() {
+                        "none" | "false" => EnabledStatistics::None,
+                        "chunk" => EnabledStatistics::Chunk,
+                        "page" | "true" => EnabledStatistics::Page,
+                        _ => return Err(Self::invalid(key, value, "expected none, chunk or page")),
+                    };
+                    builder = builder.set_statistics_enabled(statistics);
+                }
+                "bloom_filter_columns" => {
+                    bloom_filter_columns = value
+                        .split(',')
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect();
+                }
+                "data_page_size" => {
+                    builder = builder.set_data_page_size_limit(Self::parse_count(key, value)?)
+                }
+                _ => {
+                    if let Some(name) = key.strip_prefix("metadata.") {
+                        metadata.push(KeyValue::new(name.to_string(), value.to_string()));
+                    }
+                }
+            }
+        }
+
+        let codec = options.get("compression").map(|c| c.trim().to_lowercase());
+        builder = builder.set_compression(Self::parse_compression(codec.as_deref(), level)?);
+        for column in &bloom_filter_columns {
+            builder =
+                builder.set_column_bloom_filter_enabled(ColumnPath::from(column.as_str()), true);
+        }
+        if !metadata.is_empty() {
+            metadata.sort_by(|a, b| a.key.cmp(&b.key));
+            builder = builder.set_key_value_metadata(Some(metadata));
+        }
+
         Ok(Self {
             file_path: PathBuf::from(file_path),
+            properties: builder.build(),
+            bloom_filter_columns,
             schema: None,
-            buffered_rows: Vec::new(),
-            is_finalized: false,
+            writer: None,
         })
     }
 
+    /// The `compression` codec, uncompressed by default, at `compression_level`
+    /// for the codecs that have levels
+    fn parse_compression(codec: Option<&str>, level: Option<&str>) -> Result<ParquetCompression> {
+        let level = level
+            .map(|level| {
+                level
+                    .parse::<u32>()
+                    .map_err(|_| Self::invalid("compression_level", level, "expected a number"))
+            })
+            .transpose()?;
+        let invalid_level = |e: ParquetError| {
+            TinyEtlError::Configuration(format!("Invalid Parquet compression_level: {}", e))
+        };
+
+        Ok(match (codec.unwrap_or("none"), level) {
+            ("none" | "uncompressed", None) => ParquetCompression::UNCOMPRESSED,
+            ("snappy", None) => ParquetCompression::SNAPPY,
+            ("lz4", None) => ParquetCompression::LZ4_RAW,
+            ("zstd", level) => ParquetCompression::ZSTD(match level {
+                Some(level) => ZstdLevel::try_new(level as i32).map_err(invalid_level)?,
+                None => ZstdLevel::default(),
+            }),
+            ("gzip", level) => ParquetCompression::GZIP(match level {
+                Some(level) => GzipLevel::try_new(level).map_err(invalid_level)?,
+                None => GzipLevel::default(),
+            }),
+            ("brotli", level) => ParquetCompression::BROTLI(match level {
+                Some(level) => BrotliLevel::try_new(level).map_err(invalid_level)?,
+                None => BrotliLevel::default(),
+            }),
+            ("none" | "uncompressed" | "snappy" | "lz4", Some(_)) => {
+                return Err(TinyEtlError::Configuration(
+                    "Parquet compression_level needs compression zstd, gzip or brotli".to_string(),
+                ))
+            }
+            (other, _) => {
+                return Err(Self::invalid(
+                    "compression",
+                    other,
+                    "expected snappy, zstd, gzip, brotli, lz4 or none",
+                ))
+            }
+        })
+    }
+
+    fn parse_bool(key: &str, value: &str) -> Result<bool> {
+        value
+            .parse()
+            .map_err(|_| Self::invalid(key, value, "expected true or false"))
+    }
+
+    fn parse_count(key: &str, value: &str) -> Result<usize> {
+        value
+            .parse()
+            .map_err(|_| Self::invalid(key, value, "expected a number"))
+    }
+
+    fn invalid(key: &str, value: &str, expected: &str) -> TinyEtlError {
+        TinyEtlError::Configuration(format!(
+            "Invalid value '{}' for Parquet option '{}': {}",
+            value, key, expected
+        ))
+    }
+
     fn schema_to_arrow_schema(schema: &Schema) -> Arc<arrow::datatypes::Schema> {
         Arc::new(schema.to_arrow_schema())
     }
 
-    fn rows_to_record_batch(
+    pub(crate) fn rows_to_record_batch(
         rows: &[Row],
         schema: &Arc<arrow::datatypes::Schema>,
     ) -> Result<RecordBatch> {
@@ -437,19 +787,37 @@ impl ParquetTarget {
 #[async_trait]
 impl Target for ParquetTarget {
     async fn connect(&mut self) -> Result<()> {
-        // Nothing to do here - we'll create the file when we finalize
+        // Nothing to do here - the file is created with the table
         Ok(())
     }
 
     async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
         let arrow_schema = Self::schema_to_arrow_schema(schema);
-        self.schema = Some(arrow_schema);
+        if let Some(column) = self
+            .bloom_filter_columns
+            .iter()
+            .find(|c| arrow_schema.field_with_name(c).is_err())
+        {
+            return Err(TinyEtlError::Configuration(format!(
+                "Bloom filter column '{}' is not in the schema",
+                column
+            )));
+        }
 
         // Create parent directory if it doesn't exist
         if let Some(parent) = self.file_path.parent() {
             std::fs::create_dir_all(parent).map_err(TinyEtlError::Io)?;
         }
 
+        let file = std::fs::File::create(&self.file_path).map_err(TinyEtlError::Io)?;
+        let writer =
+            ArrowWriter::try_new(file, arrow_schema.clone(), Some(self.properties.clone()))
+                .map_err(|e| {
+                    TinyEtlError::Connection(format!("Failed to create parquet writer: {}", e))
+                })?;
+        self.writer = Some(Mutex::new(writer));
+        self.schema = Some(arrow_schema);
+
         Ok(())
     }
 
@@ -458,40 +826,36 @@ impl Target for ParquetTarget {
             return Ok(0);
         }
 
-        // Buffer the rows - we'll write them all at once during finalize
-        self.buffered_rows.extend_from_slice(rows);
-        Ok(rows.len())
-    }
-
-    async fn finalize(&mut self) -> Result<()> {
-        if self.is_finalized || self.buffered_rows.is_empty() {
-            return Ok(());
-        }
-
         let schema = self
             .schema
             .as_ref()
             .ok_or_else(|| TinyEtlError::Configuration("Schema not set".to_string()))?;
-
-        // Create the file and writer
-        let file = std::fs::File::create(&self.file_path).map_err(TinyEtlError::Io)?;
-
-        let mut writer = ArrowWriter::try_new(file, schema.clone(), None).map_err(|e| {
-            TinyEtlError::Connection(format!("Failed to create parquet writer: {}", e))
-        })?;
-
-        // Convert all buffered rows to record batch and write
-        let batch = Self::rows_to_record_batch(&self.buffered_rows, schema)?;
-
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| TinyEtlError::Connection("Writer not connected".to_string()))?
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner());
+
+        // The writer flushes a row group each time row_group_size rows are buffered
+        let batch = Self::rows_to_record_batch(rows, schema)?;
         writer
             .write(&batch)
             .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to write batch: {}", e)))?;
 
-        writer.close().map_err(|e| {
-            TinyEtlError::Connection(format!("Failed to close parquet writer: {}", e))
-        })?;
+        Ok(rows.len())
+    }
 
-        self.is_finalized = true;
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer
+                .into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .close()
+                .map_err(|e| {
+                    TinyEtlError::Connection(format!("Failed to close parquet writer: {}", e))
+                })?;
+        }
         Ok(())
     }
 
@@ -500,8 +864,8 @@ impl Target for ParquetTarget {
     }
 
     async fn truncate(&mut self, _table_name: &str) -> Result<()> {
-        // For Parquet files, truncation means clearing buffered rows
-        self.buffered_rows.clear();
+        // The file is recreated in create_table
+        self.writer = None;
         Ok(())
     }
 
@@ -516,6 +880,7 @@ impl Target for ParquetTarget {
 mod tests {
     use super::*;
     use crate::schema::{Column, DataType};
+    use arrow::datatypes::DataType as ArrowDataType;
     use tempfile::tempdir;
 
     #[tokio::test]
@@ -580,4 +945,158 @@ mod tests {
         let read_rows = source.read_batch(100).await.unwrap();
         assert_eq!(read_rows.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_parquet_source_projection_and_filter() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("events.parquet");
+        let path = file_path.to_str().unwrap();
+
+        // Three row groups of 100 rows, ids 0..300 in order
+        let arrow_schema = Arc::new(arrow::datatypes::Schema::new(vec![
+            Field::new("id", ArrowDataType::Int64, false),
+            Field::new("kind", ArrowDataType::Utf8, false),
+            Field::new("score", ArrowDataType::Float64, true),
+        ]));
+        let props = parquet::file::properties::WriterProperties::builder()
+            .set_max_row_group_size(100)
+            .build();
+        let file = std::fs::File::create(&file_path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, arrow_schema.clone(), Some(props)).unwrap();
+        let ids: Vec<i64> = (0..300).collect();
+        let batch = RecordBatch::try_new(
+            arrow_schema,
+            vec![
+                Arc::new(Int64Array::from(ids.clone())),
+                Arc::new(StringArray::from_iter_values(ids.iter().map(|i| {
+                    if i % 2 == 0 {
+                        "even"
+                    } else {
+                        "odd"
+                    }
+                }))),
+                Arc::new(Float64Array::from_iter_values(
+                    ids.iter().map(|&i| i as f64),
+                )),
+            ],
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let options = HashMap::from([
+            ("columns".to_string(), "kind, score".to_string()),
+            (
+                "filter".to_string(),
+                "id >= 150 and kind = 'even'".to_string(),
+            ),
+        ]);
+        let mut source = ParquetSource::with_options(path, &options).unwrap();
+        source.connect().await.unwrap();
+
+        // The first row group is skipped from its statistics
+        assert_eq!(source.estimated_row_count().await.unwrap(), Some(200));
+        let schema = source.infer_schema(100).await.unwrap();
+        let names: Vec<&str> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["kind", "score"]);
+
+        let mut rows = Vec::new();
+        while source.has_more() {
+            let batch = source.read_batch(7).await.unwrap();
+            assert!(batch.len() <= 7);
+            rows.extend(batch);
+        }
+        assert_eq!(rows.len(), 75);
+        assert!(rows
+            .iter()
+            .all(|row| row.len() == 2 && !row.contains_key("id")));
+        assert_eq!(
+            rows[0].get("score"),
+            Some(&Value::Decimal(Decimal::from(150)))
+        );
+
+        // Reset starts again from the first matching row
+        source.reset().await.unwrap();
+        assert_eq!(source.read_batch(1).await.unwrap(), rows[..1]);
+
+        let missing = HashMap::from([("columns".to_string(), "id,nope".to_string())]);
+        let mut source = ParquetSource::with_options(path, &missing).unwrap();
+        let err = source.connect().await.unwrap_err();
+        assert!(err.to_string().contains("'nope' not found"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_target_writer_options() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("tuned.parquet");
+        let path = file_path.to_str().unwrap();
+        let schema = Schema {
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let options: HashMap<String, String> = [
+            ("row_group_size", "10"),
+            ("compression", "zstd"),
+            ("compression_level", "3"),
+            ("dictionary", "false"),
+            ("statistics", "chunk"),
+            ("bloom_filter_columns", "id"),
+            ("metadata.owner", "data-team"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let mut target = ParquetTarget::with_options(path, &options).unwrap();
+        target.connect().await.unwrap();
+        target.create_table("ids", &schema).await.unwrap();
+        for chunk in (0..25).collect::<Vec<i64>>().chunks(8) {
+            let rows: Vec<Row> = chunk
+                .iter()
+                .map(|&id| HashMap::from([("id".to_string(), Value::Integer(id))]))
+                .collect();
+            target.write_batch(&rows).await.unwrap();
+        }
+        target.finalize().await.unwrap();
+
+        let file = std::fs::File::open(&file_path).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let metadata = builder.metadata();
+        let row_counts: Vec<i64> = metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect();
+        assert_eq!(row_counts, [10, 10, 5]);
+        let column = metadata.row_group(0).column(0);
+        // The level isn't recorded in the file, only the codec
+        assert!(matches!(column.compression(), ParquetCompression::ZSTD(_)));
+        assert!(column.bloom_filter_offset().is_some());
+        let owner = metadata
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap()
+            .iter()
+            .find(|kv| kv.key == "owner")
+            .and_then(|kv| kv.value.clone());
+        assert_eq!(owner.as_deref(), Some("data-team"));
+
+        let invalid = |key: &str, value: &str| {
+            let options = HashMap::from([(key.to_string(), value.to_string())]);
+            ParquetTarget::with_options(path, &options).is_err()
+        };
+        assert!(invalid("compression", "lzo"));
+        assert!(invalid("compression_level", "5"));
+        assert!(invalid("row_group_size", "0"));
+        assert!(invalid("statistics", "all"));
+
+        let options = HashMap::from([("bloom_filter_columns".to_string(), "nope".to_string())]);
+        let mut target = ParquetTarget::with_options(path, &options).unwrap();
+        assert!(target.create_table("ids", &schema).await.is_err());
+    }
 }
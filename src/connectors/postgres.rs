@@ -5,8 +5,8 @@ use sqlx::{postgres::PgConnectOptions, Column, PgPool, Row as SqlxRow};
 use std::str::FromStr;
 
 use crate::{
-    connectors::{Source, Target},
-    schema::{DataType, Row, Schema, SchemaInferer, Value},
+    connectors::{ddl::SqlDialect, Source, Target},
+    schema::{Column as SchemaColumn, DataType, Row, Schema, SchemaInferer, TableDdl, Value},
     Result, TinyEtlError,
 };
 
@@ -129,6 +129,63 @@ impl Source for PostgresSource {
         Ok(schema)
     }
 
+    async fn catalog_schema(&mut self) -> Result<Option<Schema>> {
+        // Custom queries have no catalog entry to describe
+        if self.query.is_some() {
+            return Ok(None);
+        }
+
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| TinyEtlError::Connection("Not connected".to_string()))?;
+
+        let (table_schema, table) = match self.table_name.split_once('.') {
+            Some((schema, table)) => (Some(schema), table),
+            None => (None, self.table_name.as_str()),
+        };
+
+        let rows = sqlx::query(
+            "SELECT column_name::text, data_type::text, is_nullable::text \
+             FROM information_schema.columns \
+             WHERE table_schema = COALESCE($1::text, current_schema()) AND table_name = $2 \
+             ORDER BY ordinal_position",
+        )
+        .bind(table_schema)
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            TinyEtlError::SchemaInference(format!("Failed to read table columns: {}", e))
+        })?;
+
+        if rows.is_empty() {
+            return Err(TinyEtlError::SchemaInference(format!(
+                "Table '{}' not found",
+                self.table_name
+            )));
+        }
+
+        let columns = rows
+            .iter()
+            .map(|row| {
+                let data_type: String = row.get(1);
+                let is_nullable: String = row.get(2);
+                SchemaColumn {
+                    name: row.get(0),
+                    data_type: Self::map_catalog_type(&data_type),
+                    nullable: is_nullable == "YES",
+                }
+            })
+            .collect();
+
+        Ok(Some(Schema {
+            columns,
+            estimated_rows: self.estimated_row_count().await.ok().flatten(),
+            primary_key_candidate: None,
+        }))
+    }
+
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
         let pool = self
             .pool
@@ -222,6 +279,19 @@ impl Source for PostgresSource {
 }
 
 impl PostgresSource {
+    /// Map an `information_schema.columns.data_type` name to our DataType
+    fn map_catalog_type(data_type: &str) -> DataType {
+        match data_type.to_lowercase().as_str() {
+            "smallint" | "integer" | "bigint" => DataType::Integer,
+            "numeric" | "real" | "double precision" | "money" => DataType::Decimal,
+            "boolean" => DataType::Boolean,
+            "date" => DataType::Date,
+            t if t.starts_with("timestamp") => DataType::DateTime,
+            "json" | "jsonb" => DataType::Json,
+            _ => DataType::String,
+        }
+    }
+
     fn extract_value(
         &self,
         row: &sqlx::postgres::PgRow,
@@ -306,6 +376,7 @@ pub struct PostgresTarget {
     pool: Option<PgPool>,
     table_name: Option<String>,
     schema: Option<Schema>,
+    ddl: TableDdl,
 }
 
 impl PostgresTarget {
@@ -329,8 +400,27 @@ impl PostgresTarget {
             pool: None,
             table_name,
             schema: None,
+            ddl: TableDdl::default(),
         })
     }
+
+    async fn execute_ddl(pool: &PgPool, statements: &[String]) -> Result<()> {
+        for statement in statements {
+            sqlx::query(statement).execute(pool).await.map_err(|e| {
+                TinyEtlError::DataTransfer(format!("Failed to execute '{}': {}", statement, e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn index_statements(&self) -> Vec<String> {
+        let table_name = self.table_name.as_deref().unwrap_or("data");
+        SqlDialect::Postgres
+            .index_statements(&self.ddl, table_name)
+            .into_iter()
+            .map(|(_, sql)| sql)
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -373,19 +463,16 @@ impl Target for PostgresTarget {
             .columns
             .iter()
             .map(|col| {
-                let pg_type = match col.data_type {
-                    DataType::String => "TEXT",
-                    DataType::Integer => "BIGINT",
-                    DataType::Decimal => "DECIMAL(18,6)",
-                    DataType::Boolean => "BOOLEAN",
-                    DataType::Date | DataType::DateTime => "TIMESTAMP WITH TIME ZONE",
-                    DataType::Json => "JSONB", // PostgreSQL native JSON type
-                    DataType::Null => "TEXT",  // Default to TEXT for null columns
-                };
+                let pg_type = self
+                    .ddl
+                    .sql_type(col)
+                    .unwrap_or_else(|| SqlDialect::Postgres.column_type(&col.data_type));
 
                 let nullable = if col.nullable { "" } else { " NOT NULL" };
-                format!("\"{}\" {}{}", col.name, pg_type, nullable)
+                let clauses = SqlDialect::Postgres.column_clauses(&self.ddl, &col.name);
+                format!("\"{}\" {}{}{}", col.name, pg_type, nullable, clauses)
             })
+            .chain(SqlDialect::Postgres.table_constraints(&self.ddl))
             .collect();
 
         create_sql.push_str(&column_defs.join(", "));
@@ -396,6 +483,12 @@ impl Target for PostgresTarget {
             .await
             .map_err(|e| TinyEtlError::DataTransfer(format!("Failed to create table: {}", e)))?;
 
+        let mut statements = SqlDialect::Postgres.comment_statements(&self.ddl, &actual_table_name);
+        if !self.ddl.indexes_after_load {
+            statements.extend(self.index_statements());
+        }
+        Self::execute_ddl(pool, &statements).await?;
+
         Ok(())
     }
 
@@ -496,7 +589,12 @@ impl Target for PostgresTarget {
     }
 
     async fn finalize(&mut self) -> Result<()> {
-        // PostgreSQL doesn't require explicit finalization for basic operations
+        // Indexes are cheaper to build once than to maintain row by row
+        if self.ddl.indexes_after_load {
+            if let Some(pool) = &self.pool {
+                Self::execute_ddl(pool, &self.index_statements()).await?;
+            }
+        }
         Ok(())
     }
 
@@ -540,6 +638,10 @@ impl Target for PostgresTarget {
         // PostgreSQL databases support appending new rows
         true
     }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
 }
 
 #[cfg(test)]
@@ -770,6 +872,27 @@ mod tests {
         assert_eq!(schema.columns[5].data_type, DataType::Date);
     }
 
+    #[test]
+    fn test_postgres_catalog_type_mapping() {
+        assert_eq!(
+            PostgresSource::map_catalog_type("bigint"),
+            DataType::Integer
+        );
+        assert_eq!(
+            PostgresSource::map_catalog_type("double precision"),
+            DataType::Decimal
+        );
+        assert_eq!(
+            PostgresSource::map_catalog_type("timestamp with time zone"),
+            DataType::DateTime
+        );
+        assert_eq!(PostgresSource::map_catalog_type("jsonb"), DataType::Json);
+        assert_eq!(
+            PostgresSource::map_catalog_type("character varying"),
+            DataType::String
+        );
+    }
+
     #[test]
     fn test_postgres_source_has_more_with_total() {
         let mut source =
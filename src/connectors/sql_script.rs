@@ -0,0 +1,543 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    connectors::{
+        compression::{self, CompressedWriter, Compression},
+        ddl::SqlDialect,
+        Target,
+    },
+    schema::{Column, Row, Schema, TableDdl, Value},
+    Result, TinyEtlError,
+};
+
+/// Rows per INSERT statement unless `rows_per_insert` is set
+const DEFAULT_ROWS_PER_INSERT: usize = 500;
+
+/// SQL Server accepts at most 1000 rows in a VALUES list
+const MSSQL_MAX_ROWS_PER_INSERT: usize = 1000;
+
+/// SQL script target: `CREATE TABLE` DDL followed by multi-row `INSERT`
+/// statements for the chosen dialect, to load with psql, mysql, sqlcmd or sqlite3
+pub struct SqlScriptTarget {
+    file_path: PathBuf,
+    compression: Compression,
+    dialect: SqlDialect,
+    table: String,
+    rows_per_insert: usize,
+    ddl: TableDdl,
+    writer: Option<BufWriter<CompressedWriter>>,
+    /// Writer given to `with_output`, used in place of the file
+    output: Option<Box<dyn Write + Send + Sync>>,
+    columns: Vec<Column>,
+    /// Whether this run starts the script. Only then are indexes created,
+    /// since MySQL has no CREATE INDEX IF NOT EXISTS to repeat on append.
+    new_script: bool,
+}
+
+impl SqlScriptTarget {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let dialect = match options.get("dialect").map(|d| d.to_lowercase()) {
+            None => SqlDialect::Postgres,
+            Some(dialect) => match dialect.as_str() {
+                "postgres" | "postgresql" => SqlDialect::Postgres,
+                "mysql" => SqlDialect::Mysql,
+                "mssql" | "sqlserver" => SqlDialect::Mssql,
+                "sqlite" => SqlDialect::Sqlite,
+                _ => {
+                    return Err(Self::invalid(
+                        "dialect",
+                        &dialect,
+                        "expected postgres, mysql, mssql or sqlite",
+                    ))
+                }
+            },
+        };
+
+        let rows_per_insert = match options.get("rows_per_insert") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(rows) if rows > 0 => rows,
+                _ => return Err(Self::invalid("rows_per_insert", value, "expected a number")),
+            },
+            None => DEFAULT_ROWS_PER_INSERT,
+        };
+        if dialect == SqlDialect::Mssql && rows_per_insert > MSSQL_MAX_ROWS_PER_INSERT {
+            return Err(Self::invalid(
+                "rows_per_insert",
+                &rows_per_insert.to_string(),
+                "SQL Server allows at most 1000 rows per INSERT",
+            ));
+        }
+
+        // out.sql.gz loads into table "out", like out.sql
+        let table = match options.get("table") {
+            Some(table) => table.clone(),
+            None => {
+                let format_path =
+                    compression::strip_compression_suffix(file_path).unwrap_or(file_path);
+                Path::new(format_path)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("data")
+                    .to_string()
+            }
+        };
+
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            compression: Compression::from_options(options, file_path)?,
+            dialect,
+            table,
+            rows_per_insert,
+            ddl: TableDdl::default(),
+            writer: None,
+            output: None,
+            columns: Vec::new(),
+            new_script: true,
+        })
+    }
+
+    /// Write to `output`, such as standard output, instead of creating the
+    /// file. The file name still tells the compression.
+    pub fn with_output<W: Write + Send + Sync + 'static>(mut self, output: W) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
+    fn invalid(key: &str, value: &str, expected: &str) -> TinyEtlError {
+        TinyEtlError::Configuration(format!(
+            "Invalid value '{}' for SQL script option '{}': {}",
+            value, key, expected
+        ))
+    }
+
+    /// Open the script for appending, or recreate it. Compressed scripts are
+    /// appended as a new stream, which decompressors read as one.
+    fn open_writer(&mut self, append: bool) -> Result<BufWriter<CompressedWriter>> {
+        if let Some(output) = self.output.take() {
+            return Ok(BufWriter::new(self.compression.compress(output)?));
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&self.file_path)?;
+        Ok(BufWriter::new(self.compression.compress(file)?))
+    }
+
+    /// CREATE TABLE followed by the comment statements, and the indexes
+    /// unless they are created after the data is loaded
+    fn create_table_statements(&self, schema: &Schema) -> Vec<String> {
+        let dialect = self.dialect;
+        let mut definitions: Vec<String> = schema
+            .columns
+            .iter()
+            .map(|col| {
+                let sql_type = self
+                    .ddl
+                    .sql_type(col)
+                    .unwrap_or_else(|| dialect.column_type(&col.data_type));
+                let nullable = dialect.null_clause(&self.ddl, col);
+                let clauses = dialect.column_clauses(&self.ddl, &col.name);
+                format!(
+                    "{} {}{}{}",
+                    dialect.quote(&col.name),
+                    sql_type,
+                    nullable,
+                    clauses
+                )
+            })
+            .collect();
+        definitions.extend(dialect.table_constraints(&self.ddl));
+
+        let create = format!(
+            "CREATE TABLE {} (\n    {}\n){}",
+            dialect.quote(&self.table),
+            definitions.join(",\n    "),
+            dialect.table_options(&self.ddl)
+        );
+        let comments = dialect.comment_statements(&self.ddl, &self.table);
+
+        let mut statements = if dialect == SqlDialect::Mssql {
+            // SQL Server has no CREATE TABLE IF NOT EXISTS, and comments can only be added once
+            let block: Vec<String> = std::iter::once(create).chain(comments).collect();
+            vec![format!(
+                "IF NOT EXISTS (SELECT * FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_NAME = {})\n\
+                 BEGIN\n{};\nEND",
+                dialect.string_literal(&self.table),
+                block.join(";\n")
+            )]
+        } else {
+            let create = create.replacen("CREATE TABLE", "CREATE TABLE IF NOT EXISTS", 1);
+            std::iter::once(create).chain(comments).collect()
+        };
+
+        if !self.ddl.indexes_after_load && self.new_script {
+            statements.extend(self.index_statements());
+        }
+        statements
+    }
+
+    fn index_statements(&self) -> Vec<String> {
+        self.dialect
+            .index_statements(&self.ddl, &self.table)
+            .into_iter()
+            .map(|(_, sql)| sql)
+            .collect()
+    }
+
+    fn write_statements<W: Write>(writer: &mut W, statements: &[String]) -> Result<()> {
+        for statement in statements {
+            writeln!(writer, "{};", statement)?;
+        }
+        Ok(())
+    }
+
+    fn write_insert<W: Write>(&self, writer: &mut W, rows: &[Row]) -> Result<()> {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| self.dialect.quote(&c.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        writeln!(
+            writer,
+            "INSERT INTO {} ({}) VALUES",
+            self.dialect.quote(&self.table),
+            columns
+        )?;
+
+        for (i, row) in rows.iter().enumerate() {
+            let values = self
+                .columns
+                .iter()
+                .map(|c| {
+                    let value = row.get(&c.name).unwrap_or(&Value::Null);
+                    self.dialect.value_literal(value, &c.data_type)
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let end = if i + 1 == rows.len() { ";" } else { "," };
+            writeln!(writer, "({}){}", values, end)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Target for SqlScriptTarget {
+    async fn connect(&mut self) -> Result<()> {
+        // Create parent directory if it doesn't exist
+        if let Some(parent) = self.file_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(())
+    }
+
+    async fn create_table(&mut self, _table_name: &str, schema: &Schema) -> Result<()> {
+        self.columns = schema.columns.clone();
+        let mut writer = match self.writer.take() {
+            Some(writer) => writer,
+            None => {
+                self.new_script = self.output.is_some()
+                    || std::fs::metadata(&self.file_path).map_or(true, |m| m.len() == 0);
+                self.open_writer(true)?
+            }
+        };
+        let statements = self.create_table_statements(schema);
+
+        Self::write_statements(&mut writer, &statements)?;
+        writeln!(writer)?;
+        self.writer = Some(writer);
+        Ok(())
+    }
+
+    async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
+        let mut writer = self
+            .writer
+            .take()
+            .ok_or_else(|| TinyEtlError::Connection("Writer not connected".to_string()))?;
+
+        let result = rows
+            .chunks(self.rows_per_insert)
+            .try_for_each(|chunk| self.write_insert(&mut writer, chunk));
+        self.writer = Some(writer);
+        result?;
+
+        Ok(rows.len())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            if self.ddl.indexes_after_load && self.new_script {
+                writeln!(writer)?;
+                Self::write_statements(&mut writer, &self.index_statements())?;
+            }
+            CompressedWriter::finish_buffered(writer)?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, _table_name: &str) -> Result<bool> {
+        Ok(self.file_path.exists())
+    }
+
+    async fn truncate(&mut self, _table_name: &str) -> Result<()> {
+        self.writer = Some(self.open_writer(false)?);
+        self.new_script = true;
+        Ok(())
+    }
+
+    fn supports_append(&self) -> bool {
+        // The script creates the table only if it doesn't exist, so more
+        // statements can be added to the end
+        true
+    }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::options;
+    use crate::schema::{DataType, IndexDefinition};
+    use chrono::{TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+    use tempfile::TempDir;
+
+    fn schema() -> Schema {
+        Schema {
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                Column {
+                    name: "name".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                },
+                Column {
+                    name: "active".to_string(),
+                    data_type: DataType::Boolean,
+                    nullable: true,
+                },
+                Column {
+                    name: "joined".to_string(),
+                    data_type: DataType::Date,
+                    nullable: true,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        }
+    }
+
+    fn row(id: i64, name: &str) -> Row {
+        HashMap::from([
+            ("id".to_string(), Value::Integer(id)),
+            ("name".to_string(), Value::String(name.to_string())),
+            ("active".to_string(), Value::Boolean(id % 2 == 1)),
+            (
+                "joined".to_string(),
+                Value::Date(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()),
+            ),
+        ])
+    }
+
+    async fn write_script(pairs: &[(&str, &str)], ddl: TableDdl, rows: &[Row]) -> String {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("customers.sql");
+        write_to(&path, pairs, ddl, rows).await
+    }
+
+    async fn write_to(path: &Path, pairs: &[(&str, &str)], ddl: TableDdl, rows: &[Row]) -> String {
+        let mut target =
+            SqlScriptTarget::with_options(path.to_str().unwrap(), &options(pairs)).unwrap();
+        target.set_table_ddl(ddl);
+        target.connect().await.unwrap();
+        target.create_table("customers", &schema()).await.unwrap();
+        assert_eq!(target.write_batch(rows).await.unwrap(), rows.len());
+        target.finalize().await.unwrap();
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_postgres_script() {
+        let ddl = TableDdl {
+            primary_key: vec!["id".to_string()],
+            ..Default::default()
+        };
+        let rows = [row(1, "O'Brien"), row(2, "Zoë")];
+        let script = write_script(&[], ddl, &rows).await;
+        assert_eq!(
+            script,
+            "CREATE TABLE IF NOT EXISTS \"customers\" (\n    \
+             \"id\" BIGINT NOT NULL,\n    \
+             \"name\" TEXT,\n    \
+             \"active\" BOOLEAN,\n    \
+             \"joined\" TIMESTAMP WITH TIME ZONE,\n    \
+             PRIMARY KEY (\"id\")\n);\n\n\
+             INSERT INTO \"customers\" (\"id\", \"name\", \"active\", \"joined\") VALUES\n\
+             (1, 'O''Brien', TRUE, '2024-03-01T00:00:00+00:00'),\n\
+             (2, 'Zoë', FALSE, '2024-03-01T00:00:00+00:00');\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rows_per_insert_and_dialects() {
+        let rows: Vec<Row> = (1..=5).map(|id| row(id, "a\\b")).collect();
+
+        let script = write_script(
+            &[("dialect", "mysql"), ("rows_per_insert", "2")],
+            TableDdl::default(),
+            &rows,
+        )
+        .await;
+        assert!(script
+            .starts_with("CREATE TABLE IF NOT EXISTS `customers` (\n    `id` BIGINT NOT NULL,"));
+        assert_eq!(script.matches("INSERT INTO `customers`").count(), 3);
+        assert!(script.contains("(1, 'a\\\\b', TRUE, '2024-03-01'),\n"));
+
+        let script = write_script(&[("dialect", "mssql")], TableDdl::default(), &rows).await;
+        assert!(script.starts_with(
+            "IF NOT EXISTS (SELECT * FROM INFORMATION_SCHEMA.TABLES \
+             WHERE TABLE_NAME = N'customers')\n\
+             BEGIN\nCREATE TABLE [customers] (\n    \
+             [id] BIGINT NOT NULL,\n    [name] NVARCHAR(MAX) NULL,"
+        ));
+        assert!(script.contains("(1, N'a\\b', 1, N'2024-03-01'),\n"));
+
+        let script = write_script(&[("dialect", "sqlite")], TableDdl::default(), &rows).await;
+        assert!(script.contains("\"active\" INTEGER,"));
+        assert!(script.contains("(5, 'a\\b', 1, '2024-03-01T00:00:00+00:00');\n"));
+    }
+
+    #[test]
+    fn test_primary_key_not_null() {
+        let mut schema = schema();
+        schema.columns[0].nullable = true;
+        let mut target = SqlScriptTarget::with_options(
+            "customers.sql",
+            &HashMap::from([("dialect".to_string(), "mssql".to_string())]),
+        )
+        .unwrap();
+        target.set_table_ddl(TableDdl {
+            primary_key: vec!["id".to_string()],
+            ..Default::default()
+        });
+        let create = &target.create_table_statements(&schema)[0];
+        assert!(create.contains("[id] BIGINT NOT NULL,"));
+        assert!(create.contains("[name] NVARCHAR(MAX) NULL,"));
+    }
+
+    #[tokio::test]
+    async fn test_indexes_after_load() {
+        let ddl = TableDdl {
+            indexes: vec![IndexDefinition {
+                name: None,
+                columns: vec!["name".to_string()],
+                unique: false,
+            }],
+            indexes_after_load: true,
+            ..Default::default()
+        };
+        let script = write_script(&[("table", "people")], ddl, &[row(1, "a")]).await;
+        assert!(script.starts_with("CREATE TABLE IF NOT EXISTS \"people\""));
+        assert!(script.ends_with(
+            "\nCREATE INDEX IF NOT EXISTS \"idx_people_name\" ON \"people\" (\"name\");\n"
+        ));
+        assert!(script.find("INSERT INTO").unwrap() < script.find("CREATE INDEX").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_mysql_append_creates_indexes_once() {
+        let ddl = TableDdl {
+            indexes: vec![IndexDefinition {
+                name: None,
+                columns: vec!["name".to_string()],
+                unique: false,
+            }],
+            ..Default::default()
+        };
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("customers.sql");
+        let options = [("dialect", "mysql")];
+        let first = write_to(&path, &options, ddl.clone(), &[row(1, "a")]).await;
+        assert_eq!(
+            first.matches("CREATE INDEX `idx_customers_name`").count(),
+            1
+        );
+
+        let script = write_to(&path, &options, ddl, &[row(2, "b")]).await;
+        assert!(script.starts_with(&first));
+        assert_eq!(script.matches("CREATE TABLE IF NOT EXISTS").count(), 2);
+        assert_eq!(script.matches("CREATE INDEX").count(), 1);
+        assert_eq!(script.matches("INSERT INTO").count(), 2);
+    }
+
+    #[test]
+    fn test_value_literals() {
+        let json = Value::Json(serde_json::json!({"note": "it's"}));
+        assert_eq!(
+            SqlDialect::Postgres.value_literal(&json, &DataType::Json),
+            "'{\"note\":\"it''s\"}'"
+        );
+        let decimal = Value::Decimal(Decimal::from_str("12.50").unwrap());
+        assert_eq!(
+            SqlDialect::Mssql.value_literal(&decimal, &DataType::Decimal),
+            "12.50"
+        );
+        assert_eq!(
+            SqlDialect::Mysql.value_literal(&Value::Null, &DataType::String),
+            "NULL"
+        );
+        let nul = Value::String("a\0b".to_string());
+        assert_eq!(
+            SqlDialect::Mysql.value_literal(&nul, &DataType::String),
+            "'a\\0b'"
+        );
+        assert_eq!(
+            SqlDialect::Postgres.value_literal(&nul, &DataType::String),
+            "'ab'"
+        );
+        let datetime = Value::Date(Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap());
+        assert_eq!(
+            SqlDialect::Mssql.value_literal(&datetime, &DataType::DateTime),
+            "N'2024-03-01 12:30:00.000'"
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let target = SqlScriptTarget::with_options("out/orders.sql.gz", &HashMap::new()).unwrap();
+        assert_eq!(target.table, "orders");
+        assert_eq!(target.dialect, SqlDialect::Postgres);
+        assert_eq!(target.compression, Compression::Gzip);
+
+        assert!(
+            SqlScriptTarget::with_options("a.sql", &options(&[("dialect", "oracle")])).is_err()
+        );
+        assert!(
+            SqlScriptTarget::with_options("a.sql", &options(&[("rows_per_insert", "0")])).is_err()
+        );
+        assert!(SqlScriptTarget::with_options(
+            "a.sql",
+            &options(&[("dialect", "mssql"), ("rows_per_insert", "5000")])
+        )
+        .is_err());
+    }
+}
@@ -4,8 +4,8 @@ use sqlx::{sqlite::SqliteConnectOptions, Column, Row as SqlxRow, SqlitePool};
 use std::path::PathBuf;
 
 use crate::{
-    connectors::{Source, Target},
-    schema::{Column as SchemaColumn, DataType, Row, Schema, Value},
+    connectors::{ddl::SqlDialect, Source, Target},
+    schema::{Column as SchemaColumn, DataType, Row, Schema, TableDdl, Value},
     Result, TinyEtlError,
 };
 
@@ -119,6 +119,11 @@ impl Source for SqliteSource {
         })
     }
 
+    async fn catalog_schema(&mut self) -> Result<Option<Schema>> {
+        // The schema is read from PRAGMA table_info, not sampled rows
+        self.infer_schema(0).await.map(Some)
+    }
+
     async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
         if self.pool.is_none() {
             self.connect().await?;
@@ -215,6 +220,7 @@ pub struct SqliteTarget {
     connection_string: String,
     pool: Option<SqlitePool>,
     table_name: String,
+    ddl: TableDdl,
 }
 
 impl SqliteTarget {
@@ -245,25 +251,29 @@ impl SqliteTarget {
             connection_string: format!("sqlite:{}", db_path),
             pool: None,
             table_name: table.to_string(),
+            ddl: TableDdl::default(),
         })
     }
 
+    async fn create_indexes(&self) -> Result<()> {
+        let pool = self
+            .pool
+            .as_ref()
+            .ok_or_else(|| TinyEtlError::Connection("Pool not connected".to_string()))?;
+
+        for (_, index_sql) in SqlDialect::Sqlite.index_statements(&self.ddl, &self.table_name) {
+            sqlx::query(&index_sql).execute(pool).await?;
+        }
+        Ok(())
+    }
+
     fn get_db_path(&self) -> Result<PathBuf> {
         let path_str = self.connection_string.trim_start_matches("sqlite:");
         Ok(PathBuf::from(path_str))
     }
 
     fn map_data_type_to_sqlite(&self, data_type: &DataType) -> &'static str {
-        match data_type {
-            DataType::Integer => "INTEGER",
-            DataType::Decimal => "REAL",
-            DataType::String => "TEXT",
-            DataType::Boolean => "INTEGER", // SQLite uses INTEGER for boolean
-            DataType::Date => "TEXT",
-            DataType::DateTime => "TEXT",
-            DataType::Json => "TEXT", // SQLite stores JSON as TEXT
-            DataType::Null => "TEXT",
-        }
+        SqlDialect::Sqlite.column_type(data_type)
     }
 }
 
@@ -313,15 +323,20 @@ impl Target for SqliteTarget {
         self.table_name = actual_table_name.clone();
 
         // Build CREATE TABLE statement with IF NOT EXISTS (append-first philosophy)
-        let column_definitions: Vec<String> = schema
+        let mut column_definitions: Vec<String> = schema
             .columns
             .iter()
             .map(|col| {
-                let sqlite_type = self.map_data_type_to_sqlite(&col.data_type);
+                let sqlite_type = self
+                    .ddl
+                    .sql_type(col)
+                    .unwrap_or_else(|| self.map_data_type_to_sqlite(&col.data_type));
                 let nullable = if col.nullable { "" } else { " NOT NULL" };
-                format!("\"{}\" {}{}", col.name, sqlite_type, nullable)
+                let clauses = SqlDialect::Sqlite.column_clauses(&self.ddl, &col.name);
+                format!("\"{}\" {}{}{}", col.name, sqlite_type, nullable, clauses)
             })
             .collect();
+        column_definitions.extend(SqlDialect::Sqlite.table_constraints(&self.ddl));
 
         // Use CREATE TABLE IF NOT EXISTS to support append-first philosophy
         let create_sql = format!(
@@ -331,6 +346,14 @@ impl Target for SqliteTarget {
         );
 
         sqlx::query(&create_sql).execute(pool).await?;
+
+        if self.ddl.comment.is_some() || !self.ddl.column_comments.is_empty() {
+            tracing::warn!("SQLite does not support table or column comments, skipping them");
+        }
+
+        if !self.ddl.indexes_after_load {
+            self.create_indexes().await?;
+        }
         Ok(())
     }
 
@@ -405,7 +428,10 @@ impl Target for SqliteTarget {
     }
 
     async fn finalize(&mut self) -> Result<()> {
-        // SQLite doesn't require explicit finalization
+        // Indexes are cheaper to build once than to maintain row by row
+        if self.ddl.indexes_after_load {
+            self.create_indexes().await?;
+        }
         Ok(())
     }
 
@@ -448,6 +474,10 @@ impl Target for SqliteTarget {
         // SQLite databases support appending new rows
         true
     }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.ddl = ddl;
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::{connectors::compression::Compression, Result};
+
+/// Bytes sampled from the start of a file to estimate its row count
+pub(crate) const ESTIMATE_SAMPLE_SIZE: u64 = 1024 * 1024;
+
+/// Counts the bytes read from the underlying file, before decompression and decoding
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+/// Open a file for streaming, decompressed. The bytes of the file consumed
+/// so far are kept in `bytes_read` for progress reporting.
+pub(crate) fn open_file(
+    path: &Path,
+    compression: Compression,
+    bytes_read: &Arc<AtomicU64>,
+) -> Result<Box<dyn Read + Send + Sync>> {
+    bytes_read.store(0, Ordering::Relaxed);
+    compression.decompress(CountingReader {
+        inner: File::open(path)?,
+        bytes_read: Arc::clone(bytes_read),
+    })
+}
+
+/// Count lines in a sample and scale by the share of the file it took up,
+/// so large files are never read in full. Compressed files are scaled by the
+/// compressed bytes consumed. Files smaller than the sample are exact.
+pub(crate) fn estimate_line_count(path: &Path, compression: Compression) -> Result<usize> {
+    let file_size = std::fs::metadata(path)?.len();
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let mut sample = Vec::new();
+    open_file(path, compression, &bytes_read)?
+        .take(ESTIMATE_SAMPLE_SIZE)
+        .read_to_end(&mut sample)?;
+    if sample.is_empty() {
+        return Ok(0);
+    }
+
+    let newlines = sample.iter().filter(|&&b| b == b'\n').count();
+    if sample.len() as u64 == ESTIMATE_SAMPLE_SIZE {
+        let consumed = bytes_read.load(Ordering::Relaxed).max(1);
+        Ok((newlines as f64 * file_size as f64 / consumed as f64).round() as usize)
+    } else {
+        Ok(newlines + usize::from(sample.last() != Some(&b'\n')))
+    }
+}
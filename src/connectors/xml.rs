@@ -0,0 +1,628 @@
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::{
+    connectors::{
+        compression::Compression, csv::CsvSource, encoding::TextEncoding, json::Flatten, stream,
+        Source,
+    },
+    schema::{DataType, Row, Schema, SchemaInferer, Value},
+    Result, TinyEtlError,
+};
+
+/// Key for the text of an element that also has attributes or children
+const TEXT_KEY: &str = "value";
+
+/// Elements selected as records by the `record_path` option, e.g.
+/// `/Orders/Order`. Names match by local name, ignoring namespace prefixes,
+/// unless the segment has a prefix itself; `*` matches any element.
+#[derive(Debug, Clone, PartialEq)]
+struct RecordPath {
+    segments: Vec<String>,
+}
+
+impl RecordPath {
+    fn parse(expression: &str) -> Result<Self> {
+        let segments: Vec<String> = expression
+            .trim()
+            .trim_start_matches('/')
+            .split('/')
+            .map(|s| s.trim().to_string())
+            .collect();
+        if segments.iter().any(|s| s.is_empty()) {
+            return Err(TinyEtlError::Configuration(format!(
+                "Invalid XML record_path '{}': expected element names separated by '/', e.g. /Orders/Order",
+                expression
+            )));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Whether the open elements, outermost first, are a record
+    fn matches(&self, open: &[String]) -> bool {
+        open.len() == self.segments.len()
+            && self.segments.iter().zip(open).all(|(segment, name)| {
+                segment == "*"
+                    || segment == name
+                    || (!segment.contains(':') && segment == local(name))
+            })
+    }
+}
+
+impl Default for RecordPath {
+    /// Each child of the document element, as in `<rows><row/><row/></rows>`
+    fn default() -> Self {
+        Self {
+            segments: vec!["*".to_string(), "*".to_string()],
+        }
+    }
+}
+
+/// A name without its namespace prefix
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Attributes, child elements and text of an element being read, in
+/// document order. Repeated children are collected into an array.
+#[derive(Default)]
+struct Element {
+    /// Key, qualified name and value of each field
+    fields: Vec<(String, String, serde_json::Value)>,
+    text: String,
+}
+
+impl Element {
+    fn insert(&mut self, name: String, value: serde_json::Value) {
+        let qualified = name.clone();
+        self.insert_qualified(name, qualified, value);
+    }
+
+    /// Add a field keyed by the local name of `qualified`. Children with the
+    /// same local name from different namespaces, such as `o:Item` and
+    /// `c:Item`, are both keyed by their qualified names instead.
+    fn insert_qualified(&mut self, key: String, qualified: String, value: serde_json::Value) {
+        if let Some((_, _, existing)) = self.fields.iter_mut().find(|(_, q, _)| *q == qualified) {
+            match existing {
+                serde_json::Value::Array(values) => values.push(value),
+                existing => {
+                    let first = existing.take();
+                    *existing = serde_json::Value::Array(vec![first, value]);
+                }
+            }
+            return;
+        }
+        let key = match self.fields.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((other_key, other_qualified, _)) => {
+                *other_key = other_qualified.clone();
+                qualified.clone()
+            }
+            None => key,
+        };
+        self.fields.push((key, qualified, value));
+    }
+
+    /// Fields of a record, with any text of its own under `value`
+    fn into_fields(mut self) -> Vec<(String, serde_json::Value)> {
+        let text = self.text.trim();
+        if !text.is_empty() {
+            let text = serde_json::Value::String(text.to_string());
+            self.insert(TEXT_KEY.to_string(), text);
+        }
+        self.fields
+            .into_iter()
+            .map(|(key, _, value)| (key, value))
+            .collect()
+    }
+
+    /// A child element as a value: its text when it has only text, null when
+    /// empty, otherwise an object
+    fn into_value(self) -> serde_json::Value {
+        if self.fields.is_empty() {
+            let text = self.text.trim();
+            return if text.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::Value::String(text.to_string())
+            };
+        }
+        serde_json::Value::Object(self.into_fields().into_iter().collect())
+    }
+}
+
+type XmlReader = Reader<BufReader<Box<dyn Read + Send + Sync>>>;
+
+/// XML source: streams the document and returns the elements at the
+/// `record_path` option as rows, with attributes and child elements as columns
+pub struct XmlSource {
+    file_path: PathBuf,
+    encoding: TextEncoding,
+    compression: Compression,
+    record_path: RecordPath,
+    /// Prepended to attribute names to tell them apart from child elements
+    attribute_prefix: String,
+    flatten: Flatten,
+    reader: Option<XmlReader>,
+    /// Qualified names of the elements enclosing the reader position
+    open_elements: Vec<String>,
+    /// Columns in the order they were first seen
+    columns: Vec<String>,
+    /// Column types from `set_schema`, e.g. a schema file
+    declared_types: HashMap<String, DataType>,
+    /// Column types from the last `infer_schema`
+    inferred_types: HashMap<String, DataType>,
+    /// Columns that held repeated elements since the last `infer_schema`
+    array_columns: HashSet<String>,
+    has_more_data: bool,
+    /// Bytes of the file consumed by the current reader
+    bytes_read: Arc<AtomicU64>,
+    file_size: u64,
+}
+
+impl XmlSource {
+    pub fn new(file_path: &str) -> Result<Self> {
+        Self::with_options(file_path, &HashMap::new())
+    }
+
+    /// `record_path` selects the repeating elements to read as rows, each
+    /// child of the document element by default. Nested elements are kept as
+    /// JSON unless `flatten` expands them into columns.
+    pub fn with_options(file_path: &str, options: &HashMap<String, String>) -> Result<Self> {
+        let record_path = match options.get("record_path") {
+            Some(path) => RecordPath::parse(path)?,
+            None => RecordPath::default(),
+        };
+        Ok(Self {
+            file_path: PathBuf::from(file_path),
+            encoding: TextEncoding::from_options(options)?,
+            compression: Compression::from_options(options, file_path)?,
+            record_path,
+            attribute_prefix: options.get("attribute_prefix").cloned().unwrap_or_default(),
+            flatten: Flatten::from_options(options)?,
+            reader: None,
+            open_elements: Vec::new(),
+            columns: Vec::new(),
+            declared_types: HashMap::new(),
+            inferred_types: HashMap::new(),
+            array_columns: HashSet::new(),
+            has_more_data: true,
+            bytes_read: Arc::new(AtomicU64::new(0)),
+            file_size: 0,
+        })
+    }
+
+    fn open_reader(&self) -> Result<XmlReader> {
+        let file = stream::open_file(&self.file_path, self.compression, &self.bytes_read)?;
+        let mut reader = Reader::from_reader(BufReader::new(self.encoding.decode(file)?));
+        reader.config_mut().trim_text(true);
+        Ok(reader)
+    }
+
+    /// Read up to the next record element and return its fields
+    fn next_record(&mut self) -> Result<Option<Vec<(String, serde_json::Value)>>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Ok(None),
+        };
+
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            let event = reader
+                .read_event_into(&mut buffer)
+                .map_err(|e| xml_error(&self.file_path, reader, e))?;
+            match event {
+                Event::Start(start) => {
+                    self.open_elements.push(name(&start));
+                    if self.record_path.matches(&self.open_elements) {
+                        let start = start.into_owned();
+                        let element =
+                            read_element(reader, &start, &self.attribute_prefix, &self.file_path)?;
+                        self.open_elements.pop();
+                        return Ok(Some(element.into_fields()));
+                    }
+                }
+                Event::Empty(start) => {
+                    self.open_elements.push(name(&start));
+                    let matched = self.record_path.matches(&self.open_elements);
+                    self.open_elements.pop();
+                    if matched {
+                        let mut element = Element::default();
+                        add_attributes(&mut element, &start, &self.attribute_prefix)?;
+                        return Ok(Some(element.into_fields()));
+                    }
+                }
+                Event::End(_) => {
+                    self.open_elements.pop();
+                }
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    fn next_row(&mut self) -> Result<Option<Row>> {
+        let fields = match self.next_record()? {
+            Some(fields) => fields,
+            None => return Ok(None),
+        };
+
+        let mut row = Row::new();
+        for (key, value) in &fields {
+            let key = key.clone();
+            self.flatten
+                .insert_value(&mut row, key, value, &mut |column, value| {
+                    if !self.columns.iter().any(|c| c == column) {
+                        self.columns.push(column.to_string());
+                    }
+                    match value {
+                        serde_json::Value::String(text) => {
+                            let data_type = self
+                                .declared_types
+                                .get(column)
+                                .or_else(|| self.inferred_types.get(column));
+                            match CsvSource::parse_as(text, data_type) {
+                                // A single element in a column of repeated ones
+                                Value::String(text) if data_type == Some(&DataType::Json) => {
+                                    Value::Json(serde_json::Value::String(text))
+                                }
+                                value => value,
+                            }
+                        }
+                        serde_json::Value::Null => Value::Null,
+                        nested => {
+                            if nested.is_array() {
+                                self.array_columns.insert(column.to_string());
+                            }
+                            Value::Json(nested.clone())
+                        }
+                    }
+                });
+        }
+        Ok(Some(row))
+    }
+}
+
+/// Qualified name of an element, with any namespace prefix
+fn name(start: &BytesStart) -> String {
+    String::from_utf8_lossy(start.name().as_ref()).into_owned()
+}
+
+/// Add the attributes of an element, by local name. Namespace declarations
+/// are left out.
+fn add_attributes(element: &mut Element, start: &BytesStart, prefix: &str) -> Result<()> {
+    for attribute in start.attributes() {
+        let attribute = attribute
+            .map_err(|e| TinyEtlError::DataValidation(format!("Invalid XML attribute: {}", e)))?;
+        if attribute.key.as_namespace_binding().is_some() {
+            continue;
+        }
+        let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+        let value = attribute.unescape_value().map_err(|e| {
+            TinyEtlError::DataValidation(format!("Invalid XML attribute '{}': {}", key, e))
+        })?;
+        element.insert(
+            format!("{}{}", prefix, key),
+            serde_json::Value::String(value.into_owned()),
+        );
+    }
+    Ok(())
+}
+
+/// Read the content of an element whose start tag was just read, up to and
+/// including its end tag
+fn read_element(
+    reader: &mut XmlReader,
+    start: &BytesStart,
+    prefix: &str,
+    path: &std::path::Path,
+) -> Result<Element> {
+    let mut element = Element::default();
+    add_attributes(&mut element, start, prefix)?;
+
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        let event = reader
+            .read_event_into(&mut buffer)
+            .map_err(|e| xml_error(path, reader, e))?;
+        match event {
+            Event::Start(child) => {
+                let child = child.into_owned();
+                let key = String::from_utf8_lossy(child.local_name().as_ref()).into_owned();
+                let value = read_element(reader, &child, prefix, path)?.into_value();
+                element.insert_qualified(key, name(&child), value);
+            }
+            Event::Empty(child) => {
+                let key = String::from_utf8_lossy(child.local_name().as_ref()).into_owned();
+                let mut empty = Element::default();
+                add_attributes(&mut empty, &child, prefix)?;
+                element.insert_qualified(key, name(&child), empty.into_value());
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| xml_error(path, reader, e))?;
+                element.text.push_str(&text);
+            }
+            Event::CData(data) => {
+                element.text.push_str(&String::from_utf8_lossy(&data));
+            }
+            Event::End(_) => return Ok(element),
+            Event::Eof => {
+                return Err(TinyEtlError::DataValidation(format!(
+                    "Unexpected end of XML in {}",
+                    path.display()
+                )))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn xml_error<R: BufRead>(
+    path: &std::path::Path,
+    reader: &Reader<R>,
+    error: quick_xml::Error,
+) -> TinyEtlError {
+    TinyEtlError::DataValidation(format!(
+        "Invalid XML in {} at byte {}: {}",
+        path.display(),
+        reader.error_position(),
+        error
+    ))
+}
+
+#[async_trait]
+impl Source for XmlSource {
+    async fn connect(&mut self) -> Result<()> {
+        if !self.file_path.exists() {
+            return Err(TinyEtlError::Connection(format!(
+                "XML file not found: {}",
+                self.file_path.display()
+            )));
+        }
+
+        self.file_size = std::fs::metadata(&self.file_path)?.len();
+        self.reset().await
+    }
+
+    async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        // Guess afresh rather than parsing as the previous inference's types
+        self.inferred_types.clear();
+        self.array_columns.clear();
+        let mut sample_rows = Vec::new();
+        while sample_rows.len() < sample_size {
+            match self.next_row()? {
+                Some(row) => sample_rows.push(row),
+                None => break,
+            }
+        }
+
+        // Reset for future reading
+        self.reset().await?;
+
+        // Columns in the order they appear in the document
+        let mut schema = SchemaInferer::infer_from_rows(&sample_rows)?;
+        schema
+            .columns
+            .sort_by_key(|c| self.columns.iter().position(|name| *name == c.name));
+        // Elements repeated in some records are arrays, so the column is JSON
+        for column in &mut schema.columns {
+            if self.array_columns.contains(&column.name) {
+                column.data_type = DataType::Json;
+            }
+        }
+
+        // Parse the remaining rows consistently as the inferred types
+        self.inferred_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        Ok(schema)
+    }
+
+    fn set_schema(&mut self, schema: &Schema) {
+        self.declared_types = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        if self.reader.is_none() {
+            self.connect().await?;
+        }
+
+        let mut rows = Vec::new();
+        while rows.len() < batch_size {
+            match self.next_row()? {
+                Some(row) => rows.push(row),
+                None => {
+                    self.has_more_data = false;
+                    break;
+                }
+            }
+        }
+
+        Ok(rows)
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.reader = Some(self.open_reader()?);
+        self.open_elements.clear();
+        self.has_more_data = true;
+        Ok(())
+    }
+
+    fn has_more(&self) -> bool {
+        self.has_more_data && self.reader.is_some()
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.reader.as_ref()?;
+        let bytes_read = self.bytes_read.load(Ordering::Relaxed);
+        Some((bytes_read.min(self.file_size), self.file_size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::options;
+    use rust_decimal::Decimal;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const ORDERS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<o:Export xmlns:o="urn:orders" xmlns:c="urn:customers">
+  <o:Header created="2024-05-01"/>
+  <o:Orders>
+    <o:Order id="1" status="paid">
+      <o:Total currency="EUR">19.90</o:Total>
+      <c:Customer><c:Name>Ada &amp; Co</c:Name><c:City>Paris</c:City></c:Customer>
+      <o:Item>pen</o:Item>
+      <o:Item>ink</o:Item>
+      <o:Note/>
+    </o:Order>
+    <!-- a comment -->
+    <o:Order id="2" status="open">
+      <o:Total currency="USD">5.50</o:Total>
+      <c:Customer><c:Name><![CDATA[Bob <B>]]></c:Name><c:City>Oslo</c:City></c:Customer>
+      <o:Item>paper</o:Item>
+    </o:Order>
+  </o:Orders>
+</o:Export>"#;
+
+    fn xml_file(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::with_suffix(".xml").unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    fn xml_source(file: &NamedTempFile, pairs: &[(&str, &str)]) -> XmlSource {
+        XmlSource::with_options(file.path().to_str().unwrap(), &options(pairs)).unwrap()
+    }
+
+    #[test]
+    fn test_record_path() {
+        let path = RecordPath::parse("/Orders/o:Order").unwrap();
+        let open = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(path.matches(&open(&["x:Orders", "o:Order"])));
+        assert!(!path.matches(&open(&["Orders", "p:Order"])));
+        assert!(!path.matches(&open(&["Orders"])));
+        assert!(RecordPath::default().matches(&open(&["rows", "row"])));
+        assert!(RecordPath::parse("/Orders//Order").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_xml_source_nested_records() {
+        let file = xml_file(ORDERS);
+        let mut source = xml_source(&file, &[("record_path", "/Export/Orders/Order")]);
+        source.connect().await.unwrap();
+
+        let schema = source.infer_schema(10).await.unwrap();
+        let columns: Vec<(&str, &DataType)> = schema
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), &c.data_type))
+            .collect();
+        assert_eq!(
+            columns,
+            [
+                ("id", &DataType::Integer),
+                ("status", &DataType::String),
+                ("Total", &DataType::Json),
+                ("Customer", &DataType::Json),
+                // Repeated in one order and not the other
+                ("Item", &DataType::Json),
+                ("Note", &DataType::String),
+            ]
+        );
+
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["id"], Value::Integer(1));
+        assert_eq!(
+            rows[0]["Customer"],
+            Value::Json(serde_json::json!({"Name": "Ada & Co", "City": "Paris"}))
+        );
+        assert_eq!(
+            rows[0]["Item"],
+            Value::Json(serde_json::json!(["pen", "ink"]))
+        );
+        assert_eq!(rows[1]["Item"], Value::Json(serde_json::json!("paper")));
+    }
+
+    #[tokio::test]
+    async fn test_xml_source_same_local_name() {
+        let file = xml_file(
+            r#"<rows xmlns:a="urn:a" xmlns:b="urn:b">
+  <row><a:id>1</a:id><b:id>x</b:id><b:id>y</b:id><name>n</name></row>
+</rows>"#,
+        );
+        let mut source = xml_source(&file, &[]);
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows[0]["a:id"], Value::Integer(1));
+        assert_eq!(rows[0]["b:id"], Value::Json(serde_json::json!(["x", "y"])));
+        assert_eq!(rows[0]["name"], Value::String("n".to_string()));
+        assert!(!rows[0].contains_key("id"));
+    }
+
+    #[tokio::test]
+    async fn test_xml_source_flatten_and_attribute_prefix() {
+        let file = xml_file(ORDERS);
+        let mut source = xml_source(
+            &file,
+            &[
+                ("record_path", "/*/Orders/Order"),
+                ("flatten", "true"),
+                ("attribute_prefix", "@"),
+            ],
+        );
+        source.connect().await.unwrap();
+        source.infer_schema(10).await.unwrap();
+
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows[0]["@id"], Value::Integer(1));
+        assert_eq!(rows[0]["Total_@currency"], Value::String("EUR".to_string()));
+        assert_eq!(
+            rows[0]["Total_value"],
+            Value::Decimal(Decimal::new(1990, 2))
+        );
+        assert_eq!(rows[1]["Total_value"], Value::Decimal(Decimal::new(550, 2)));
+        assert_eq!(
+            rows[1]["Customer_Name"],
+            Value::String("Bob <B>".to_string())
+        );
+        assert_eq!(rows[1]["Customer_City"], Value::String("Oslo".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_xml_source_default_path_and_errors() {
+        let file = xml_file("<rows><row a='1'/><row><a>2</a></row></rows>");
+        let mut source = xml_source(&file, &[]);
+        let rows = source.read_batch(10).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["a"], Value::Integer(2));
+
+        let file = xml_file("<rows><row><a>1</b></row></rows>");
+        let mut source = xml_source(&file, &[]);
+        let err = source.read_batch(10).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid XML"), "{}", err);
+    }
+}
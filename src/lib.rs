@@ -4,6 +4,7 @@ pub mod connectors;
 pub mod date_parser;
 pub mod error;
 pub mod protocols;
+pub mod quality;
 pub mod schema;
 pub mod secrets;
 pub mod transfer;
@@ -5,7 +5,12 @@ use tracing_subscriber::{fmt, EnvFilter};
 use tinyetl::{
     cli::Cli,
     config::Config,
-    connectors::{create_source_from_url_with_type_and_options, create_target_from_url_with_options, Source, Target},
+    connectors::{
+        create_source_from_url_with_type_and_options,
+        create_target_from_url_with_type_and_options, Source, Target,
+    },
+    protocols::stdio,
+    schema::SchemaFile,
     secrets::process_connection_string,
     transfer::TransferEngine,
     yaml_config::YamlConfig,
@@ -25,6 +30,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return handle_generate_config(cli);
     }
 
+    // handle schema subcommand
+    if cli.is_schema_mode() {
+        return handle_schema(cli).await;
+    }
+
     // load configuration either from YAML file or CLI arguments
     let config = load_config(cli)?;
     setup_logging(&config);
@@ -52,6 +62,7 @@ fn handle_generate_config(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         transform_file,
         transform,
         source_type,
+        target_type,
         source_secret_id,
         dest_secret_id,
     }) = cli.command
@@ -71,6 +82,7 @@ fn handle_generate_config(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             truncate,
             transform: transform_config,
             source_type,
+            target_type,
             source_secret_id,
             dest_secret_id,
             source_options: std::collections::HashMap::new(),
@@ -85,6 +97,41 @@ fn handle_generate_config(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Handle the schema subcommand by describing a table or inferring a source as a schema file
+async fn handle_schema(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(tinyetl::cli::Commands::Schema {
+        uri,
+        format,
+        sample_size,
+        source_type,
+        secret_id,
+    }) = cli.command
+    {
+        let processed_uri = process_connection_string(&uri, secret_id.as_ref(), "source")?;
+        let mut source = create_source_from_url_with_type_and_options(
+            &processed_uri,
+            source_type.as_deref(),
+            &std::collections::HashMap::new(),
+        )
+        .await?;
+        source.connect().await?;
+
+        // Prefer the database catalog so nullability and types reflect the table definition
+        let schema = match source.catalog_schema().await? {
+            Some(schema) => schema,
+            None => source.infer_schema(sample_size).await?,
+        };
+
+        source.reset().await?;
+        let sample = source.read_batch(sample_size).await?;
+
+        let schema_file = SchemaFile::from_schema(&schema, &sample);
+        println!("{}", schema_file.to_formatted_string(format)?);
+    }
+
+    Ok(())
+}
+
 /// Handle the generate-default-config subcommand by outputting the example config from README.md
 fn handle_generate_default_config() -> Result<(), Box<dyn std::error::Error>> {
     let default_config = r#"# TinyETL Default Configuration Example
@@ -135,6 +182,7 @@ options:
   log_level: info                 # info, warn, error (lowercase in YAML)
   skip_existing: false            # Skip if target exists
   source_type: "csv"              # Force source file type
+  # target_type: "jsonl"          # Target file type, needed when the target uri is "-" (stdout)
   truncate: false                 # Truncate target before writing
   transform:                      # Inline Lua script transformation
     type: script
@@ -194,6 +242,7 @@ fn load_config(cli: Cli) -> Result<Config, Box<dyn std::error::Error>> {
 
 // Initialize logging with specific module filtering
 // Respect RUST_LOG environment variable if set, otherwise use config
+// Logs go to stderr when stdout carries the data
 fn setup_logging(config: &Config) {
     let env_filter = if std::env::var("RUST_LOG").is_ok() {
         EnvFilter::from_default_env()
@@ -208,7 +257,11 @@ fn setup_logging(config: &Config) {
         ))
     };
 
-    fmt().with_env_filter(env_filter).init();
+    if stdio::is_stdout(&config.target) {
+        fmt().with_env_filter(env_filter).with_writer(std::io::stderr).init();
+    } else {
+        fmt().with_env_filter(env_filter).init();
+    }
 }
 
 /// Create source and target connectors with secret processing
@@ -230,8 +283,9 @@ async fn create_connectors(
         &config.source_options,
     ).await?;
     
-    let target = create_target_from_url_with_options(
+    let target = create_target_from_url_with_type_and_options(
         &processed_target,
+        config.target_type.as_deref(),
         &config.target_options,
     ).await?;
 
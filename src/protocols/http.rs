@@ -1,5 +1,8 @@
 use crate::{
-    connectors::{create_source, Source, Target},
+    connectors::{
+        compression::{compression_suffix, split_compression_suffix},
+        create_source_with_options, Source, Target,
+    },
     protocols::Protocol,
     Result, TinyEtlError,
 };
@@ -107,13 +110,15 @@ impl HttpProtocol {
         Ok(temp_file)
     }
 
-    /// Extract file extension from URL path for proper temporary file naming
+    /// Extract file extension from URL path for proper temporary file naming.
+    /// A compression suffix is kept with the format, e.g. `csv.gz`
     fn extract_extension_from_url(&self, url: &Url) -> Option<String> {
         let path = url.path();
         if let Some(filename) = path.split('/').next_back() {
+            let (filename, compression) = split_compression_suffix(filename);
             if let Some(extension) = filename.split('.').next_back() {
                 if !extension.is_empty() && extension.len() <= 10 && extension != filename {
-                    return Some(extension.to_lowercase());
+                    return Some(format!("{}{}", extension, compression).to_lowercase());
                 }
             }
         }
@@ -123,7 +128,9 @@ impl HttpProtocol {
     /// Extract file extension from URL with optional type override
     fn get_file_extension(&self, url: &Url, source_type: Option<&str>) -> Option<String> {
         if let Some(forced_type) = source_type {
-            Some(forced_type.to_lowercase())
+            // data.gz with source_type csv is downloaded as .csv.gz
+            let compression = compression_suffix(url.path()).unwrap_or_default();
+            Some(format!("{}{}", forced_type, compression).to_lowercase())
         } else {
             self.extract_extension_from_url(url)
         }
@@ -167,7 +174,7 @@ impl Protocol for HttpProtocol {
         // Create source using the persistent temporary file path
         // Note: This file will not be automatically cleaned up
         // In a production implementation, we'd want better lifecycle management
-        create_source(&final_path)
+        create_source_with_options(&final_path, options)
     }
 
     async fn create_target(
@@ -263,6 +270,19 @@ mod tests {
             protocol.extract_extension_from_url(&url),
             Some("csv".to_string())
         );
+
+        // Compressed files keep the format in front of the compression suffix
+        let url = Url::parse("https://example.com/orders.CSV.gz").unwrap();
+        assert_eq!(
+            protocol.extract_extension_from_url(&url),
+            Some("csv.gz".to_string())
+        );
+        let url = Url::parse("https://example.com/export.zst").unwrap();
+        assert_eq!(protocol.extract_extension_from_url(&url), None);
+        assert_eq!(
+            protocol.get_file_extension(&url, Some("json")),
+            Some("json.zst".to_string())
+        );
     }
 
     #[test]
@@ -2,6 +2,7 @@ pub mod file;
 pub mod http;
 pub mod snowflake;
 pub mod ssh;
+pub mod stdio;
 
 use crate::{
     connectors::{Source, Target},
@@ -41,6 +42,17 @@ pub trait Protocol: Send + Sync {
         options: &HashMap<String, String>,
     ) -> Result<Box<dyn Target>>;
 
+    /// Create a target connector with a type hint (needed for standard output)
+    async fn create_target_with_type(
+        &self,
+        url: &Url,
+        _target_type: Option<&str>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn Target>> {
+        // Default implementation ignores the type hint
+        self.create_target(url, options).await
+    }
+
     /// Validate that the URL is properly formatted for this protocol
     fn validate_url(&self, url: &Url) -> Result<()>;
 
@@ -63,9 +75,10 @@ pub fn create_protocol(url: &str) -> Result<Box<dyn Protocol>> {
         "snowflake" => Ok(Box::new(snowflake::SnowflakeProtocol::new())),
         "http" | "https" => Ok(Box::new(http::HttpProtocol::new())),
         "ssh" => Ok(Box::new(ssh::SshProtocol::new())),
+        "stdin" | "stdout" => Ok(Box::new(stdio::StdioProtocol::new())),
         scheme => {
             Err(TinyEtlError::Configuration(
-                format!("Unsupported protocol: {}. Supported protocols: file://, snowflake://, http://, https://, ssh://", scheme)
+                format!("Unsupported protocol: {}. Supported protocols: file://, snowflake://, http://, https://, ssh://, stdin://, stdout://", scheme)
             ))
         }
     }
@@ -108,6 +121,15 @@ pub async fn create_target_from_url(url: &str) -> Result<Box<dyn Target>> {
 pub async fn create_target_from_url_with_options(
     url: &str,
     options: &HashMap<String, String>,
+) -> Result<Box<dyn Target>> {
+    create_target_from_url_with_type(url, None, options).await
+}
+
+/// Helper function to create target using protocol abstraction with optional type hint
+pub async fn create_target_from_url_with_type(
+    url: &str,
+    target_type: Option<&str>,
+    options: &HashMap<String, String>,
 ) -> Result<Box<dyn Target>> {
     let protocol = create_protocol(url)?;
     let parsed_url = if url.contains("://") {
@@ -121,7 +143,9 @@ pub async fn create_target_from_url_with_options(
     };
 
     protocol.validate_url(&parsed_url)?;
-    protocol.create_target(&parsed_url, options).await
+    protocol
+        .create_target_with_type(&parsed_url, target_type, options)
+        .await
 }
 
 #[cfg(test)]
@@ -165,6 +189,14 @@ mod tests {
         assert_eq!(protocol.unwrap().name(), "ssh");
     }
 
+    #[test]
+    fn test_create_stdio_protocol() {
+        let protocol = create_protocol("stdin://");
+        assert!(protocol.is_ok());
+        assert_eq!(protocol.unwrap().name(), "stdio");
+        assert_eq!(create_protocol("stdout://").unwrap().name(), "stdio");
+    }
+
     #[test]
     fn test_backward_compatibility() {
         // Test that old file paths still work
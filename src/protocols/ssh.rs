@@ -1,5 +1,7 @@
 use crate::{
-    connectors::{create_source, Source, Target},
+    connectors::{
+        compression::split_compression_suffix, create_source_with_options, Source, Target,
+    },
     protocols::Protocol,
     Result, TinyEtlError,
 };
@@ -152,12 +154,14 @@ impl SshProtocol {
         Ok(())
     }
 
-    /// Extract file extension from remote path
+    /// Extract file extension from remote path.
+    /// A compression suffix is kept with the format, e.g. `csv.gz`
     fn extract_extension_from_path(&self, path: &str) -> Option<String> {
         if let Some(filename) = path.split('/').next_back() {
+            let (filename, compression) = split_compression_suffix(filename);
             if let Some(extension) = filename.split('.').next_back() {
                 if !extension.is_empty() && extension.len() <= 10 && extension != filename {
-                    return Some(extension.to_lowercase());
+                    return Some(format!("{}{}", extension, compression).to_lowercase());
                 }
             }
         }
@@ -170,9 +174,10 @@ impl Protocol for SshProtocol {
     async fn create_source(
         &self, 
         url: &Url,
-        _options: &HashMap<String, String>,
+        options: &HashMap<String, String>,
     ) -> Result<Box<dyn Source>> {
-        // SSH protocol could use options for:
+        // Options are passed on to the connector as format options.
+        // SSH protocol could also use options for:
         // - identity_file: path to SSH key
         // - known_hosts_file: path to known_hosts
         // - ssh_options: additional SSH options
@@ -183,7 +188,7 @@ impl Protocol for SshProtocol {
         // Create source using the temporary file path
         // Note: Similar limitation as HTTP - the temp file lifetime management
         // could be improved
-        create_source(&temp_path)
+        create_source_with_options(&temp_path, options)
     }
 
     async fn create_target(
@@ -281,9 +286,15 @@ mod tests {
             Some("parquet".to_string())
         );
 
+        assert_eq!(
+            protocol.extract_extension_from_path("/exports/orders.csv.gz"),
+            Some("csv.gz".to_string())
+        );
+
         // Paths without extensions
         assert_eq!(protocol.extract_extension_from_path("/path/to/data"), None);
         assert_eq!(protocol.extract_extension_from_path("/api/endpoint"), None);
+        assert_eq!(protocol.extract_extension_from_path("/path/to/data.gz"), None);
     }
 
     #[test]
@@ -381,9 +392,10 @@ mod tests {
             protocol.extract_extension_from_path("/file.JSON"),
             Some("json".to_string())
         );
+        // The compression suffix stays with the extension before it
         assert_eq!(
             protocol.extract_extension_from_path("/archive.tar.gz"),
-            Some("gz".to_string())
+            Some("tar.gz".to_string())
         );
 
         // Test nested paths
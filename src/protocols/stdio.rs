@@ -0,0 +1,419 @@
+use crate::{
+    connectors::{
+        compression, create_source_with_options, create_target_with_options, csv::CsvTarget,
+        json::JsonTarget, jsonl::JsonlTarget, sql_script::SqlScriptTarget, Source, Target,
+    },
+    protocols::Protocol,
+    schema::{Row, Schema, TableDdl},
+    Result, TinyEtlError,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use tempfile::{NamedTempFile, TempDir};
+use tracing::info;
+use url::Url;
+
+/// Whether a source URI reads standard input: `-` or `stdin://`
+pub fn is_stdin(uri: &str) -> bool {
+    uri == "-" || uri.starts_with("stdin://")
+}
+
+/// Whether a target URI writes standard output: `-` or `stdout://`
+pub fn is_stdout(uri: &str) -> bool {
+    uri == "-" || uri.starts_with("stdout://")
+}
+
+/// Standard input and output, for use in shell pipelines. Data has no file
+/// name to tell its format, so the source or target type must be given.
+///
+/// Standard input is spooled to a temporary file first, because formats such
+/// as Parquet need to seek and schema inference reads the data twice. Text
+/// targets write standard output as each batch arrives; Parquet, Avro, Arrow
+/// and Excel targets write a temporary file that is copied once finalized.
+pub struct StdioProtocol;
+
+impl Default for StdioProtocol {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StdioProtocol {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// File name suffix for a format such as `csv` or `csv.gz`
+    fn extension(format: Option<&str>, direction: &str) -> Result<String> {
+        let option = if direction == "input" {
+            "--source-type"
+        } else {
+            "--target-type"
+        };
+        match format.map(|f| f.trim().trim_start_matches('.').to_lowercase()) {
+            Some(format) if !format.is_empty() => Ok(format),
+            _ => Err(TinyEtlError::Configuration(format!(
+                "Standard {} has no file name to tell its format: use {} (csv, jsonl, json, parquet, ...)",
+                direction, option
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl Protocol for StdioProtocol {
+    async fn create_source(
+        &self,
+        url: &Url,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn Source>> {
+        self.create_source_with_type(url, None, options).await
+    }
+
+    async fn create_source_with_type(
+        &self,
+        url: &Url,
+        source_type: Option<&str>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn Source>> {
+        if url.scheme() != "stdin" {
+            return Err(TinyEtlError::Configuration(
+                "Standard output can't be read: use - or stdin:// as the source".to_string(),
+            ));
+        }
+        let extension = Self::extension(source_type, "input")?;
+        let mut file = tempfile::Builder::new()
+            .prefix("tinyetl_stdin_")
+            .suffix(&format!(".{}", extension))
+            .tempfile()
+            .map_err(TinyEtlError::Io)?;
+
+        info!("Reading standard input as {}", extension);
+        let bytes = std::io::copy(&mut std::io::stdin().lock(), &mut file)?;
+        file.flush()?;
+        info!("Read {} bytes from standard input", bytes);
+
+        let inner = create_source_with_options(&file.path().to_string_lossy(), options)?;
+        Ok(Box::new(StdinSource { inner, _file: file }))
+    }
+
+    async fn create_target(
+        &self,
+        url: &Url,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn Target>> {
+        self.create_target_with_type(url, None, options).await
+    }
+
+    async fn create_target_with_type(
+        &self,
+        url: &Url,
+        target_type: Option<&str>,
+        options: &HashMap<String, String>,
+    ) -> Result<Box<dyn Target>> {
+        if url.scheme() != "stdout" {
+            return Err(TinyEtlError::Configuration(
+                "Standard input can't be written: use - or stdout:// as the target".to_string(),
+            ));
+        }
+        let extension = Self::extension(target_type, "output")?;
+        Ok(Box::new(StdoutTarget::new(&extension, options)?))
+    }
+
+    fn validate_url(&self, url: &Url) -> Result<()> {
+        match url.scheme() {
+            "stdin" | "stdout" => Ok(()),
+            scheme => Err(TinyEtlError::Configuration(format!(
+                "Stdio protocol requires stdin:// or stdout:// scheme, got: {}",
+                scheme
+            ))),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "stdio"
+    }
+}
+
+/// Source reading standard input from the file it was spooled to, which is
+/// removed when the source is dropped
+struct StdinSource {
+    inner: Box<dyn Source>,
+    _file: NamedTempFile,
+}
+
+#[async_trait]
+impl Source for StdinSource {
+    async fn connect(&mut self) -> Result<()> {
+        self.inner.connect().await
+    }
+
+    async fn infer_schema(&mut self, sample_size: usize) -> Result<Schema> {
+        self.inner.infer_schema(sample_size).await
+    }
+
+    fn set_schema(&mut self, schema: &Schema) {
+        self.inner.set_schema(schema)
+    }
+
+    async fn read_batch(&mut self, batch_size: usize) -> Result<Vec<Row>> {
+        self.inner.read_batch(batch_size).await
+    }
+
+    async fn estimated_row_count(&self) -> Result<Option<usize>> {
+        self.inner.estimated_row_count().await
+    }
+
+    fn progress_bytes(&self) -> Option<(u64, u64)> {
+        self.inner.progress_bytes()
+    }
+
+    async fn reset(&mut self) -> Result<()> {
+        self.inner.reset().await
+    }
+
+    fn has_more(&self) -> bool {
+        self.inner.has_more()
+    }
+}
+
+/// Standard output, locked for each write so a buffered chunk of rows isn't
+/// interleaved with other output
+struct StdoutWriter;
+
+impl Write for StdoutWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        std::io::stdout().lock().write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        std::io::stdout().lock().write_all(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::stdout().lock().flush()
+    }
+}
+
+/// Temporary file of a target that needs to seek, and where it is copied
+struct Spool {
+    path: PathBuf,
+    output: Box<dyn Write + Send + Sync>,
+    _dir: TempDir,
+}
+
+/// Target writing standard output. CSV, JSON, JSON Lines and SQL scripts are
+/// written as batches arrive; other formats are spooled to a temporary file
+/// that is copied to standard output when the transfer is finalized.
+struct StdoutTarget {
+    inner: Box<dyn Target>,
+    spool: Option<Spool>,
+}
+
+impl StdoutTarget {
+    fn new(extension: &str, options: &HashMap<String, String>) -> Result<Self> {
+        Self::with_output(extension, options, StdoutWriter)
+    }
+
+    fn with_output<W: Write + Send + Sync + 'static>(
+        extension: &str,
+        options: &HashMap<String, String>,
+        output: W,
+    ) -> Result<Self> {
+        // stdout.csv.gz is written as CSV and compressed, like a file
+        let name = format!("stdout.{}", extension);
+        let format = compression::strip_compression_suffix(&name).unwrap_or(&name);
+        let inner: Box<dyn Target> = if format.ends_with(".csv") {
+            Box::new(CsvTarget::with_options(&name, options)?.with_output(output))
+        } else if format.ends_with(".json") {
+            Box::new(JsonTarget::with_options(&name, options)?.with_output(output))
+        } else if format.ends_with(".jsonl") || format.ends_with(".ndjson") {
+            Box::new(JsonlTarget::with_options(&name, options)?.with_output(output))
+        } else if format.ends_with(".sql") {
+            Box::new(SqlScriptTarget::with_options(&name, options)?.with_output(output))
+        } else {
+            return Self::spooled(&name, options, Box::new(output));
+        };
+        Ok(Self { inner, spool: None })
+    }
+
+    fn spooled(
+        name: &str,
+        options: &HashMap<String, String>,
+        output: Box<dyn Write + Send + Sync>,
+    ) -> Result<Self> {
+        // A path in a fresh directory, so the target starts from no file
+        let dir = tempfile::Builder::new()
+            .prefix("tinyetl_stdout_")
+            .tempdir()
+            .map_err(TinyEtlError::Io)?;
+        let path = dir.path().join(name);
+
+        let inner = create_target_with_options(&path.to_string_lossy(), options)?;
+        Ok(Self {
+            inner,
+            spool: Some(Spool {
+                path,
+                output,
+                _dir: dir,
+            }),
+        })
+    }
+}
+
+#[async_trait]
+impl Target for StdoutTarget {
+    async fn connect(&mut self) -> Result<()> {
+        self.inner.connect().await
+    }
+
+    async fn create_table(&mut self, table_name: &str, schema: &Schema) -> Result<()> {
+        self.inner.create_table(table_name, schema).await
+    }
+
+    async fn write_batch(&mut self, rows: &[Row]) -> Result<usize> {
+        self.inner.write_batch(rows).await
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize().await?;
+        if let Some(spool) = self.spool.as_mut() {
+            if spool.path.exists() {
+                std::io::copy(&mut std::fs::File::open(&spool.path)?, &mut spool.output)?;
+            }
+            spool.output.flush()?;
+        }
+        Ok(())
+    }
+
+    async fn exists(&self, _table_name: &str) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn truncate(&mut self, _table_name: &str) -> Result<()> {
+        // Standard output never holds earlier data
+        Ok(())
+    }
+
+    fn supports_append(&self) -> bool {
+        self.inner.supports_append()
+    }
+
+    fn set_table_ddl(&mut self, ddl: TableDdl) {
+        self.inner.set_table_ddl(ddl)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Column, DataType, Value};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_is_stdin_and_stdout() {
+        assert!(is_stdin("-"));
+        assert!(is_stdin("stdin://"));
+        assert!(!is_stdin("stdout://"));
+        assert!(!is_stdin("data.csv"));
+        assert!(is_stdout("-"));
+        assert!(is_stdout("stdout://"));
+        assert!(!is_stdout("./-.csv"));
+    }
+
+    #[test]
+    fn test_validate_stdio_url() {
+        let protocol = StdioProtocol::new();
+        assert!(protocol
+            .validate_url(&Url::parse("stdin://").unwrap())
+            .is_ok());
+        assert!(protocol
+            .validate_url(&Url::parse("stdout://").unwrap())
+            .is_ok());
+        assert!(protocol
+            .validate_url(&Url::parse("file:///data.csv").unwrap())
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_type_required() {
+        let protocol = StdioProtocol::new();
+        let url = Url::parse("stdout://").unwrap();
+        let result = protocol.create_target(&url, &HashMap::new()).await;
+        assert!(result.is_err());
+        if let Err(err) = result {
+            assert!(err.to_string().contains("--target-type"));
+        }
+
+        let url = Url::parse("stdin://").unwrap();
+        let result = protocol
+            .create_target_with_type(&url, Some("csv"), &HashMap::new())
+            .await;
+        assert!(result.is_err());
+    }
+
+    /// Output that tests can read back after the target takes a copy
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    async fn write_stdout(extension: &str) -> (StdoutTarget, Vec<u8>) {
+        let output = Captured::default();
+        let mut target =
+            StdoutTarget::with_output(extension, &HashMap::new(), output.clone()).unwrap();
+        assert!(!target.exists("stdout").await.unwrap());
+
+        let schema = Schema {
+            columns: vec![Column {
+                name: "id".to_string(),
+                data_type: DataType::Integer,
+                nullable: false,
+            }],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+        let row: Row = [("id".to_string(), Value::Integer(1))]
+            .into_iter()
+            .collect();
+        target.connect().await.unwrap();
+        target.create_table("stdout", &schema).await.unwrap();
+        assert_eq!(target.write_batch(&[row]).await.unwrap(), 1);
+        target.finalize().await.unwrap();
+
+        let bytes = output.0.lock().unwrap().clone();
+        (target, bytes)
+    }
+
+    #[tokio::test]
+    async fn test_stdout_target_streams_text_formats() {
+        let (target, output) = write_stdout("jsonl").await;
+        assert!(target.spool.is_none());
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"id\":1}\n");
+
+        let (target, output) = write_stdout("csv").await;
+        assert!(target.spool.is_none());
+        assert_eq!(String::from_utf8(output).unwrap(), "id\n1\n");
+
+        let (_, output) = write_stdout("json.gz").await;
+        assert_eq!(&output[..2], &[0x1f, 0x8b]);
+    }
+
+    #[tokio::test]
+    async fn test_stdout_target_spools_parquet() {
+        let (target, output) = write_stdout("parquet").await;
+        assert!(target.spool.is_some());
+        assert!(output.starts_with(b"PAR1"));
+        assert!(output.ends_with(b"PAR1"));
+    }
+}
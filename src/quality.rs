@@ -0,0 +1,624 @@
+use mlua::{Function, Lua, Value as LuaValue};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use tracing::{info, warn};
+
+use crate::{
+    date_parser::DateParser,
+    schema::{Row, RuleBound, SchemaFile, SchemaFileColumn, Severity, Value},
+    transformer::row_to_lua_table,
+    Result, TinyEtlError,
+};
+
+/// Number of violations logged per rule before they are only counted
+const MAX_LOGGED_VIOLATIONS: usize = 10;
+
+/// Violation count for a single rule, reported at the end of the run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSummary {
+    /// `column.rule` for column rules, `check.name` for row checks
+    pub rule: String,
+    pub severity: Severity,
+    pub violations: usize,
+}
+
+struct Violation {
+    rule: String,
+    severity: Severity,
+    message: String,
+}
+
+/// Evaluates the data-quality rules declared in a schema file and keeps the
+/// state that has to survive across batches (unique values, violation counts)
+pub struct QualityChecker {
+    schema_file: SchemaFile,
+    lua: Lua,
+    seen_values: HashMap<String, HashSet<String>>,
+    summary: Vec<RuleSummary>,
+    summary_index: HashMap<String, usize>,
+    rows_checked: usize,
+    rows_rejected: usize,
+}
+
+impl QualityChecker {
+    pub fn new(schema_file: &SchemaFile) -> Result<Self> {
+        let mut checker = Self {
+            schema_file: schema_file.clone(),
+            lua: Lua::new(),
+            seen_values: HashMap::new(),
+            summary: Vec::new(),
+            summary_index: HashMap::new(),
+            rows_checked: 0,
+            rows_rejected: 0,
+        };
+
+        // Register every rule up front so the summary also lists clean rules
+        for column in schema_file.columns.iter().filter(|c| !c.drop) {
+            let default_severity = column.severity.unwrap_or_default();
+            let rules = [
+                ("min", column.min.as_ref().map(|r| r.severity())),
+                ("max", column.max.as_ref().map(|r| r.severity())),
+                (
+                    "allowed_values",
+                    column.allowed_values.as_ref().map(|r| r.severity()),
+                ),
+                (
+                    "min_length",
+                    column.min_length.as_ref().map(|r| r.severity()),
+                ),
+                (
+                    "max_length",
+                    column.max_length.as_ref().map(|r| r.severity()),
+                ),
+                (
+                    "unique",
+                    column
+                        .unique
+                        .as_ref()
+                        .filter(|r| *r.value())
+                        .map(|r| r.severity()),
+                ),
+            ];
+            for (rule, severity) in rules {
+                if let Some(severity) = severity {
+                    checker.register(
+                        format!("{}.{}", column.output_name(), rule),
+                        severity.unwrap_or(default_severity),
+                    );
+                }
+            }
+        }
+
+        for (i, check) in schema_file.checks.iter().enumerate() {
+            checker.compile_check(i, &check.expression).map_err(|e| {
+                TinyEtlError::Configuration(format!(
+                    "Invalid expression for check '{}': {}",
+                    check.name, e
+                ))
+            })?;
+            checker.register(format!("check.{}", check.name), check.severity);
+        }
+
+        Ok(checker)
+    }
+
+    /// Check if the schema file declares any data-quality rules
+    pub fn is_enabled(&self) -> bool {
+        !self.summary.is_empty()
+    }
+
+    fn register(&mut self, rule: String, severity: Severity) {
+        self.summary_index.insert(rule.clone(), self.summary.len());
+        self.summary.push(RuleSummary {
+            rule,
+            severity,
+            violations: 0,
+        });
+    }
+
+    /// Compile a row check into a global Lua function. Columns resolve as bare
+    /// names (falling back to Lua globals such as `math`) and through `row`.
+    fn compile_check(&self, index: usize, expression: &str) -> Result<()> {
+        let lua_code = format!(
+            "function __tinyetl_check_{index}(row)\n\
+             \x20 local _ENV = setmetatable({{ row = row }}, {{ __index = function(_, key)\n\
+             \x20   local value = row[key]\n\
+             \x20   if value ~= nil then return value end\n\
+             \x20   return _G[key]\n\
+             \x20 end }})\n\
+             \x20 return ({expression})\n\
+             end\n"
+        );
+        self.lua.load(&lua_code).exec()?;
+        Ok(())
+    }
+
+    /// Check a batch, returning the rows that were not rejected.
+    /// A `fail` violation aborts with a data validation error.
+    pub fn check_batch(&mut self, rows: Vec<Row>) -> Result<Vec<Row>> {
+        let mut kept = Vec::with_capacity(rows.len());
+        for row in rows {
+            if self.check_row(&row)? {
+                kept.push(row);
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Check a single row. Returns false if the row should be rejected.
+    pub fn check_row(&mut self, row: &Row) -> Result<bool> {
+        self.rows_checked += 1;
+
+        let mut violations = Vec::new();
+        let mut unique_values = Vec::new();
+        for column in self.schema_file.columns.iter().filter(|c| !c.drop) {
+            if let Some(value) = row.get(column.output_name()) {
+                Self::column_violations(
+                    column,
+                    value,
+                    &self.seen_values,
+                    &mut unique_values,
+                    &mut violations,
+                );
+            }
+        }
+        self.check_violations(row, &mut violations)?;
+
+        let mut rejected = false;
+        let mut failure = None;
+        for violation in violations {
+            let count = match self.summary_index.get(&violation.rule) {
+                Some(&i) => {
+                    self.summary[i].violations += 1;
+                    self.summary[i].violations
+                }
+                None => 1,
+            };
+
+            match violation.severity {
+                Severity::Warn | Severity::Reject if count <= MAX_LOGGED_VIOLATIONS => {
+                    warn!(
+                        "Data quality {} ({}): {}",
+                        violation.severity, violation.rule, violation.message
+                    );
+                }
+                Severity::Warn | Severity::Reject => {}
+                Severity::Fail => {
+                    failure.get_or_insert(violation.message);
+                }
+            }
+            rejected |= violation.severity == Severity::Reject;
+        }
+
+        if let Some(message) = failure {
+            return Err(TinyEtlError::DataValidation(message));
+        }
+        if rejected {
+            self.rows_rejected += 1;
+        } else {
+            // Only rows that are kept claim their values, so a rejected
+            // duplicate doesn't make the next valid occurrence fail
+            for (name, value) in unique_values {
+                self.seen_values.entry(name).or_default().insert(value);
+            }
+        }
+        Ok(!rejected)
+    }
+
+    /// Collect the violations of a column's rules. Values of `unique`
+    /// columns not seen before are added to `unique_values`.
+    fn column_violations(
+        column: &SchemaFileColumn,
+        value: &Value,
+        seen_values: &HashMap<String, HashSet<String>>,
+        unique_values: &mut Vec<(String, String)>,
+        violations: &mut Vec<Violation>,
+    ) {
+        // Null handling belongs to `nullable`/`default`, not to quality rules
+        if matches!(value, Value::Null) {
+            return;
+        }
+
+        let name = column.output_name();
+        let default_severity = column.severity.unwrap_or_default();
+        let display = value.to_string_for_arrow().unwrap_or_default();
+        let mut push = |rule: &str, severity: Option<Severity>, message: String| {
+            violations.push(Violation {
+                rule: format!("{}.{}", name, rule),
+                severity: severity.unwrap_or(default_severity),
+                message,
+            });
+        };
+
+        if let Some(rule) = &column.min {
+            if Self::compare_to_bound(value, rule.value()) == Some(Ordering::Less) {
+                push(
+                    "min",
+                    rule.severity(),
+                    format!(
+                        "Column '{}' value '{}' is below min {}",
+                        name,
+                        display,
+                        rule.value()
+                    ),
+                );
+            }
+        }
+
+        if let Some(rule) = &column.max {
+            if Self::compare_to_bound(value, rule.value()) == Some(Ordering::Greater) {
+                push(
+                    "max",
+                    rule.severity(),
+                    format!(
+                        "Column '{}' value '{}' is above max {}",
+                        name,
+                        display,
+                        rule.value()
+                    ),
+                );
+            }
+        }
+
+        if let Some(rule) = &column.allowed_values {
+            if !rule
+                .value()
+                .iter()
+                .any(|allowed| Self::matches_allowed(value, allowed))
+            {
+                push(
+                    "allowed_values",
+                    rule.severity(),
+                    format!(
+                        "Column '{}' value '{}' is not an allowed value",
+                        name, display
+                    ),
+                );
+            }
+        }
+
+        let length = display.chars().count();
+        if let Some(rule) = &column.min_length {
+            if length < *rule.value() {
+                push(
+                    "min_length",
+                    rule.severity(),
+                    format!(
+                        "Column '{}' value '{}' is shorter than {} characters",
+                        name,
+                        display,
+                        rule.value()
+                    ),
+                );
+            }
+        }
+
+        if let Some(rule) = &column.max_length {
+            if length > *rule.value() {
+                push(
+                    "max_length",
+                    rule.severity(),
+                    format!(
+                        "Column '{}' value '{}' is longer than {} characters",
+                        name,
+                        display,
+                        rule.value()
+                    ),
+                );
+            }
+        }
+
+        if let Some(rule) = column.unique.as_ref().filter(|r| *r.value()) {
+            let seen = seen_values
+                .get(name)
+                .is_some_and(|seen| seen.contains(&display));
+            if seen {
+                push(
+                    "unique",
+                    rule.severity(),
+                    format!("Column '{}' value '{}' is not unique", name, display),
+                );
+            } else {
+                unique_values.push((name.to_string(), display.clone()));
+            }
+        }
+    }
+
+    /// Evaluate the row-level checks. A check passes when its expression is truthy.
+    fn check_violations(&self, row: &Row, violations: &mut Vec<Violation>) -> Result<()> {
+        if self.schema_file.checks.is_empty() {
+            return Ok(());
+        }
+
+        let globals = self.lua.globals();
+        for (i, check) in self.schema_file.checks.iter().enumerate() {
+            let check_fn: Function = globals.get(format!("__tinyetl_check_{}", i))?;
+            let lua_row = row_to_lua_table(&self.lua, row)?;
+
+            let message = match check_fn.call::<_, LuaValue>(lua_row) {
+                Ok(LuaValue::Nil) | Ok(LuaValue::Boolean(false)) => {
+                    format!("Row failed check '{}': {}", check.name, check.expression)
+                }
+                Ok(_) => continue,
+                Err(e) => format!(
+                    "Row failed check '{}': {} ({})",
+                    check.name, check.expression, e
+                ),
+            };
+
+            violations.push(Violation {
+                rule: format!("check.{}", check.name),
+                severity: check.severity,
+                message,
+            });
+        }
+        Ok(())
+    }
+
+    fn compare_to_bound(value: &Value, bound: &RuleBound) -> Option<Ordering> {
+        match (value, bound) {
+            (Value::Integer(i), RuleBound::Number(n)) => Some(Decimal::from(*i).cmp(n)),
+            (Value::Decimal(d), RuleBound::Number(n)) => Some(d.cmp(n)),
+            (Value::Date(dt), RuleBound::Text(s)) => match DateParser::try_parse(s) {
+                Some(Value::Date(bound)) => Some(dt.cmp(&bound)),
+                _ => None,
+            },
+            (Value::String(s), RuleBound::Text(t)) => Some(s.as_str().cmp(t.as_str())),
+            _ => None,
+        }
+    }
+
+    fn matches_allowed(value: &Value, allowed: &serde_json::Value) -> bool {
+        match (value, allowed) {
+            (Value::String(s), serde_json::Value::String(a)) => s == a,
+            (Value::Integer(_) | Value::Decimal(_), serde_json::Value::Number(n)) => {
+                let expected = n.to_string().parse::<Decimal>().ok();
+                let actual = match value {
+                    Value::Integer(i) => Some(Decimal::from(*i)),
+                    Value::Decimal(d) => Some(*d),
+                    _ => None,
+                };
+                expected.is_some() && expected == actual
+            }
+            (Value::Boolean(b), serde_json::Value::Bool(a)) => b == a,
+            // Fall back to comparing text, e.g. numeric codes listed as strings
+            (other, serde_json::Value::String(a)) => {
+                other.to_string_for_arrow().as_deref() == Some(a.as_str())
+            }
+            _ => false,
+        }
+    }
+
+    /// Per-rule violation counts in declaration order
+    pub fn summary(&self) -> &[RuleSummary] {
+        &self.summary
+    }
+
+    pub fn rows_rejected(&self) -> usize {
+        self.rows_rejected
+    }
+
+    /// Log the end-of-run data quality summary
+    pub fn log_summary(&self) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        info!(
+            "→ Data quality: {} rows checked, {} rejected",
+            self.rows_checked, self.rows_rejected
+        );
+        for rule in &self.summary {
+            if rule.violations > 0 {
+                warn!(
+                    "  {} [{}]: {} violations",
+                    rule.rule, rule.severity, rule.violations
+                );
+            } else {
+                info!("  {} [{}]: ok", rule.rule, rule.severity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker(yaml: &str) -> QualityChecker {
+        let schema_file: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+        schema_file.validate().unwrap();
+        QualityChecker::new(&schema_file).unwrap()
+    }
+
+    fn row(values: &[(&str, Value)]) -> Row {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_range_and_enum_rules() {
+        let mut checker = checker(
+            r#"
+columns:
+  - name: age
+    type: integer
+    nullable: true
+    min: 0
+    max: { value: 130, severity: warn }
+  - name: status
+    type: string
+    nullable: true
+    allowed_values: [active, inactive]
+    severity: reject
+"#,
+        );
+        assert!(checker.is_enabled());
+
+        let ok = row(&[
+            ("age", Value::Integer(30)),
+            ("status", Value::String("active".to_string())),
+        ]);
+        assert!(checker.check_row(&ok).unwrap());
+
+        // Warn keeps the row
+        let old = row(&[
+            ("age", Value::Integer(140)),
+            ("status", Value::String("active".to_string())),
+        ]);
+        assert!(checker.check_row(&old).unwrap());
+
+        // Reject drops the row
+        let unknown = row(&[
+            ("age", Value::Integer(30)),
+            ("status", Value::String("deleted".to_string())),
+        ]);
+        assert!(!checker.check_row(&unknown).unwrap());
+
+        // Fail (the default severity) aborts
+        let negative = row(&[
+            ("age", Value::Integer(-1)),
+            ("status", Value::String("active".to_string())),
+        ]);
+        assert!(checker.check_row(&negative).is_err());
+
+        let counts: Vec<_> = checker
+            .summary()
+            .iter()
+            .map(|r| (r.rule.as_str(), r.violations))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![("age.min", 1), ("age.max", 1), ("status.allowed_values", 1)]
+        );
+        assert_eq!(checker.rows_rejected(), 1);
+    }
+
+    #[test]
+    fn test_unique_across_batches_and_lengths() {
+        let mut checker = checker(
+            r#"
+columns:
+  - name: code
+    type: string
+    nullable: true
+    unique: { value: true, severity: reject }
+    min_length: { value: 2, severity: reject }
+    max_length: { value: 4, severity: reject }
+"#,
+        );
+
+        let batch1 = vec![
+            row(&[("code", Value::String("AB".to_string()))]),
+            row(&[("code", Value::String("A".to_string()))]),
+        ];
+        let batch2 = vec![
+            row(&[("code", Value::String("AB".to_string()))]),
+            row(&[("code", Value::String("ABCDE".to_string()))]),
+            row(&[("code", Value::String("ABC".to_string()))]),
+        ];
+
+        assert_eq!(checker.check_batch(batch1).unwrap().len(), 1);
+        assert_eq!(checker.check_batch(batch2).unwrap().len(), 1);
+        assert_eq!(checker.rows_rejected(), 3);
+    }
+
+    #[test]
+    fn test_unique_ignores_rejected_rows() {
+        let mut checker = checker(
+            r#"
+columns:
+  - name: code
+    type: string
+    nullable: true
+    unique: { value: true, severity: reject }
+  - name: qty
+    type: integer
+    nullable: true
+    min: { value: 0, severity: reject }
+"#,
+        );
+
+        // The first A is rejected for its quantity, so the next A is its first occurrence
+        let rows = vec![
+            row(&[
+                ("code", Value::String("A".to_string())),
+                ("qty", Value::Integer(-1)),
+            ]),
+            row(&[
+                ("code", Value::String("A".to_string())),
+                ("qty", Value::Integer(1)),
+            ]),
+            row(&[
+                ("code", Value::String("A".to_string())),
+                ("qty", Value::Integer(2)),
+            ]),
+        ];
+        let kept = checker.check_batch(rows).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0]["qty"], Value::Integer(1));
+        assert_eq!(checker.rows_rejected(), 2);
+    }
+
+    #[test]
+    fn test_row_level_checks() {
+        let mut checker = checker(
+            r#"
+columns:
+  - name: start_date
+    type: date
+    nullable: true
+  - name: end_date
+    type: date
+    nullable: true
+checks:
+  - name: valid_period
+    expression: "end_date >= start_date"
+    severity: reject
+"#,
+        );
+
+        let start = DateParser::try_parse("2024-01-01").unwrap();
+        let end = DateParser::try_parse("2024-02-01").unwrap();
+
+        let valid = row(&[("start_date", start.clone()), ("end_date", end.clone())]);
+        let reversed = row(&[("start_date", end), ("end_date", start)]);
+
+        assert!(checker.check_row(&valid).unwrap());
+        assert!(!checker.check_row(&reversed).unwrap());
+        assert_eq!(checker.summary()[0].rule, "check.valid_period");
+        assert_eq!(checker.summary()[0].violations, 1);
+    }
+
+    #[test]
+    fn test_invalid_check_expression() {
+        let schema_file: SchemaFile = serde_yaml::from_str(
+            r#"
+columns: []
+checks:
+  - name: broken
+    expression: "a >= "
+"#,
+        )
+        .unwrap();
+        assert!(QualityChecker::new(&schema_file).is_err());
+    }
+
+    #[test]
+    fn test_bound_type_validation() {
+        let schema_file: SchemaFile = serde_yaml::from_str(
+            r#"
+columns:
+  - name: signup
+    type: date
+    nullable: true
+    min: 5
+"#,
+        )
+        .unwrap();
+        assert!(schema_file.validate().is_err());
+    }
+}
@@ -60,19 +60,681 @@ impl DataType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Reference to a source column, either by header name or by zero-based position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SourceColumnRef {
+    Position(usize),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaFileColumn {
     pub name: String,
     #[serde(rename = "type")]
     pub data_type: String,
     pub nullable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default: Option<String>,
+    /// Source column to read the value from (defaults to `name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceColumnRef>,
+    /// Name of the column in the target (defaults to `name`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename: Option<String>,
+    /// Parsing format: strftime for dates, locale or sample for numbers, `true/false` tokens for booleans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Trim surrounding whitespace from string values before casting
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trim: bool,
+    /// Remove the column from the output entirely
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub drop: bool,
+    /// Fixed-width sources: first character of the field, counting from 1
+    /// (defaults to right after the previous field)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<usize>,
+    /// Fixed-width sources: length of the field in characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<usize>,
+    /// Fixed-width sources: last character of the field, counting from 1, instead of `width`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<usize>,
+    /// Fixed-width sources: COBOL picture such as `9(7)V99`, giving the width and implied decimals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    /// Smallest allowed value (number, date or string depending on the column type)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<QualityRule<RuleBound>>,
+    /// Largest allowed value (number, date or string depending on the column type)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<QualityRule<RuleBound>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<QualityRule<Vec<serde_json::Value>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<QualityRule<usize>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<QualityRule<usize>>,
+    /// Values must not repeat across the whole run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unique: Option<QualityRule<bool>>,
+    /// Default severity for this column's rules that don't set their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Part of the target table's primary key (in column order)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary_key: bool,
+    /// SQL expression used as the column's DEFAULT in the target table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql_default: Option<String>,
+    /// Column comment in the target table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Physical SQL type for the target column (e.g. `VARCHAR(64)`), overriding the connector's mapping
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// What happens to a row that violates a data-quality rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Log the violation and keep the row
+    Warn,
+    /// Drop the row and keep going
+    Reject,
+    /// Abort the run
+    #[default]
+    Fail,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warn => write!(f, "warn"),
+            Severity::Reject => write!(f, "reject"),
+            Severity::Fail => write!(f, "fail"),
+        }
+    }
+}
+
+/// A rule value, written either bare (`min: 0`) or with its own severity
+/// (`min: { value: 0, severity: warn }`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QualityRule<T> {
+    WithSeverity { value: T, severity: Severity },
+    Value(T),
+}
+
+impl<T> QualityRule<T> {
+    pub fn value(&self) -> &T {
+        match self {
+            QualityRule::WithSeverity { value, .. } | QualityRule::Value(value) => value,
+        }
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        match self {
+            QualityRule::WithSeverity { severity, .. } => Some(*severity),
+            QualityRule::Value(_) => None,
+        }
+    }
+}
+
+/// Bound for `min`/`max` rules: numbers for numeric columns, text for dates and strings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleBound {
+    Number(Decimal),
+    Text(String),
+}
+
+impl std::fmt::Display for RuleBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleBound::Number(n) => write!(f, "{}", n),
+            RuleBound::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// Row-level check written as a Lua expression over the row's columns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowCheck {
+    pub name: String,
+    /// e.g. `end_date >= start_date`; columns are available by name or as `row.<name>`
+    pub expression: String,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+impl SchemaFileColumn {
+    /// Name the column is written under in the target
+    pub fn output_name(&self) -> &str {
+        self.rename.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Name of the source column the value is read from
+    fn source_name(&self) -> Result<&str> {
+        match &self.source {
+            None => Ok(&self.name),
+            Some(SourceColumnRef::Name(name)) => Ok(name),
+            Some(SourceColumnRef::Position(pos)) => {
+                Err(crate::TinyEtlError::DataValidation(format!(
+                    "Source position {} for column '{}' was not resolved against the source columns",
+                    pos, self.name
+                )))
+            }
+        }
+    }
+
+    /// Declared type as a [`DataType`]
+    pub fn schema_data_type(&self) -> DataType {
+        match self.data_type.to_lowercase().as_str() {
+            "integer" => DataType::Integer,
+            "decimal" => DataType::Decimal,
+            "boolean" => DataType::Boolean,
+            "date" => DataType::Date,
+            "datetime" => DataType::DateTime,
+            "json" => DataType::Json,
+            _ => DataType::String, // Already validated, so this shouldn't happen
+        }
+    }
+
+    /// Check that `min`/`max` bounds can be compared with the column's type
+    fn validate_quality_rules(&self) -> Result<()> {
+        for bound in [&self.min, &self.max].into_iter().flatten() {
+            let valid = match (self.schema_data_type(), bound.value()) {
+                (DataType::Integer | DataType::Decimal, RuleBound::Number(_)) => true,
+                (DataType::Date | DataType::DateTime, RuleBound::Text(s)) => {
+                    crate::date_parser::DateParser::try_parse(s).is_some()
+                }
+                (DataType::String, RuleBound::Text(_)) => true,
+                _ => false,
+            };
+            if !valid {
+                return Err(crate::TinyEtlError::Configuration(format!(
+                    "Invalid min/max bound '{}' for {} column '{}'",
+                    bound.value(),
+                    self.data_type,
+                    self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Trim and cast a raw source value into the declared type using the column's format
+    fn cast_value(&self, value: Value) -> Result<Value> {
+        let value = match value {
+            Value::String(s) if self.trim => {
+                let trimmed = s.trim();
+                if trimmed.is_empty() {
+                    Value::Null
+                } else {
+                    Value::String(trimmed.to_string())
+                }
+            }
+            other => other,
+        };
+
+        let cast_error = |raw: &str| {
+            crate::TinyEtlError::DataValidation(match &self.format {
+                Some(format) => format!(
+                    "Column '{}' value '{}' cannot be parsed as {} with format '{}'",
+                    self.name, raw, self.data_type, format
+                ),
+                None => format!(
+                    "Column '{}' value '{}' cannot be parsed as {}",
+                    self.name, raw, self.data_type
+                ),
+            })
+        };
+
+        match (self.schema_data_type(), value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (DataType::String, Value::String(s)) => Ok(Value::String(s)),
+            (DataType::String, Value::Date(dt)) => Ok(Value::String(match &self.format {
+                Some(format) => dt.format(format).to_string(),
+                None => dt.to_rfc3339(),
+            })),
+            (DataType::String, other) => Ok(other
+                .to_string_for_arrow()
+                .map(Value::String)
+                .unwrap_or(Value::Null)),
+            (DataType::Integer, Value::String(s)) => {
+                let number = NumberFormat::parse(self.format.as_deref()).normalize(&s);
+                number
+                    .parse::<i64>()
+                    .map(Value::Integer)
+                    .map_err(|_| cast_error(&s))
+            }
+            (DataType::Integer, Value::Decimal(d)) if d.fract().is_zero() => i64::try_from(d)
+                .map(Value::Integer)
+                .or(Ok(Value::Decimal(d))),
+            (DataType::Decimal, Value::String(s)) => {
+                let number = NumberFormat::parse(self.format.as_deref()).normalize(&s);
+                number
+                    .parse::<Decimal>()
+                    .or_else(|_| Decimal::from_scientific(&number))
+                    .map(Value::Decimal)
+                    .map_err(|_| cast_error(&s))
+            }
+            (DataType::Decimal, Value::Integer(i)) => Ok(Value::Decimal(Decimal::from(i))),
+            (DataType::Boolean, Value::String(s)) => {
+                Self::parse_boolean(&s, self.format.as_deref()).ok_or_else(|| cast_error(&s))
+            }
+            (DataType::Boolean, Value::Integer(i)) if i == 0 || i == 1 => {
+                Ok(Value::Boolean(i == 1))
+            }
+            (DataType::Date, Value::String(s)) | (DataType::DateTime, Value::String(s)) => {
+                let parsed = match &self.format {
+                    Some(format) => Self::parse_date_with_format(&s, format),
+                    None => crate::date_parser::DateParser::try_parse(&s),
+                };
+                parsed.ok_or_else(|| cast_error(&s))
+            }
+            (DataType::Json, Value::String(s)) => {
+                // Try to parse the string as JSON
+                match serde_json::from_str::<serde_json::Value>(&s) {
+                    Ok(json_val) => Ok(Value::Json(json_val)),
+                    Err(e) => Err(crate::TinyEtlError::DataValidation(format!(
+                        "Column '{}' contains invalid JSON: {}",
+                        self.name, e
+                    ))),
+                }
+            }
+            (_, other) => Ok(other),
+        }
+    }
+
+    /// Parse a boolean using `true_tokens/false_tokens` (alternatives separated by `|`),
+    /// falling back to the common true/false, 1/0 and yes/no spellings
+    fn parse_boolean(raw: &str, format: Option<&str>) -> Option<Value> {
+        let (true_tokens, false_tokens) = match format.and_then(|f| f.split_once('/')) {
+            Some((t, f)) => (
+                t.split('|').map(str::trim).collect::<Vec<_>>(),
+                f.split('|').map(str::trim).collect::<Vec<_>>(),
+            ),
+            None => (vec!["true", "1", "yes"], vec!["false", "0", "no"]),
+        };
+
+        let raw = raw.trim();
+        if true_tokens.iter().any(|t| t.eq_ignore_ascii_case(raw)) {
+            Some(Value::Boolean(true))
+        } else if false_tokens.iter().any(|t| t.eq_ignore_ascii_case(raw)) {
+            Some(Value::Boolean(false))
+        } else {
+            None
+        }
+    }
+
+    /// Parse a date or datetime with an explicit strftime format
+    fn parse_date_with_format(raw: &str, format: &str) -> Option<Value> {
+        let raw = raw.trim();
+        if let Ok(dt) = chrono::DateTime::parse_from_str(raw, format) {
+            return Some(Value::Date(dt.with_timezone(&Utc)));
+        }
+        if let Ok(ndt) = chrono::NaiveDateTime::parse_from_str(raw, format) {
+            return Some(Value::Date(ndt.and_utc()));
+        }
+        chrono::NaiveDate::parse_from_str(raw, format)
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|ndt| Value::Date(ndt.and_utc()))
+    }
+}
+
+/// Decimal and thousands separators used to read locale-formatted numbers
+#[derive(Debug, Clone, PartialEq)]
+struct NumberFormat {
+    decimal: char,
+    thousands: Vec<char>,
+}
+
+impl NumberFormat {
+    /// Build from a locale (`en_US`, `de`, `fr_FR`, ...) or a sample pattern (`#,##0.00`, `#.##0,00`)
+    fn parse(format: Option<&str>) -> Self {
+        let format = match format {
+            Some(f) => f.trim(),
+            None => {
+                return Self {
+                    decimal: '.',
+                    thousands: Vec::new(),
+                }
+            }
+        };
+
+        let language = format
+            .split(['_', '-'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let region = format.split(['_', '-']).nth(1).map(|r| r.to_uppercase());
+
+        if region.as_deref() == Some("CH") || language == "ch" {
+            return Self {
+                decimal: '.',
+                thousands: vec!['\'', '\u{2019}'],
+            };
+        }
+        match language.as_str() {
+            "en" | "us" | "ja" | "zh" | "ko" | "he" | "th" => {
+                return Self {
+                    decimal: '.',
+                    thousands: vec![','],
+                }
+            }
+            "de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "eu" => {
+                return Self {
+                    decimal: ',',
+                    thousands: vec!['.'],
+                }
+            }
+            "fr" | "ru" | "sv" | "pl" | "cs" | "fi" | "nb" | "no" | "hu" | "sk" | "uk" => {
+                return Self {
+                    decimal: ',',
+                    thousands: vec![' ', '\u{a0}', '\u{202f}'],
+                }
+            }
+            _ => {}
+        }
+
+        // Sample pattern: separators are any characters that are not digit placeholders
+        let separators: Vec<(usize, char)> = format
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_ascii_digit() && *c != '#')
+            .collect();
+
+        let (last_pos, last_sep) = match separators.last() {
+            Some(&(pos, sep)) => (pos, sep),
+            None => {
+                return Self {
+                    decimal: '.',
+                    thousands: Vec::new(),
+                }
+            }
+        };
+
+        let distinct_other = separators
+            .iter()
+            .find(|(_, c)| *c != last_sep)
+            .map(|(_, c)| *c);
+        let occurrences = separators.iter().filter(|(_, c)| *c == last_sep).count();
+        let digits_after = format.chars().count() - last_pos - 1;
+
+        match distinct_other {
+            Some(thousands) => Self {
+                decimal: last_sep,
+                thousands: vec![thousands],
+            },
+            None if occurrences == 1 && digits_after != 3 => Self {
+                decimal: last_sep,
+                thousands: Vec::new(),
+            },
+            None => Self {
+                decimal: if last_sep == '.' { ',' } else { '.' },
+                thousands: vec![last_sep],
+            },
+        }
+    }
+
+    /// Strip currency symbols and thousands separators and normalize the decimal point
+    fn normalize(&self, raw: &str) -> String {
+        raw.trim()
+            .chars()
+            .filter(|c| {
+                !self.thousands.contains(c) && !matches!(c, '$' | '\u{20ac}' | '\u{a3}' | '\u{a5}')
+            })
+            .map(|c| if c == self.decimal { '.' } else { c })
+            .filter(|c| !c.is_whitespace())
+            .collect()
+    }
+}
+
+/// Output format for generated schema files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaFileFormat {
+    #[default]
+    Yaml,
+    Json,
+}
+
+impl std::str::FromStr for SchemaFileFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yaml" | "yml" => Ok(SchemaFileFormat::Yaml),
+            "json" => Ok(SchemaFileFormat::Json),
+            _ => Err("Invalid schema format. Valid values: yaml, json"),
+        }
+    }
+}
+
+/// Suggest a regex that every sampled value matches, for common shapes of
+/// identifiers (UUIDs, emails, ISO dates, numeric and letter codes, phone numbers)
+fn suggest_pattern(values: &[&str]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+
+    let all_match = |pattern: &str| {
+        let re = Regex::new(pattern).expect("built-in pattern is valid");
+        values.iter().all(|v| re.is_match(v))
+    };
+    let same_length = values.iter().all(|v| v.len() == values[0].len());
+
+    let candidates = [
+        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+        r"^[\w.+-]+@[\w-]+(\.[\w-]+)+$",
+        r"^\d{4}-\d{2}-\d{2}$",
+    ];
+    if let Some(pattern) = candidates.iter().find(|p| all_match(p)) {
+        return Some(pattern.to_string());
+    }
+
+    // Codes stored as text, e.g. zip codes with leading zeros or ISO country codes
+    if all_match(r"^\d+$") {
+        return Some(if same_length {
+            format!(r"^\d{{{}}}$", values[0].len())
+        } else {
+            r"^\d+$".to_string()
+        });
+    }
+    if same_length && values[0].len() <= 3 && all_match(r"^[A-Z]+$") {
+        return Some(format!("^[A-Z]{{{}}}$", values[0].len()));
+    }
+
+    let phone = r"^\+?[0-9][0-9 ().-]{6,}$";
+    all_match(phone).then(|| phone.to_string())
+}
+
+/// Keys, constraints, indexes, defaults and comments for a SQL target table,
+/// declared in the schema file's `table` section or in the target options
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableDdl {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub primary_key: Vec<String>,
+    /// Unique constraints, each a list of columns
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unique: Vec<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub indexes: Vec<IndexDefinition>,
+    /// Table comment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// Create secondary indexes in `finalize`, after the rows are loaded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub indexes_after_load: bool,
+    /// SQL types to use instead of the connector's defaults, keyed by schema
+    /// type (`string`, `integer`, `decimal`, `boolean`, `date`, `datetime`, `json`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub type_mapping: HashMap<String, String>,
+    /// Column SQL types, collected from `sql_type` on the columns
+    #[serde(skip)]
+    pub column_types: HashMap<String, String>,
+    /// Column DEFAULT expressions, collected from `sql_default` on the columns
+    #[serde(skip)]
+    pub column_defaults: HashMap<String, String>,
+    /// Column comments, collected from `comment` on the columns
+    #[serde(skip)]
+    pub column_comments: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexDefinition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub columns: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unique: bool,
+}
+
+impl IndexDefinition {
+    /// The declared name, or `idx_<table>_<columns>`
+    pub fn name_for(&self, table_name: &str) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("idx_{}_{}", table_name, self.columns.join("_")))
+    }
+}
+
+const SCHEMA_TYPE_NAMES: [&str; 7] = [
+    "string", "integer", "decimal", "boolean", "date", "datetime", "json",
+];
+
+impl TableDdl {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The SQL type for a target column: its `sql_type` override, then the
+    /// `type_mapping` entry for its data type. `None` keeps the connector's mapping.
+    pub fn sql_type(&self, column: &Column) -> Option<&str> {
+        if let Some(sql_type) = self.column_types.get(&column.name) {
+            return Some(sql_type);
+        }
+        let type_name = match column.data_type {
+            DataType::String | DataType::Null => "string",
+            DataType::Integer => "integer",
+            DataType::Decimal => "decimal",
+            DataType::Boolean => "boolean",
+            DataType::Date => "date",
+            DataType::DateTime => "datetime",
+            DataType::Json => "json",
+        };
+        self.type_mapping
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(type_name))
+            .map(|(_, sql_type)| sql_type.as_str())
+    }
+
+    fn validate_type_mapping(&self) -> Result<()> {
+        for name in self.type_mapping.keys() {
+            if !SCHEMA_TYPE_NAMES.contains(&name.to_lowercase().as_str()) {
+                return Err(crate::TinyEtlError::Configuration(format!(
+                    "Invalid type '{}' in type_mapping: expected one of {}",
+                    name,
+                    SCHEMA_TYPE_NAMES.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply DDL settings from target options, which take precedence over the schema file:
+    /// `primary_key` (`a,b`), `unique` (`a;b,c`), `index.<name>` and `unique_index.<name>`
+    /// (`a,b`), `comment`, `comment.<column>`, `default.<column>`, `indexes_after_load`,
+    /// `type_mapping` (`string=NVARCHAR(MAX);decimal=NUMERIC(18,4)`) and `sql_type.<column>`.
+    /// Other keys are left to the connectors and protocols.
+    pub fn apply_options(&mut self, options: &HashMap<String, String>) -> Result<()> {
+        let columns = |value: &str| -> Vec<String> {
+            value
+                .split(',')
+                .map(|c| c.trim().to_string())
+                .filter(|c| !c.is_empty())
+                .collect()
+        };
+
+        // Sort for a deterministic index order
+        let mut keys: Vec<&String> = options.keys().collect();
+        keys.sort();
+
+        for key in keys {
+            let value = &options[key];
+            match key.split_once('.') {
+                None if key == "primary_key" => self.primary_key = columns(value),
+                None if key == "unique" => {
+                    self.unique.extend(
+                        value
+                            .split(';')
+                            .map(columns)
+                            .filter(|constraint| !constraint.is_empty()),
+                    );
+                }
+                None if key == "comment" => self.comment = Some(value.clone()),
+                None if key == "indexes_after_load" => {
+                    self.indexes_after_load = value.parse().map_err(|_| {
+                        crate::TinyEtlError::Configuration(format!(
+                            "Invalid value '{}' for option 'indexes_after_load': expected true or false",
+                            value
+                        ))
+                    })?;
+                }
+                Some((kind @ ("index" | "unique_index"), name)) => {
+                    self.indexes
+                        .retain(|index| index.name.as_deref() != Some(name));
+                    self.indexes.push(IndexDefinition {
+                        name: Some(name.to_string()),
+                        columns: columns(value),
+                        unique: kind == "unique_index",
+                    });
+                }
+                Some(("comment", column)) => {
+                    self.column_comments
+                        .insert(column.to_string(), value.clone());
+                }
+                Some(("default", column)) => {
+                    self.column_defaults
+                        .insert(column.to_string(), value.clone());
+                }
+                None if key == "type_mapping" => {
+                    // ';' separates entries because types like NUMERIC(18,4) contain commas
+                    for entry in value.split(';').filter(|e| !e.trim().is_empty()) {
+                        let (name, sql_type) = entry.split_once('=').ok_or_else(|| {
+                            crate::TinyEtlError::Configuration(format!(
+                                "Invalid type_mapping entry '{}': expected type=SQL_TYPE",
+                                entry.trim()
+                            ))
+                        })?;
+                        self.type_mapping
+                            .insert(name.trim().to_lowercase(), sql_type.trim().to_string());
+                    }
+                }
+                Some(("sql_type", column)) => {
+                    self.column_types.insert(column.to_string(), value.clone());
+                }
+                _ => {}
+            }
+        }
+        self.validate_type_mapping()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SchemaFile {
+    /// Keys, constraints and indexes for SQL targets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<TableDdl>,
     pub columns: Vec<SchemaFileColumn>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<RowCheck>,
 }
 
 impl SchemaFile {
@@ -110,44 +772,219 @@ impl SchemaFile {
                     ))
                 })?;
             }
+
+            // Boolean formats must name both the true and the false tokens
+            if let Some(format) = &column.format {
+                if column.data_type.eq_ignore_ascii_case("boolean") && !format.contains('/') {
+                    return Err(crate::TinyEtlError::Configuration(format!(
+                        "Invalid boolean format '{}' for column '{}': expected 'true_tokens/false_tokens'",
+                        format, column.name
+                    )));
+                }
+            }
+        }
+
+        for column in &self.columns {
+            column.validate_quality_rules()?;
+        }
+
+        // Output names must be unique once renames are applied
+        let mut output_names = std::collections::HashSet::new();
+        for column in self.columns.iter().filter(|c| !c.drop) {
+            if !output_names.insert(column.output_name()) {
+                return Err(crate::TinyEtlError::Configuration(format!(
+                    "Duplicate output column '{}' in schema file",
+                    column.output_name()
+                )));
+            }
+        }
+
+        if let Some(table) = &self.table {
+            if !table.primary_key.is_empty() && self.columns.iter().any(|c| c.primary_key) {
+                return Err(crate::TinyEtlError::Configuration(
+                    "Primary key is declared both in 'table' and on columns".to_string(),
+                ));
+            }
+
+            let key_columns = table
+                .primary_key
+                .iter()
+                .chain(table.unique.iter().flatten())
+                .chain(table.indexes.iter().flat_map(|index| &index.columns));
+            for name in key_columns {
+                if !output_names.contains(name.as_str()) {
+                    return Err(crate::TinyEtlError::Configuration(format!(
+                        "Table key or index refers to unknown column '{}'",
+                        name
+                    )));
+                }
+            }
+
+            table.validate_type_mapping()?;
+
+            if let Some(index) = table.indexes.iter().find(|index| index.columns.is_empty()) {
+                return Err(crate::TinyEtlError::Configuration(format!(
+                    "Index '{}' has no columns",
+                    index.name.as_deref().unwrap_or_default()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Table DDL from the `table` section merged with column-level
+    /// `primary_key`, `sql_default` and `comment` settings
+    pub fn table_ddl(&self) -> TableDdl {
+        let mut ddl = self.table.clone().unwrap_or_default();
+
+        for column in self.columns.iter().filter(|c| !c.drop) {
+            let name = column.output_name().to_string();
+            if column.primary_key {
+                ddl.primary_key.push(name.clone());
+            }
+            if let Some(default) = &column.sql_default {
+                ddl.column_defaults.insert(name.clone(), default.clone());
+            }
+            if let Some(comment) = &column.comment {
+                ddl.column_comments.insert(name.clone(), comment.clone());
+            }
+            if let Some(sql_type) = &column.sql_type {
+                ddl.column_types.insert(name, sql_type.clone());
+            }
+        }
+        ddl
+    }
+
+    /// Types for the source to parse columns as, keyed by source column name.
+    /// Columns with a `format` stay strings so the format is applied when casting.
+    pub fn source_schema(&self) -> Result<Schema> {
+        let columns = self
+            .columns
+            .iter()
+            .map(|col| {
+                Ok(Column {
+                    name: col.source_name()?.to_string(),
+                    data_type: if col.format.is_some() {
+                        DataType::String
+                    } else {
+                        col.schema_data_type()
+                    },
+                    nullable: true,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Schema {
+            columns,
+            estimated_rows: None,
+            primary_key_candidate: None,
+        })
+    }
+
+    /// Resolve positional `source` references to names using the source's column order
+    pub fn resolve_source_positions(&mut self, source_columns: &[String]) -> Result<()> {
+        for column in &mut self.columns {
+            if let Some(SourceColumnRef::Position(pos)) = column.source {
+                let name = source_columns.get(pos).ok_or_else(|| {
+                    crate::TinyEtlError::Configuration(format!(
+                        "Column '{}' maps from source position {}, but the source only has {} columns",
+                        column.name,
+                        pos,
+                        source_columns.len()
+                    ))
+                })?;
+                column.source = Some(SourceColumnRef::Name(name.clone()));
+            }
         }
         Ok(())
     }
 
     pub fn to_schema(&self) -> Result<Schema> {
         let columns = self
+            .columns
+            .iter()
+            .filter(|col| !col.drop)
+            .map(|col| Column {
+                name: col.output_name().to_string(),
+                data_type: col.schema_data_type(),
+                nullable: col.nullable,
+            })
+            .collect();
+
+        Ok(Schema {
+            columns,
+            estimated_rows: None,
+            primary_key_candidate: None,
+        })
+    }
+
+    /// Build a ready-to-edit schema file from a catalog or inferred schema.
+    /// Sample rows are used to suggest `pattern`s for string columns.
+    pub fn from_schema(schema: &Schema, sample: &[Row]) -> Self {
+        let columns = schema
             .columns
             .iter()
             .map(|col| {
-                let data_type = match col.data_type.to_lowercase().as_str() {
-                    "string" => DataType::String,
-                    "integer" => DataType::Integer,
-                    "decimal" => DataType::Decimal,
-                    "boolean" => DataType::Boolean,
-                    "date" => DataType::Date,
-                    "datetime" => DataType::DateTime,
-                    "json" => DataType::Json,
-                    _ => DataType::String, // Already validated, so this shouldn't happen
+                let pattern = match col.data_type {
+                    DataType::String => {
+                        let values: Vec<&str> = sample
+                            .iter()
+                            .filter_map(|row| match row.get(&col.name) {
+                                Some(Value::String(s)) if !s.is_empty() => Some(s.as_str()),
+                                _ => None,
+                            })
+                            .collect();
+                        suggest_pattern(&values)
+                    }
+                    _ => None,
                 };
 
-                Column {
+                let data_type = match col.data_type {
+                    DataType::String | DataType::Null => "String",
+                    DataType::Integer => "Integer",
+                    DataType::Decimal => "Decimal",
+                    DataType::Boolean => "Boolean",
+                    DataType::Date => "Date",
+                    DataType::DateTime => "DateTime",
+                    DataType::Json => "Json",
+                };
+
+                SchemaFileColumn {
                     name: col.name.clone(),
-                    data_type,
+                    data_type: data_type.to_string(),
                     nullable: col.nullable,
+                    pattern,
+                    ..Default::default()
                 }
             })
             .collect();
 
-        Ok(Schema {
+        Self {
             columns,
-            estimated_rows: None,
-            primary_key_candidate: None,
-        })
+            ..Default::default()
+        }
+    }
+
+    /// Serialize the schema file as YAML or JSON
+    pub fn to_formatted_string(&self, format: SchemaFileFormat) -> Result<String> {
+        match format {
+            SchemaFileFormat::Yaml => serde_yaml::to_string(self).map_err(|e| {
+                crate::TinyEtlError::Configuration(format!(
+                    "Failed to serialize schema file: {}",
+                    e
+                ))
+            }),
+            SchemaFileFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+        }
     }
 
     pub fn validate_and_transform_row(&self, row: &mut Row) -> Result<()> {
-        for schema_col in &self.columns {
-            let value = row.get(&schema_col.name);
+        // Map, trim, cast and rename source values before validating them
+        self.map_source_columns(row)?;
+
+        for schema_col in self.columns.iter().filter(|c| !c.drop) {
+            let column_name = schema_col.output_name();
+            let value = row.get(column_name);
 
             // Check for required columns
             if !schema_col.nullable && (value.is_none() || matches!(value, Some(Value::Null))) {
@@ -155,44 +992,42 @@ impl SchemaFile {
                 if let Some(default_str) = &schema_col.default {
                     let default_value =
                         self.parse_default_value(default_str, &schema_col.data_type)?;
-                    row.insert(schema_col.name.clone(), default_value);
+                    row.insert(column_name.to_string(), default_value);
                 } else {
                     return Err(crate::TinyEtlError::DataValidation(format!(
                         "Required column '{}' is missing or null",
-                        schema_col.name
+                        column_name
                     )));
                 }
             }
 
-            // Transform and validate existing values
-            if let Some(val) = row.get(&schema_col.name).cloned() {
-                // Convert string to JSON if schema expects JSON type
-                let transformed_val = if schema_col.data_type.to_lowercase() == "json"
-                    && matches!(val, Value::String(_))
-                {
-                    if let Value::String(s) = val {
-                        // Try to parse the string as JSON
-                        match serde_json::from_str::<serde_json::Value>(&s) {
-                            Ok(json_val) => Value::Json(json_val),
-                            Err(e) => {
-                                return Err(crate::TinyEtlError::DataValidation(format!(
-                                    "Column '{}' contains invalid JSON: {}",
-                                    schema_col.name, e
-                                )));
-                            }
-                        }
-                    } else {
-                        val
-                    }
-                } else {
-                    val
-                };
+            // Validate existing values
+            if let Some(val) = row.get(column_name) {
+                self.validate_column_value(val, schema_col)?;
+            }
+        }
+        Ok(())
+    }
 
-                // Update the row with the transformed value
-                row.insert(schema_col.name.clone(), transformed_val.clone());
+    /// Move each schema column's source value to its output name, applying trim and format
+    /// casting. Dropped columns are removed; columns not mentioned in the schema pass through.
+    fn map_source_columns(&self, row: &mut Row) -> Result<()> {
+        let mut mapped = Vec::with_capacity(self.columns.len());
+        for schema_col in self.columns.iter().filter(|c| !c.drop) {
+            let value = match row.get(schema_col.source_name()?) {
+                Some(value) => Some(schema_col.cast_value(value.clone())?),
+                None => None,
+            };
+            mapped.push((schema_col.output_name(), value));
+        }
 
-                // Validate the transformed value
-                self.validate_column_value(&transformed_val, schema_col)?;
+        for schema_col in &self.columns {
+            row.remove(schema_col.source_name()?);
+        }
+
+        for (name, value) in mapped {
+            if let Some(value) = value {
+                row.insert(name.to_string(), value);
             }
         }
         Ok(())
@@ -221,7 +1056,11 @@ impl SchemaFile {
             }
         };
 
-        let actual_type = SchemaInferer::infer_type(value);
+        // Date-only columns still hold full timestamps in memory
+        let actual_type = match SchemaInferer::infer_type(value) {
+            DataType::DateTime if expected_type == DataType::Date => DataType::Date,
+            other => other,
+        };
         if actual_type != expected_type && actual_type != DataType::Null {
             return Err(crate::TinyEtlError::DataValidation(format!(
                 "Column '{}' expected type {:?}, got {:?}",
@@ -790,7 +1629,9 @@ mod tests {
                 nullable: false,
                 pattern: None,
                 default: Some(r#"{"enabled": true, "count": 0}"#.to_string()),
+                ..Default::default()
             }],
+            ..Default::default()
         };
 
         let default = schema_file
@@ -805,4 +1646,415 @@ mod tests {
             _ => panic!("Expected JSON value"),
         }
     }
+
+    #[test]
+    fn test_schema_file_source_mapping_rename_and_drop() {
+        let yaml = r#"
+columns:
+  - name: customer_id
+    type: integer
+    nullable: false
+    source: "Cust No"
+  - name: full_name
+    type: string
+    nullable: true
+    rename: name
+    trim: true
+  - name: internal_flag
+    type: string
+    nullable: true
+    drop: true
+"#;
+        let schema_file: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+        schema_file.validate().unwrap();
+
+        let mut row = HashMap::new();
+        row.insert("Cust No".to_string(), Value::String("42".to_string()));
+        row.insert(
+            "full_name".to_string(),
+            Value::String("  Alice Smith ".to_string()),
+        );
+        row.insert("internal_flag".to_string(), Value::String("x".to_string()));
+        row.insert("untouched".to_string(), Value::Integer(7));
+
+        schema_file.validate_and_transform_row(&mut row).unwrap();
+
+        assert_eq!(row.get("customer_id"), Some(&Value::Integer(42)));
+        assert_eq!(
+            row.get("name"),
+            Some(&Value::String("Alice Smith".to_string()))
+        );
+        assert!(!row.contains_key("Cust No"));
+        assert!(!row.contains_key("full_name"));
+        assert!(!row.contains_key("internal_flag"));
+        assert_eq!(row.get("untouched"), Some(&Value::Integer(7)));
+
+        let schema = schema_file.to_schema().unwrap();
+        let names: Vec<_> = schema.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["customer_id", "name"]);
+    }
+
+    #[test]
+    fn test_schema_file_positional_source() {
+        let yaml = r#"
+columns:
+  - name: amount
+    type: decimal
+    nullable: true
+    source: 1
+"#;
+        let mut schema_file: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+
+        let mut row = HashMap::new();
+        row.insert("col_b".to_string(), Value::String("12.50".to_string()));
+        assert!(schema_file.validate_and_transform_row(&mut row).is_err());
+
+        schema_file
+            .resolve_source_positions(&["col_a".to_string(), "col_b".to_string()])
+            .unwrap();
+        // Sources parse by source column name
+        let source_schema = schema_file.source_schema().unwrap();
+        assert_eq!(source_schema.columns[0].name, "col_b");
+        assert_eq!(source_schema.columns[0].data_type, DataType::Decimal);
+        schema_file.validate_and_transform_row(&mut row).unwrap();
+        assert_eq!(
+            row.get("amount"),
+            Some(&Value::Decimal(Decimal::new(1250, 2)))
+        );
+
+        assert!(schema_file
+            .resolve_source_positions(&["only".to_string()])
+            .is_ok()); // already resolved to a name
+        let mut unresolved: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+        assert!(unresolved.source_schema().is_err());
+        assert!(unresolved
+            .resolve_source_positions(&["only".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn test_schema_file_casting_formats() {
+        let column = |data_type: &str, format: &str| SchemaFileColumn {
+            name: "value".to_string(),
+            data_type: data_type.to_string(),
+            nullable: true,
+            format: Some(format.to_string()),
+            ..Default::default()
+        };
+
+        // Locale and sample-pattern numbers
+        assert_eq!(
+            column("decimal", "de_DE")
+                .cast_value(Value::String("1.234,56".to_string()))
+                .unwrap(),
+            Value::Decimal(Decimal::new(123456, 2))
+        );
+        assert_eq!(
+            column("decimal", "#,##0.00")
+                .cast_value(Value::String("$1,234.56".to_string()))
+                .unwrap(),
+            Value::Decimal(Decimal::new(123456, 2))
+        );
+        assert_eq!(
+            column("integer", "fr_FR")
+                .cast_value(Value::String("1 234 567".to_string()))
+                .unwrap(),
+            Value::Integer(1_234_567)
+        );
+
+        // Boolean tokens
+        let flag = column("boolean", "Y|yes/N|no");
+        assert_eq!(
+            flag.cast_value(Value::String("Y".to_string())).unwrap(),
+            Value::Boolean(true)
+        );
+        assert_eq!(
+            flag.cast_value(Value::String("no".to_string())).unwrap(),
+            Value::Boolean(false)
+        );
+        assert!(flag.cast_value(Value::String("maybe".to_string())).is_err());
+
+        // strftime dates
+        let date = column("date", "%d/%m/%Y")
+            .cast_value(Value::String("03/02/2024".to_string()))
+            .unwrap();
+        match date {
+            Value::Date(dt) => assert_eq!(dt.format("%Y-%m-%d").to_string(), "2024-02-03"),
+            _ => panic!("Expected date value"),
+        }
+    }
+
+    #[test]
+    fn test_schema_file_rejects_duplicate_output_names() {
+        let schema_file = SchemaFile {
+            columns: vec![
+                SchemaFileColumn {
+                    name: "a".to_string(),
+                    data_type: "string".to_string(),
+                    nullable: true,
+                    rename: Some("b".to_string()),
+                    ..Default::default()
+                },
+                SchemaFileColumn {
+                    name: "b".to_string(),
+                    data_type: "string".to_string(),
+                    nullable: true,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(schema_file.validate().is_err());
+    }
+
+    #[test]
+    fn test_schema_file_table_ddl() {
+        let yaml = r#"
+table:
+  unique:
+    - [email]
+  indexes:
+    - columns: [last_name, first_name]
+  comment: Customer master
+columns:
+  - name: customer_id
+    rename: id
+    type: integer
+    nullable: false
+    primary_key: true
+  - name: email
+    type: string
+    nullable: false
+    comment: Contact address
+  - name: last_name
+    type: string
+    nullable: true
+  - name: first_name
+    type: string
+    nullable: true
+  - name: created_at
+    type: datetime
+    nullable: true
+    sql_default: CURRENT_TIMESTAMP
+"#;
+        let schema_file: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+        schema_file.validate().unwrap();
+
+        let mut ddl = schema_file.table_ddl();
+        assert_eq!(ddl.primary_key, vec!["id"]);
+        assert_eq!(ddl.unique, vec![vec!["email".to_string()]]);
+        assert_eq!(
+            ddl.indexes[0].name_for("customers"),
+            "idx_customers_last_name_first_name"
+        );
+        assert_eq!(ddl.column_defaults["created_at"], "CURRENT_TIMESTAMP");
+        assert_eq!(ddl.column_comments["email"], "Contact address");
+        assert!(!ddl.indexes_after_load);
+
+        // Target options override and extend the schema file
+        let options: HashMap<String, String> = [
+            ("primary_key", "id, email"),
+            ("index.by_created", "created_at"),
+            ("unique_index.by_name", "last_name,first_name"),
+            ("default.email", "''"),
+            ("indexes_after_load", "true"),
+            ("delimiter", ";"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        ddl.apply_options(&options).unwrap();
+        assert_eq!(ddl.primary_key, vec!["id", "email"]);
+        assert_eq!(ddl.indexes.len(), 3);
+        assert_eq!(ddl.indexes[1].name_for("customers"), "by_created");
+        assert!(ddl.indexes[2].unique);
+        assert_eq!(ddl.column_defaults["email"], "''");
+        assert!(ddl.indexes_after_load);
+
+        let invalid: HashMap<String, String> =
+            [("indexes_after_load".to_string(), "later".to_string())].into();
+        assert!(ddl.apply_options(&invalid).is_err());
+        assert!(TableDdl::default().is_empty());
+    }
+
+    #[test]
+    fn test_table_ddl_sql_types() {
+        let yaml = r#"
+table:
+  type_mapping:
+    string: NVARCHAR(MAX)
+columns:
+  - name: code
+    type: string
+    nullable: false
+    sql_type: VARCHAR(64)
+  - name: note
+    type: string
+    nullable: true
+  - name: amount
+    type: decimal
+    nullable: true
+"#;
+        let schema_file: SchemaFile = serde_yaml::from_str(yaml).unwrap();
+        schema_file.validate().unwrap();
+        let schema = schema_file.to_schema().unwrap();
+
+        let mut ddl = schema_file.table_ddl();
+        assert_eq!(ddl.sql_type(&schema.columns[0]), Some("VARCHAR(64)"));
+        assert_eq!(ddl.sql_type(&schema.columns[1]), Some("NVARCHAR(MAX)"));
+        assert_eq!(ddl.sql_type(&schema.columns[2]), None);
+
+        let options: HashMap<String, String> = [
+            ("type_mapping", "Decimal=NUMERIC(18,4); string=TEXT"),
+            ("sql_type.note", "JSONB"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        ddl.apply_options(&options).unwrap();
+        assert_eq!(ddl.sql_type(&schema.columns[1]), Some("JSONB"));
+        assert_eq!(ddl.sql_type(&schema.columns[2]), Some("NUMERIC(18,4)"));
+
+        let invalid: HashMap<String, String> =
+            [("type_mapping".to_string(), "text=CLOB".to_string())].into();
+        assert!(ddl.apply_options(&invalid).is_err());
+        let invalid: HashMap<String, String> =
+            [("type_mapping".to_string(), "CLOB".to_string())].into();
+        assert!(ddl.apply_options(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_schema_file_rejects_invalid_table_ddl() {
+        let column = |name: &str, primary_key: bool| SchemaFileColumn {
+            name: name.to_string(),
+            data_type: "string".to_string(),
+            nullable: true,
+            primary_key,
+            ..Default::default()
+        };
+
+        // Primary key declared twice
+        let schema_file = SchemaFile {
+            table: Some(TableDdl {
+                primary_key: vec!["a".to_string()],
+                ..Default::default()
+            }),
+            columns: vec![column("a", true)],
+            ..Default::default()
+        };
+        assert!(schema_file.validate().is_err());
+
+        // Index on an unknown column
+        let schema_file = SchemaFile {
+            table: Some(TableDdl {
+                indexes: vec![IndexDefinition {
+                    name: None,
+                    columns: vec!["missing".to_string()],
+                    unique: false,
+                }],
+                ..Default::default()
+            }),
+            columns: vec![column("a", false)],
+            ..Default::default()
+        };
+        assert!(schema_file.validate().is_err());
+
+        // Index without columns
+        let schema_file = SchemaFile {
+            table: Some(TableDdl {
+                indexes: vec![IndexDefinition {
+                    name: Some("empty".to_string()),
+                    columns: vec![],
+                    unique: false,
+                }],
+                ..Default::default()
+            }),
+            columns: vec![column("a", false)],
+            ..Default::default()
+        };
+        assert!(schema_file.validate().is_err());
+    }
+
+    #[test]
+    fn test_schema_file_from_schema() {
+        let schema = Schema {
+            columns: vec![
+                Column {
+                    name: "id".to_string(),
+                    data_type: DataType::Integer,
+                    nullable: false,
+                },
+                Column {
+                    name: "email".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                },
+                Column {
+                    name: "zip".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                },
+                Column {
+                    name: "notes".to_string(),
+                    data_type: DataType::String,
+                    nullable: true,
+                },
+            ],
+            estimated_rows: None,
+            primary_key_candidate: None,
+        };
+
+        let sample: Vec<Row> = [("a@example.com", "01234"), ("b@example.org", "98765")]
+            .iter()
+            .map(|(email, zip)| {
+                let mut row = Row::new();
+                row.insert("id".to_string(), Value::Integer(1));
+                row.insert("email".to_string(), Value::String(email.to_string()));
+                row.insert("zip".to_string(), Value::String(zip.to_string()));
+                row.insert("notes".to_string(), Value::String("free text".to_string()));
+                row
+            })
+            .collect();
+
+        let schema_file = SchemaFile::from_schema(&schema, &sample);
+        schema_file.validate().unwrap();
+
+        assert_eq!(schema_file.columns[0].data_type, "Integer");
+        assert!(!schema_file.columns[0].nullable);
+        assert_eq!(
+            schema_file.columns[1].pattern.as_deref(),
+            Some(r"^[\w.+-]+@[\w-]+(\.[\w-]+)+$")
+        );
+        assert_eq!(schema_file.columns[2].pattern.as_deref(), Some(r"^\d{5}$"));
+        assert_eq!(schema_file.columns[3].pattern, None);
+
+        // The generated file round-trips through both formats
+        let yaml = schema_file
+            .to_formatted_string(SchemaFileFormat::Yaml)
+            .unwrap();
+        assert!(!yaml.contains("default"));
+        let reparsed: SchemaFile = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(reparsed.columns.len(), 4);
+
+        let json = schema_file
+            .to_formatted_string(SchemaFileFormat::Json)
+            .unwrap();
+        let reparsed: SchemaFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(reparsed.columns[2].pattern.as_deref(), Some(r"^\d{5}$"));
+    }
+
+    #[test]
+    fn test_suggest_pattern() {
+        assert_eq!(suggest_pattern(&[]), None);
+        assert_eq!(
+            suggest_pattern(&["US", "DE", "FR"]).as_deref(),
+            Some("^[A-Z]{2}$")
+        );
+        assert_eq!(
+            suggest_pattern(&["7", "42", "1234"]).as_deref(),
+            Some(r"^\d+$")
+        );
+        assert!(suggest_pattern(&["+1 (555) 123-4567", "555-987-6543"]).is_some());
+        assert_eq!(suggest_pattern(&["hello", "world"]), None);
+    }
 }
@@ -5,6 +5,7 @@ use tracing::{info, warn};
 use crate::{
     config::Config,
     connectors::{Source, Target},
+    quality::QualityChecker,
     schema::{Schema, SchemaFile},
     transformer::Transformer,
     Result,
@@ -35,13 +36,24 @@ impl TransferEngine {
         target.connect().await?;
 
         // Step 2: Infer or load schema
+        let mut schema_file = None;
         let schema = if let Some(schema_file_path) = &config.schema_file {
             info!("→ Loading schema from file: {}", schema_file_path);
-            let schema_file = SchemaFile::from_file(schema_file_path)?;
+            let mut file = SchemaFile::from_file(schema_file_path)?;
             // Even when using a schema file, we need to initialize the source
-            // (e.g., for pagination state in SQLite)
-            let _ = source.infer_schema(1).await?;
-            schema_file.to_schema()?
+            // (e.g., for pagination state in SQLite). The source column order is
+            // also what positional `source` mappings refer to.
+            let source_schema = source.infer_schema(1).await?;
+            let source_columns: Vec<String> = source_schema
+                .columns
+                .iter()
+                .map(|c| c.name.clone())
+                .collect();
+            file.resolve_source_positions(&source_columns)?;
+            source.set_schema(&file.source_schema()?);
+            let schema = file.to_schema()?;
+            schema_file = Some(file);
+            schema
         } else {
             info!("→ Inferring schema from source...");
             source.infer_schema(1000).await?
@@ -65,7 +77,12 @@ impl TransferEngine {
 
             // Read a small sample to infer the transformed schema
             source.reset().await?;
-            let sample_batch = source.read_batch(1).await?;
+            let mut sample_batch = source.read_batch(1).await?;
+            if let Some(ref schema_file) = schema_file {
+                for row in &mut sample_batch {
+                    schema_file.validate_and_transform_row(row)?;
+                }
+            }
 
             if !sample_batch.is_empty() {
                 transformer.transform_batch(&sample_batch)?;
@@ -88,6 +105,17 @@ impl TransferEngine {
         // Step 6: Extract table name from target
         let table_name = Self::extract_table_name(&config.target);
 
+        // Keys, constraints, indexes, defaults and comments from the schema
+        // file, overridden by target options
+        let mut table_ddl = schema_file
+            .as_ref()
+            .map(|file| file.table_ddl())
+            .unwrap_or_default();
+        table_ddl.apply_options(&config.target_options)?;
+        if !table_ddl.is_empty() {
+            target.set_table_ddl(table_ddl);
+        }
+
         // Step 7: Handle append-first logic or truncate mode
         let table_exists = target.exists(&table_name).await?;
 
@@ -121,30 +149,42 @@ impl TransferEngine {
         let estimated_rows = source.estimated_row_count().await?.unwrap_or(0);
         info!("→ Copying {} rows", estimated_rows);
 
-        let progress_bar = if estimated_rows > 0 {
-            let pb = ProgressBar::new(estimated_rows as u64);
+        // File sources report progress by bytes consumed, which stays accurate
+        // when the row count is only an estimate
+        let progress_bytes = source.progress_bytes().filter(|(_, total)| *total > 0);
+        let progress_bar = match progress_bytes {
+            Some((_, total_bytes)) => Some((
+                "{bar:40.cyan/blue} {percent}% ({bytes}/{total_bytes}) {msg}",
+                total_bytes,
+            )),
+            None if estimated_rows > 0 => Some((
+                "{bar:40.cyan/blue} {percent}% ({pos}/{len}) {msg}",
+                estimated_rows as u64,
+            )),
+            None => None,
+        }
+        .map(|(template, len)| {
+            let pb = ProgressBar::new(len);
             let style = ProgressStyle::default_bar()
-                .template("{bar:40.cyan/blue} {percent}% ({pos}/{len}) {msg}")
+                .template(template)
                 .expect("Failed to create progress bar template")
                 .progress_chars("█▇▆▅▄▃▂▁  ");
             pb.set_style(style);
-            Some(pb)
-        } else {
-            None
-        };
-
-        // Load schema file for validation if provided
-        let schema_file = if let Some(schema_file_path) = &config.schema_file {
-            Some(SchemaFile::from_file(schema_file_path)?)
-        } else {
-            None
-        };
+            pb
+        });
 
         let mut total_rows = 0;
         let mut batches_processed = 0;
 
         source.reset().await?;
 
+        // Data-quality rules are evaluated after schema validation and casting
+        let mut quality_checker = match schema_file {
+            Some(ref schema_file) => Some(QualityChecker::new(schema_file)?),
+            None => None,
+        }
+        .filter(|checker| checker.is_enabled());
+
         while source.has_more() {
             let mut batch = source.read_batch(config.batch_size).await?;
             if batch.is_empty() {
@@ -158,6 +198,16 @@ impl TransferEngine {
                 }
             }
 
+            if let Some(ref mut checker) = quality_checker {
+                batch = match checker.check_batch(batch) {
+                    Ok(kept) => kept,
+                    Err(e) => {
+                        checker.log_summary();
+                        return Err(e);
+                    }
+                };
+            }
+
             // Apply transformations
             let processed_batch = if transformer.is_enabled() {
                 transformer.transform_batch(&batch)?
@@ -170,7 +220,12 @@ impl TransferEngine {
             batches_processed += 1;
 
             if let Some(ref pb) = progress_bar {
-                pb.set_position(total_rows as u64);
+                match source.progress_bytes() {
+                    Some((bytes_read, _)) if progress_bytes.is_some() => {
+                        pb.set_position(bytes_read)
+                    }
+                    _ => pb.set_position(total_rows as u64),
+                }
                 pb.set_message(format!(
                     "{}k rows/sec",
                     (total_rows as f64 / start_time.elapsed().as_secs_f64() / 1000.0) as u64
@@ -185,6 +240,10 @@ impl TransferEngine {
         // Step 10: Finalize
         target.finalize().await?;
 
+        if let Some(ref checker) = quality_checker {
+            checker.log_summary();
+        }
+
         let total_time = start_time.elapsed();
         let rows_per_second = total_rows as f64 / total_time.as_secs_f64();
 
@@ -358,34 +358,7 @@ impl Transformer {
 
     /// Convert a Row to a Lua table
     fn row_to_lua_table(&self, row: &Row) -> Result<Table<'_>> {
-        let table = self
-            .lua
-            .create_table()
-            .map_err(|e| TinyEtlError::Transform(format!("Failed to create Lua table: {}", e)))?;
-
-        for (key, value) in row {
-            let lua_value = match value {
-                Value::String(s) => LuaValue::String(self.lua.create_string(s)?),
-                Value::Integer(i) => LuaValue::Integer(*i),
-                Value::Decimal(d) => {
-                    // Convert Decimal to f64 for Lua
-                    let f: f64 = (*d).try_into().unwrap_or(0.0);
-                    LuaValue::Number(f)
-                }
-                
-                Value::Boolean(b) => LuaValue::Boolean(*b),
-                Value::Date(dt) => LuaValue::String(self.lua.create_string(&dt.to_rfc3339())?),
-                Value::Json(j) => {
-                    // Convert JSON to Lua string representation
-                    let json_str = serde_json::to_string(j).unwrap_or_else(|_| "{}".to_string());
-                    LuaValue::String(self.lua.create_string(&json_str)?)
-                }
-                Value::Null => LuaValue::Nil,
-            };
-            table.set(key.as_str(), lua_value)?;
-        }
-
-        Ok(table)
+        row_to_lua_table(&self.lua, row)
     }
 
     /// Convert a Lua table back to a Row
@@ -507,6 +480,36 @@ impl Transformer {
     }
 }
 
+/// Convert a Row to a Lua table in the given Lua state
+pub(crate) fn row_to_lua_table<'lua>(lua: &'lua Lua, row: &Row) -> Result<Table<'lua>> {
+    let table = lua
+        .create_table()
+        .map_err(|e| TinyEtlError::Transform(format!("Failed to create Lua table: {}", e)))?;
+
+    for (key, value) in row {
+        let lua_value = match value {
+            Value::String(s) => LuaValue::String(lua.create_string(s)?),
+            Value::Integer(i) => LuaValue::Integer(*i),
+            Value::Decimal(d) => {
+                // Convert Decimal to f64 for Lua
+                let f: f64 = (*d).try_into().unwrap_or(0.0);
+                LuaValue::Number(f)
+            }
+            Value::Boolean(b) => LuaValue::Boolean(*b),
+            Value::Date(dt) => LuaValue::String(lua.create_string(&dt.to_rfc3339())?),
+            Value::Json(j) => {
+                // Convert JSON to Lua string representation
+                let json_str = serde_json::to_string(j).unwrap_or_else(|_| "{}".to_string());
+                LuaValue::String(lua.create_string(&json_str)?)
+            }
+            Value::Null => LuaValue::Nil,
+        };
+        table.set(key.as_str(), lua_value)?;
+    }
+
+    Ok(table)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
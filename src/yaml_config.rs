@@ -35,6 +35,8 @@ pub struct OptionsConfig {
     pub truncate: Option<bool>,
     pub transform: Option<TransformConfig>,
     pub source_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<String>,
 }
 
 impl YamlConfig {
@@ -70,6 +72,7 @@ impl YamlConfig {
                     other => Some(other),
                 },
                 source_type: config.source_type,
+                target_type: config.target_type,
             }),
         }
     }
@@ -120,6 +123,12 @@ impl YamlConfig {
             None
         };
 
+        let target_type = if let Some(ref ttype) = options.target_type {
+            Some(Self::substitute_env_vars(ttype)?)
+        } else {
+            None
+        };
+
         Ok(Config {
             source: source_uri,
             target: target_uri,
@@ -133,6 +142,7 @@ impl YamlConfig {
             truncate: options.truncate.unwrap_or(false),
             transform: transform_config,
             source_type,
+            target_type,
             source_secret_id: None, // Not used with config files - env vars are substituted directly
             dest_secret_id: None, // Not used with config files - env vars are substituted directly
             source_options,
@@ -225,6 +235,7 @@ mod tests {
                 truncate: Some(false),
                 transform: Some(TransformConfig::Script("transform_script".to_string())),
                 source_type: Some("csv".to_string()),
+                target_type: None,
             }),
         };
         let expected_yaml = r#"version: 1
@@ -367,6 +378,7 @@ hire_year = tonumber(string.sub(row.hire_date, 1, 4))
             truncate: false,
             transform: TransformConfig::None,
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -423,6 +435,7 @@ hire_year = tonumber(string.sub(row.hire_date, 1, 4))
             truncate: true,
             transform: TransformConfig::Inline("result = row.value * 2".to_string()),
             source_type: Some("csv".to_string()),
+            target_type: None,
             source_secret_id: None, // Not preserved through YAML
             dest_secret_id: None,   // Not preserved through YAML
             source_options: HashMap::new(),
@@ -483,6 +496,7 @@ hire_year = tonumber(string.sub(row.hire_date, 1, 4))
             truncate: false,
             transform: TransformConfig::File("transform.lua".to_string()),
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -521,6 +535,7 @@ discount = total * 0.1"#;
             truncate: false,
             transform: TransformConfig::Script(script.to_string()),
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -579,6 +594,7 @@ discount = total * 0.1"#;
             truncate: false,
             transform: TransformConfig::Inline("x = row.a + row.b".to_string()),
             source_type: Some("json".to_string()),
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -627,6 +643,7 @@ discount = total * 0.1"#;
                 "name = row['first-name'] .. ' ' .. row['last-name']".to_string(),
             ),
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),
@@ -660,6 +677,7 @@ discount = total * 0.1"#;
             truncate: false,
             transform: TransformConfig::None,
             source_type: None,
+            target_type: None,
             source_secret_id: None,
             dest_secret_id: None,
             source_options: HashMap::new(),